@@ -0,0 +1,176 @@
+use crate::parser::{
+    grammar::{
+        expressions::{
+            for_expr::ForExpr, if_else::IfElseExpr, statement::Statement, while_expr::WhileExpr,
+        },
+        function::FunctionDefinition,
+    },
+    position::Span,
+};
+
+use super::visitor::{walk_for, walk_function, walk_if_else, walk_while, Visitor};
+
+/// What a coverage probe counts
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProbeKind {
+    /// Calls of a function, the span is its signature
+    Function(String),
+    /// Executions of a statement, the span is its expression
+    Statement,
+    /// `if` conditions which were true, the span is the condition
+    TrueBranch,
+    /// `if` conditions which were false, whether there's an `else` branch or not
+    FalseBranch,
+    /// Iterations of a loop, the span is the condition or the iterated list
+    LoopBody,
+}
+
+/// A place in the code whose executions are counted, probes are ordered like the source
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Probe {
+    pub span: Span,
+    pub kind: ProbeKind,
+}
+
+impl Probe {
+    pub fn new(kind: ProbeKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+
+    /// Line of the source the probe is reported at
+    pub fn line(&self) -> usize {
+        self.span.start.row
+    }
+}
+
+/// Finds all places in functions whose executions are counted, without running the program
+#[derive(Default)]
+pub struct Prober {
+    probes: Vec<Probe>,
+    /// Expression being visited
+    span: Span,
+}
+
+impl Prober {
+    pub fn probe(&mut self, kind: ProbeKind, span: Span) {
+        self.probes.push(Probe::new(kind, span));
+    }
+
+    /// Returns the probes in the order of the source
+    pub fn finish(mut self) -> Vec<Probe> {
+        self.probes.sort();
+        self.probes
+    }
+}
+
+impl Visitor for Prober {
+    type Output = ();
+
+    fn span(&mut self) -> &mut Span {
+        &mut self.span
+    }
+
+    fn visit_function(&mut self, function: &FunctionDefinition) {
+        self.probe(
+            ProbeKind::Function(function.identifier.clone()),
+            function.span,
+        );
+        walk_function(self, function);
+    }
+
+    /// Adds a probe counting the executions of each statement of a block
+    fn visit_statements(&mut self, statements: &[Statement]) {
+        for statement in statements {
+            if let Statement::Expression(expression) = statement {
+                self.probe(ProbeKind::Statement, expression.span);
+                self.visit_expression(expression);
+            }
+        }
+    }
+
+    fn visit_for(&mut self, e: &ForExpr) {
+        self.probe(ProbeKind::LoopBody, e.provider.span);
+        walk_for(self, e);
+    }
+
+    fn visit_if_else(&mut self, e: &IfElseExpr) {
+        self.probe(ProbeKind::TrueBranch, e.condition.span);
+        self.probe(ProbeKind::FalseBranch, e.condition.span);
+        walk_if_else(self, e);
+    }
+
+    fn visit_while(&mut self, e: &WhileExpr) {
+        self.probe(ProbeKind::LoopBody, e.condition.span);
+        walk_while(self, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        grammar::program::Program,
+        parse_source,
+        position::{Position, Span},
+    };
+
+    use super::{Probe, ProbeKind};
+
+    fn probes(source: &str) -> Vec<(ProbeKind, usize)> {
+        let program: Program = parse_source(source, |p| p.parse()).complete().unwrap();
+        program
+            .probes()
+            .into_iter()
+            .map(|probe| (probe.kind.clone(), probe.line()))
+            .collect()
+    }
+
+    #[test]
+    fn statements_and_branches() {
+        let source = "fn main() {
+    let i: int = 0;
+    while i < 3 {
+        if i == 1 { print(i); };
+        i = i + 1;
+    };
+    for x in [1] {};
+}";
+        assert_eq!(
+            probes(source),
+            vec![
+                (ProbeKind::Function("main".to_owned()), 1),
+                (ProbeKind::Statement, 2),
+                (ProbeKind::Statement, 3),
+                (ProbeKind::LoopBody, 3),
+                (ProbeKind::Statement, 4),
+                (ProbeKind::TrueBranch, 4),
+                (ProbeKind::FalseBranch, 4),
+                (ProbeKind::Statement, 4),
+                (ProbeKind::Statement, 5),
+                (ProbeKind::Statement, 7),
+                (ProbeKind::LoopBody, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_in_expressions() {
+        let source = "fn f() -> int {\n    let x: int = if true { 2 } else { 3 };\n    x\n}";
+        let program: Program = parse_source(source, |p| p.parse()).complete().unwrap();
+        let at = |kind, start, stop| {
+            Probe::new(
+                kind,
+                Span::new(Position::new(2, start), Position::new(2, stop)),
+            )
+        };
+        assert_eq!(
+            program.probes()[1..6],
+            [
+                at(ProbeKind::Statement, 5, 42),
+                at(ProbeKind::TrueBranch, 21, 25),
+                at(ProbeKind::FalseBranch, 21, 25),
+                at(ProbeKind::Statement, 28, 29),
+                at(ProbeKind::Statement, 39, 40),
+            ]
+        );
+    }
+}
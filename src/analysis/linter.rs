@@ -0,0 +1,685 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+};
+
+use crate::{
+    interpreter::{context::Context, ExecutionError, ExecutionErrorVariant},
+    locale,
+    parser::{
+        grammar::{
+            expressions::{
+                assignment::AssignmentExpr, declaration::DeclarationExpr, for_expr::ForExpr,
+                function_call::FunctionCallExpr, identifier::IdentifierExpr, if_else::IfElseExpr,
+                return_expr::ReturnExpr, statement::Statement, while_expr::WhileExpr, Evaluable,
+                Expression, ExpressionKind,
+            },
+            function::{FunctionDefinition, Parameter},
+            DataType, Value,
+        },
+        position::{Position, Span},
+    },
+};
+
+use super::visitor::{
+    walk_assignment, walk_declaration, walk_for, walk_function, walk_function_call, walk_return,
+    Visitor,
+};
+
+/// Rules of the linter, each can be turned on and off separately
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lint {
+    UnusedVariables,
+    UnusedFunctions,
+    UnreachableCode,
+    InfiniteLoops,
+    ShadowedVariables,
+    ConstantConditions,
+    UnusedLoopResults,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 7] = [
+        Lint::UnusedVariables,
+        Lint::UnusedFunctions,
+        Lint::UnreachableCode,
+        Lint::InfiniteLoops,
+        Lint::ShadowedVariables,
+        Lint::ConstantConditions,
+        Lint::UnusedLoopResults,
+    ];
+
+    /// Name used to turn the lint on and off
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariables => "unused_variables",
+            Lint::UnusedFunctions => "unused_functions",
+            Lint::UnreachableCode => "unreachable_code",
+            Lint::InfiniteLoops => "infinite_loops",
+            Lint::ShadowedVariables => "shadowed_variables",
+            Lint::ConstantConditions => "constant_conditions",
+            Lint::UnusedLoopResults => "unused_loop_results",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Set of enabled lints
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Lints(u8);
+
+impl Lints {
+    pub fn all() -> Self {
+        Lint::ALL.into_iter().fold(Self::default(), Self::with)
+    }
+
+    pub fn with(self, lint: Lint) -> Self {
+        Self(self.0 | (1 << lint as u8))
+    }
+
+    pub fn without(self, lint: Lint) -> Self {
+        Self(self.0 & !(1 << lint as u8))
+    }
+
+    pub fn contains(&self, lint: Lint) -> bool {
+        self.0 & (1 << lint as u8) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// Code which runs, but most likely not the way it was meant to
+#[derive(Debug, PartialEq, Eq)]
+pub enum LintWarningVariant {
+    UnusedVariable(String),
+    UnusedParameter(String),
+    UnusedFunction(String),
+    UnreachableCode,
+    InfiniteLoop,
+    ShadowedVariable(String),
+    ConstantCondition(bool),
+    UnusedLoopResult,
+}
+
+impl Display for LintWarningVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            LintWarningVariant::UnusedVariable(id)
+            | LintWarningVariant::UnusedParameter(id)
+            | LintWarningVariant::UnusedFunction(id)
+            | LintWarningVariant::ShadowedVariable(id) => locale::message(self.code(), &[id]),
+            LintWarningVariant::ConstantCondition(value) => locale::message(self.code(), &[value]),
+            _ => locale::message(self.code(), &[]),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl LintWarningVariant {
+    /// Stable code identifying the problem, explained by `--explain`
+    pub fn code(&self) -> &'static str {
+        match self {
+            LintWarningVariant::UnusedVariable(_) => "A0001",
+            LintWarningVariant::UnusedParameter(_) => "A0002",
+            LintWarningVariant::UnusedFunction(_) => "A0003",
+            LintWarningVariant::UnreachableCode => "A0004",
+            LintWarningVariant::InfiniteLoop => "A0005",
+            LintWarningVariant::ShadowedVariable(_) => "A0006",
+            LintWarningVariant::ConstantCondition(_) => "A0007",
+            LintWarningVariant::UnusedLoopResult => "A0008",
+        }
+    }
+
+    /// Suggestion on how to fix the problem
+    pub fn hint(&self) -> &'static str {
+        locale::hint(self.code())
+    }
+
+    /// Lint which reports the warning
+    pub fn lint(&self) -> Lint {
+        match self {
+            LintWarningVariant::UnusedVariable(_) | LintWarningVariant::UnusedParameter(_) => {
+                Lint::UnusedVariables
+            }
+            LintWarningVariant::UnusedFunction(_) => Lint::UnusedFunctions,
+            LintWarningVariant::UnreachableCode => Lint::UnreachableCode,
+            LintWarningVariant::InfiniteLoop => Lint::InfiniteLoops,
+            LintWarningVariant::ShadowedVariable(_) => Lint::ShadowedVariables,
+            LintWarningVariant::ConstantCondition(_) => Lint::ConstantConditions,
+            LintWarningVariant::UnusedLoopResult => Lint::UnusedLoopResults,
+        }
+    }
+}
+
+/// Suspicious code found without running the program
+#[derive(Debug, PartialEq, Eq)]
+pub struct LintWarning {
+    pub warning: LintWarningVariant,
+    pub start: Position,
+    pub stop: Position,
+}
+
+impl Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Lint warning from {} to {}: {}",
+            self.start, self.stop, self.warning
+        ))
+    }
+}
+
+impl Error for LintWarning {}
+
+/// Variable declared in a scope, along with whether its value was ever read
+struct Variable {
+    id: String,
+    span: Span,
+    parameter: bool,
+    used: bool,
+}
+
+/// Context of constant expressions, which can't use variables or call functions
+struct ConstCtx;
+
+impl Context for ConstCtx {
+    fn ret(&self, _value: Value) {}
+
+    fn is_ret(&self) -> bool {
+        false
+    }
+
+    fn call_function(&self, id: &str, _args: Vec<Value>) -> Result<Value, ExecutionError> {
+        Err(ExecutionError::new(
+            ExecutionErrorVariant::FunctionDoesNotExist(id.to_owned()),
+        ))
+    }
+
+    fn name(&self) -> String {
+        "constant expression".to_owned()
+    }
+}
+
+/// Looks for suspicious code in all functions and remembers which functions call which,
+/// so the ones never called from `main` can be found at the end.
+pub struct Linter {
+    lints: Lints,
+    /// Signatures of linted functions
+    functions: HashMap<String, Span>,
+    /// Functions called from each function
+    calls: HashMap<String, HashSet<String>>,
+    /// Function being linted
+    function: String,
+    /// Variables of nested contexts, innermost last
+    scopes: Vec<Vec<Variable>>,
+    /// Number of return expressions linted so far
+    returns: usize,
+    /// Number of expressions linted so far which depend on variables, functions or control flow
+    dependent: usize,
+    /// Expression being linted
+    span: Span,
+    warnings: Vec<LintWarning>,
+}
+
+impl Linter {
+    pub fn new(lints: Lints) -> Self {
+        Self {
+            lints,
+            functions: HashMap::new(),
+            calls: HashMap::new(),
+            function: String::new(),
+            scopes: vec![],
+            returns: 0,
+            dependent: 0,
+            span: Span::default(),
+            warnings: vec![],
+        }
+    }
+
+    fn add(&mut self, id: String, parameter: bool) {
+        if let Some(innermost) = self.scopes.last_mut() {
+            innermost.push(Variable {
+                id,
+                span: self.span,
+                parameter,
+                used: false,
+            });
+        }
+    }
+
+    /// Marks the value of a variable as read
+    pub fn variable(&mut self, id: &str) {
+        let variable = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.iter_mut().rev().find(|variable| variable.id == id));
+        if let Some(variable) = variable {
+            variable.used = true;
+        }
+    }
+
+    /// Remembers a call from the function being linted
+    pub fn call(&mut self, id: &str) {
+        self.calls
+            .entry(self.function.clone())
+            .or_default()
+            .insert(id.to_owned());
+    }
+
+    pub fn ret(&mut self) {
+        self.returns += 1;
+    }
+
+    /// Marks the expression being linted as dependent on variables, functions or control flow
+    pub fn depend(&mut self) {
+        self.dependent += 1;
+    }
+
+    /// Lints a condition, returning its value if it's the same every time
+    pub fn condition(&mut self, condition: &Expression) -> Option<bool> {
+        let dependent = self.dependent;
+        self.visit_expression(condition);
+        if self.dependent > dependent {
+            return None;
+        }
+        match condition.eval(&ConstCtx) {
+            Ok(Value::Bool(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Reports a warning in the expression being linted, if its lint is enabled
+    pub fn warn(&mut self, warning: LintWarningVariant) {
+        if self.lints.contains(warning.lint()) {
+            self.warnings.push(LintWarning {
+                warning,
+                start: self.span.start,
+                stop: self.span.stop,
+            });
+        }
+    }
+
+    /// Returns all warnings, including functions never called from `main`, ordered by position
+    pub fn finish(mut self) -> Vec<LintWarning> {
+        if self.functions.contains_key("main") {
+            let mut called = HashSet::from(["main".to_owned()]);
+            let mut pending = vec!["main".to_owned()];
+            while let Some(caller) = pending.pop() {
+                for callee in self.calls.remove(&caller).unwrap_or_default() {
+                    if called.insert(callee.clone()) {
+                        pending.push(callee);
+                    }
+                }
+            }
+            let mut unused: Vec<(String, Span)> = self
+                .functions
+                .drain()
+                .filter(|(id, _)| !called.contains(id))
+                .collect();
+            unused.sort_by_key(|(_, span)| *span);
+            for (id, span) in unused {
+                self.at(span, |l| l.warn(LintWarningVariant::UnusedFunction(id)));
+            }
+        }
+        self.warnings.sort_by_key(|w| w.start);
+        self.warnings
+    }
+}
+
+impl Visitor for Linter {
+    type Output = ();
+
+    fn span(&mut self) -> &mut Span {
+        &mut self.span
+    }
+
+    fn enter_scope(&mut self) {
+        self.scopes.push(vec![]);
+    }
+
+    /// Reports the unused variables of the context
+    fn exit_scope(&mut self) {
+        for variable in self.scopes.pop().unwrap_or_default() {
+            if variable.used || variable.id.starts_with('_') {
+                continue;
+            }
+            let warning = if variable.parameter {
+                LintWarningVariant::UnusedParameter(variable.id)
+            } else {
+                LintWarningVariant::UnusedVariable(variable.id)
+            };
+            self.at(variable.span, |l| l.warn(warning));
+        }
+    }
+
+    fn visit_variable(&mut self, id: &str, _data_type: Option<DataType>) {
+        let outer = self.scopes.len().saturating_sub(1);
+        if self.scopes[..outer]
+            .iter()
+            .flatten()
+            .any(|variable| variable.id == id)
+        {
+            self.warn(LintWarningVariant::ShadowedVariable(id.to_owned()));
+        }
+        self.add(id.to_owned(), false);
+    }
+
+    /// Remembers the signature, so the function can be reported if it's never called
+    fn visit_function(&mut self, function: &FunctionDefinition) {
+        self.functions
+            .insert(function.identifier.clone(), function.span);
+        self.function = function.identifier.clone();
+        self.at(function.span, |l| walk_function(l, function));
+    }
+
+    /// Parameters can't shadow variables, there are none outside of a function
+    fn visit_parameter(&mut self, parameter: &Parameter) {
+        self.at(parameter.span, |l| l.add(parameter.name.clone(), true));
+    }
+
+    /// Reports expressions following a `return` and loops whose values are dropped by a `;`
+    fn visit_statements(&mut self, statements: &[Statement]) {
+        let expressions: Vec<&Expression> = statements
+            .iter()
+            .filter_map(|s| match s {
+                Statement::Expression(expression) => Some(expression),
+                Statement::Semicolon => None,
+            })
+            .collect();
+        for (i, statement) in statements.iter().enumerate() {
+            let Statement::Expression(expression) = statement else {
+                continue;
+            };
+            self.visit_expression(expression);
+            let discarded = matches!(statements.get(i + 1), Some(Statement::Semicolon));
+            let value = match &expression.kind {
+                ExpressionKind::For(v) => v.body_value(),
+                ExpressionKind::While(v) => v.body_value(),
+                _ => None,
+            };
+            if let (true, Some(value)) = (discarded, value) {
+                if !matches!(value.kind, ExpressionKind::Return(_)) {
+                    self.at(value.span, |l| l.warn(LintWarningVariant::UnusedLoopResult));
+                }
+            }
+        }
+        let returned = expressions
+            .iter()
+            .position(|e| matches!(e.kind, ExpressionKind::Return(_)));
+        if let (Some(i), Some(last)) = (returned, expressions.last()) {
+            if let Some(first) = expressions.get(i + 1) {
+                let span = Span::new(first.span.start, last.span.stop);
+                self.at(span, |l| l.warn(LintWarningVariant::UnreachableCode));
+            }
+        }
+    }
+
+    /// Assigning a value doesn't count as using the variable
+    fn visit_assignment(&mut self, e: &AssignmentExpr) {
+        self.depend();
+        walk_assignment(self, e);
+    }
+
+    fn visit_declaration(&mut self, e: &DeclarationExpr) {
+        self.depend();
+        walk_declaration(self, e);
+    }
+
+    fn visit_for(&mut self, e: &ForExpr) {
+        self.depend();
+        walk_for(self, e);
+    }
+
+    fn visit_function_call(&mut self, e: &FunctionCallExpr) {
+        self.depend();
+        walk_function_call(self, e);
+        if let ExpressionKind::Identifier(identifier) = &e.identifier.kind {
+            self.call(&identifier.0);
+        }
+    }
+
+    fn visit_identifier(&mut self, e: &IdentifierExpr) {
+        self.depend();
+        self.variable(&e.0);
+    }
+
+    fn visit_if_else(&mut self, e: &IfElseExpr) {
+        if let Some(value) = self.condition(&e.condition) {
+            self.at(e.condition.span, |l| {
+                l.warn(LintWarningVariant::ConstantCondition(value))
+            });
+        }
+        self.scoped(|l| l.visit_statements(&e.true_case));
+        if let Some(statements) = &e.false_case {
+            self.scoped(|l| l.visit_statements(statements));
+        }
+    }
+
+    fn visit_return(&mut self, e: &ReturnExpr) {
+        self.depend();
+        walk_return(self, e);
+        self.ret();
+    }
+
+    /// `while true` is the usual way to loop until returning, so it's not a constant condition
+    fn visit_while(&mut self, e: &WhileExpr) {
+        self.depend();
+        let returns = self.returns;
+        let value = self.scoped(|l| {
+            let value = l.condition(&e.condition);
+            l.visit_statements(&e.body);
+            value
+        });
+        let Some(value) = value else {
+            return;
+        };
+        if value && self.returns == returns {
+            let header = Span::new(self.span.start, e.condition.span.stop);
+            self.at(header, |l| l.warn(LintWarningVariant::InfiniteLoop));
+        }
+        if e.condition.kind != ExpressionKind::from(Value::Bool(true)) {
+            self.at(e.condition.span, |l| {
+                l.warn(LintWarningVariant::ConstantCondition(value))
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{parse_source, position::Position};
+
+    use super::{Lint, LintWarning, LintWarningVariant, Lints};
+
+    fn lint_with(source: &str, lints: Lints) -> Vec<LintWarningVariant> {
+        parse_source(source, |p| p.parse())
+            .complete()
+            .unwrap()
+            .lint(lints)
+            .into_iter()
+            .map(|w| w.warning)
+            .collect()
+    }
+
+    fn lint(source: &str) -> Vec<LintWarningVariant> {
+        lint_with(source, Lints::all())
+    }
+
+    #[test]
+    fn clean() {
+        let source = "
+            fn main(args: []) -> int {
+                let total: int = 0;
+                for arg in args { total = total + length(arg); };
+                while total > 10 { total = total - half(total); };
+                if length(args) > 2 { return 1; };
+                total
+            }
+            fn half(x: int) -> int { x / 2 }
+            fn forever() -> int { while true { return 1; } }
+            fn ignored(_unused: int) {}";
+        assert_eq!(
+            lint(source),
+            vec![
+                LintWarningVariant::UnusedFunction("forever".to_owned()),
+                LintWarningVariant::UnusedFunction("ignored".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unused_variables() {
+        let source = "fn main(args: []) {\n    let a: int = 1;\n    a = 2;\n    let b: int = 3;\n    print(b);\n}";
+        let warnings = parse_source(source, |p| p.parse())
+            .complete()
+            .unwrap()
+            .lint(Lints::all());
+        assert_eq!(
+            warnings,
+            vec![
+                LintWarning {
+                    warning: LintWarningVariant::UnusedParameter("args".to_owned()),
+                    start: Position::new(1, 9),
+                    stop: Position::new(1, 17),
+                },
+                LintWarning {
+                    warning: LintWarningVariant::UnusedVariable("a".to_owned()),
+                    start: Position::new(2, 5),
+                    stop: Position::new(2, 19),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unused_functions() {
+        let source = "
+            fn main() { a(); }
+            fn a() { b(); }
+            fn b() { a(); }
+            fn c() { c(); d(); }
+            fn d() {}";
+        assert_eq!(
+            lint(source),
+            vec![
+                LintWarningVariant::UnusedFunction("c".to_owned()),
+                LintWarningVariant::UnusedFunction("d".to_owned()),
+            ]
+        );
+        assert_eq!(lint("fn helper() {}"), vec![]);
+    }
+
+    #[test]
+    fn unreachable_code() {
+        let source = "
+            fn main() -> int {
+                if true { return 1; print(1); print(2); };
+                return 0;
+                print(3);
+            }";
+        assert_eq!(
+            lint_with(source, Lints::default().with(Lint::UnreachableCode)),
+            vec![
+                LintWarningVariant::UnreachableCode,
+                LintWarningVariant::UnreachableCode,
+            ]
+        );
+    }
+
+    #[test]
+    fn loops() {
+        let source = "
+            fn main() {
+                while true { print(1); };
+                while 1 < 2 { print(2); };
+                while false { print(3); };
+                while true { if env(\"QUIT\") == \"1\" { return; }; };
+                for x in [1, 2] { x * 2 };
+                for x in [1, 2] { print(x) };
+                let doubled: [] = for x in [1, 2] { x * 2 };
+                print(doubled);
+            }";
+        assert_eq!(
+            lint(source),
+            vec![
+                LintWarningVariant::InfiniteLoop,
+                LintWarningVariant::InfiniteLoop,
+                LintWarningVariant::ConstantCondition(true),
+                LintWarningVariant::ConstantCondition(false),
+                LintWarningVariant::UnusedLoopResult,
+                LintWarningVariant::UnusedLoopResult,
+            ]
+        );
+    }
+
+    #[test]
+    fn constant_conditions() {
+        let source = "
+            fn main(args: []) {
+                if !(2 > 3) & \"a\" == \"a\" {};
+                if length(args) > 0 {};
+                if 1 / 0 == 0 {};
+                if { 2 > 1 } {} else {};
+            }";
+        assert_eq!(
+            lint(source),
+            vec![
+                LintWarningVariant::ConstantCondition(true),
+                LintWarningVariant::ConstantCondition(true),
+            ]
+        );
+    }
+
+    #[test]
+    fn shadowed_variables() {
+        let source = "
+            fn main(x: []) {
+                let a: int = 1;
+                { let a: int = 2; print(a); };
+                for x in [a] { print(x); };
+                if true { let b: int = 3; print(b); };
+                let b: int = 4;
+                print(b);
+            }";
+        assert_eq!(
+            lint_with(source, Lints::default().with(Lint::ShadowedVariables)),
+            vec![
+                LintWarningVariant::ShadowedVariable("a".to_owned()),
+                LintWarningVariant::ShadowedVariable("x".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lints() {
+        let lints = Lints::all().without(Lint::UnusedFunctions);
+        assert!(lints.contains(Lint::UnusedVariables));
+        assert!(!lints.contains(Lint::UnusedFunctions));
+        assert!(Lints::default().is_empty());
+        assert_eq!(
+            Lint::from_name("unused_loop_results"),
+            Some(Lint::UnusedLoopResults)
+        );
+        assert_eq!(Lint::from_name("unused"), None);
+        assert_eq!(
+            lint_with("fn main() { let a: int = 1; }", lints),
+            vec![LintWarningVariant::UnusedVariable("a".to_owned())]
+        );
+        assert_eq!(
+            lint_with("fn main() { let a: int = 1; }", Lints::default()),
+            vec![]
+        );
+    }
+}
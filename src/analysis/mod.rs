@@ -0,0 +1,5 @@
+pub mod coverage;
+pub mod linter;
+pub mod resolver;
+pub mod type_checker;
+pub mod visitor;
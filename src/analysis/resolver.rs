@@ -0,0 +1,312 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    interpreter::{standard_library::BUILTINS, ExecutionErrorVariant},
+    parser::{
+        grammar::{
+            expressions::{
+                assignment::AssignmentExpr, for_expr::ForExpr, function_call::FunctionCallExpr,
+                identifier::IdentifierExpr, statement::Statement, while_expr::WhileExpr,
+                ExpressionKind,
+            },
+            DataType,
+        },
+        position::Span,
+    },
+};
+
+use super::visitor::{walk_assignment, walk_function_call, Visitor};
+
+/// Problem found without running the program
+#[derive(Debug, PartialEq, Eq)]
+pub struct ResolverError {
+    pub variant: ExecutionErrorVariant,
+    /// Expression which would fail at runtime
+    pub span: Span,
+    /// Similar names which might have been meant instead of a missing one
+    pub suggestions: Vec<String>,
+}
+
+/// Static counterpart of the execution contexts.
+/// Tracks the names visible at each point of a function body, following the same scoping rules.
+pub struct Resolver {
+    /// Parameter counts of user functions, `None` for the standard library ones
+    functions: HashMap<String, Option<usize>>,
+    /// Variables of nested contexts, innermost last
+    scopes: Vec<HashSet<String>>,
+    /// Depth of the context of a loop body which may run more than once.
+    /// A loop keeps its context between iterations, so declarations made directly in it fail
+    /// when they're made again.
+    repeated: Option<usize>,
+    /// Expression being resolved
+    span: Span,
+    errors: Vec<ResolverError>,
+}
+
+impl Resolver {
+    /// Creates a resolver for user functions with given parameter counts.
+    /// Like during execution, they take precedence over the standard library.
+    pub fn new(functions: HashMap<String, usize>) -> Self {
+        let mut all: HashMap<String, Option<usize>> =
+            BUILTINS.iter().map(|f| (f.to_string(), None)).collect();
+        all.extend(functions.into_iter().map(|(id, count)| (id, Some(count))));
+        Self {
+            functions: all,
+            scopes: vec![],
+            repeated: None,
+            span: Span::default(),
+            errors: vec![],
+        }
+    }
+
+    /// Checks if a variable is visible
+    pub fn variable(&mut self, id: &str) {
+        if !self.scopes.iter().any(|scope| scope.contains(id)) {
+            self.error(ExecutionErrorVariant::VariableDoesNotExist(id.to_owned()));
+        }
+    }
+
+    /// Checks if a function exists and takes the given number of arguments.
+    /// Argument counts of the standard library functions are only checked at runtime.
+    pub fn call(&mut self, id: &str, given: usize) {
+        match self.functions.get(id) {
+            None => self.error(ExecutionErrorVariant::FunctionDoesNotExist(id.to_owned())),
+            Some(Some(expected)) if *expected != given => {
+                let expected = *expected;
+                self.error(ExecutionErrorVariant::InvalidArgumentCount { expected, given })
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Resolves the body of a loop in the innermost context, the one of the loop.
+    /// A body which always returns never runs a second iteration.
+    fn loop_body(&mut self, body: &[Statement]) {
+        let returns = body.iter().any(|s| {
+            matches!(s, Statement::Expression(e) if matches!(e.kind, ExpressionKind::Return(_)))
+        });
+        let repeated = (!returns).then_some(self.scopes.len());
+        let outer = std::mem::replace(&mut self.repeated, repeated);
+        self.visit_statements(body);
+        self.repeated = outer;
+    }
+
+    /// Reports a problem with the expression being resolved
+    pub fn error(&mut self, variant: ExecutionErrorVariant) {
+        let suggestions = variant.suggestions(
+            || self.scopes.iter().flatten().cloned().collect(),
+            || self.functions.keys().cloned().collect(),
+        );
+        self.errors.push(ResolverError {
+            variant,
+            span: self.span,
+            suggestions,
+        });
+    }
+
+    /// Returns all problems, ordered by position
+    pub fn finish(mut self) -> Vec<ResolverError> {
+        self.errors.sort_by_key(|e| e.span);
+        self.errors
+    }
+}
+
+impl Visitor for Resolver {
+    type Output = ();
+
+    fn span(&mut self) -> &mut Span {
+        &mut self.span
+    }
+
+    fn enter_scope(&mut self) {
+        self.scopes.push(HashSet::new());
+    }
+
+    fn exit_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Variables can shadow the ones from outer contexts, but not the ones from the same context
+    fn visit_variable(&mut self, id: &str, _data_type: Option<DataType>) {
+        let innermost = self
+            .scopes
+            .last_mut()
+            .expect("declaration outside of a context");
+        if !innermost.insert(id.to_owned()) || self.repeated == Some(self.scopes.len()) {
+            self.error(ExecutionErrorVariant::VariableAlreadyExists(id.to_owned()));
+        }
+    }
+
+    /// The loop variable is replaced by every iteration, unlike the declarations of the body
+    fn visit_for(&mut self, e: &ForExpr) {
+        self.visit_expression(&e.provider);
+        self.scoped(|r| {
+            r.visit_variable(&e.variable, None);
+            r.loop_body(&e.body);
+        });
+    }
+
+    fn visit_while(&mut self, e: &WhileExpr) {
+        self.scoped(|r| {
+            r.visit_expression(&e.condition);
+            r.loop_body(&e.body);
+        });
+    }
+
+    fn visit_assignment(&mut self, e: &AssignmentExpr) {
+        walk_assignment(self, e);
+        if let ExpressionKind::Identifier(identifier) = &e.identifier.kind {
+            self.variable(&identifier.0);
+        } else {
+            self.error(ExecutionErrorVariant::ExpectedIdentifier);
+        }
+    }
+
+    fn visit_function_call(&mut self, e: &FunctionCallExpr) {
+        if let ExpressionKind::Identifier(identifier) = &e.identifier.kind {
+            walk_function_call(self, e);
+            self.call(&identifier.0, e.arguments.len());
+        } else {
+            self.error(ExecutionErrorVariant::ExpectedIdentifier);
+        }
+    }
+
+    fn visit_identifier(&mut self, e: &IdentifierExpr) {
+        self.variable(&e.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::ExecutionErrorVariant,
+        parser::{
+            parse_source,
+            position::{Position, Span},
+        },
+    };
+
+    use super::ResolverError;
+
+    fn resolve(source: &str) -> Vec<ResolverError> {
+        parse_source(source, |p| p.parse())
+            .complete()
+            .unwrap()
+            .resolve()
+    }
+
+    fn variants(source: &str) -> Vec<ExecutionErrorVariant> {
+        resolve(source).into_iter().map(|e| e.variant).collect()
+    }
+
+    #[test]
+    fn valid() {
+        let source = "
+            fn main(args: []) {
+                let a: int = 1;
+                for i in args { print(i, a); };
+                { let a: int = a + 1; a = 2; };
+                while a < 5 { a = a + 1; };
+                helper(a);
+            }
+            fn helper(b: int) -> int { cast_int(b) }";
+        assert_eq!(variants(source), vec![]);
+    }
+
+    #[test]
+    fn rarely_taken_branch() {
+        let source = "fn main() {\n    if false {\n        print(missing);\n    }\n}";
+        assert_eq!(
+            resolve(source),
+            vec![ResolverError {
+                variant: ExecutionErrorVariant::VariableDoesNotExist("missing".to_owned()),
+                span: Span::new(Position::new(3, 15), Position::new(3, 22)),
+                suggestions: vec![],
+            }]
+        );
+    }
+
+    #[test]
+    fn scopes() {
+        let source = "
+            fn main() {
+                if true { let a: int = 1; } else { a; };
+                a = 2;
+                for i in [] {};
+                i;
+                let b: int = b;
+            }";
+        assert_eq!(
+            variants(source),
+            vec![
+                ExecutionErrorVariant::VariableDoesNotExist("a".to_owned()),
+                ExecutionErrorVariant::VariableDoesNotExist("a".to_owned()),
+                ExecutionErrorVariant::VariableDoesNotExist("i".to_owned()),
+                ExecutionErrorVariant::VariableDoesNotExist("b".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn redeclaration() {
+        let source = "
+            fn main(a: int) {
+                let a: int = 1;
+                { let a: int = 2; }
+            }";
+        assert_eq!(
+            variants(source),
+            vec![ExecutionErrorVariant::VariableAlreadyExists("a".to_owned())]
+        );
+    }
+
+    #[test]
+    fn loop_declarations() {
+        let source = "
+            fn main() {
+                while true {
+                    let a: int = 1;
+                    { let b: int = 2; };
+                    if true { let c: int = 3; };
+                };
+                for i in [1, 2] {
+                    let d: int = i;
+                };
+                while true {
+                    let e: int = 4;
+                    return;
+                };
+            }";
+        assert_eq!(
+            variants(source),
+            vec![
+                ExecutionErrorVariant::VariableAlreadyExists("a".to_owned()),
+                ExecutionErrorVariant::VariableAlreadyExists("d".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn functions() {
+        let source = "
+            fn main() {
+                doubel(1);
+                double(1, 2);
+                print(1, 2, 3);
+                length(1, 2);
+            }
+            fn double(a: int) -> int { a * 2 }";
+        let errors = resolve(source);
+        assert_eq!(
+            errors.iter().map(|e| &e.variant).collect::<Vec<_>>(),
+            vec![
+                &ExecutionErrorVariant::FunctionDoesNotExist("doubel".to_owned()),
+                &ExecutionErrorVariant::InvalidArgumentCount {
+                    expected: 1,
+                    given: 2
+                },
+            ]
+        );
+        assert_eq!(errors[0].suggestions, vec!["double".to_owned()]);
+    }
+}
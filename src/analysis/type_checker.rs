@@ -0,0 +1,466 @@
+use std::collections::HashMap;
+
+use crate::{
+    interpreter::{
+        standard_library::signature,
+        types::{sample, validate_type, validate_types},
+        ExecutionError, ExecutionErrorVariant,
+    },
+    parser::{
+        grammar::{
+            expressions::{
+                assignment::AssignmentExpr,
+                binary::{self, BinaryExpr},
+                declaration::DeclarationExpr,
+                for_expr::ForExpr,
+                function_call::FunctionCallExpr,
+                identifier::IdentifierExpr,
+                if_else::IfElseExpr,
+                list::ListExpr,
+                list_access::{IndexOrRange, ListAccessExpr},
+                literal::LiteralExpr,
+                return_expr::ReturnExpr,
+                statement::Statement,
+                unary::{self, UnaryExpr},
+                while_expr::WhileExpr,
+                ExpressionKind,
+            },
+            function::FunctionDefinition,
+            DataType,
+        },
+        position::Span,
+    },
+};
+
+use super::visitor::{walk_function, walk_list, Visitor};
+
+/// Type mismatch found without running the program
+#[derive(Debug, PartialEq, Eq)]
+pub struct TypeError {
+    pub variant: ExecutionErrorVariant,
+    /// Expression which would fail at runtime
+    pub span: Span,
+}
+
+/// Parameter types and return type of a function
+pub type FunctionType = (Vec<DataType>, DataType);
+
+/// Infers types of expressions from literals, operators and function signatures.
+/// Values whose types are only known at runtime, like list elements, are never reported.
+pub struct TypeChecker {
+    functions: HashMap<String, FunctionType>,
+    /// Return type of the function being checked
+    returning: DataType,
+    /// Number of return expressions checked so far
+    returns: usize,
+    /// Variable types of nested contexts, innermost last
+    scopes: Vec<HashMap<String, Option<DataType>>>,
+    /// Expression being checked
+    span: Span,
+    errors: Vec<TypeError>,
+}
+
+impl TypeChecker {
+    /// Creates a type checker for user functions with given types.
+    /// Like during execution, they take precedence over the standard library.
+    pub fn new(functions: HashMap<String, FunctionType>) -> Self {
+        Self {
+            functions,
+            returning: DataType::None,
+            returns: 0,
+            scopes: vec![],
+            span: Span::default(),
+            errors: vec![],
+        }
+    }
+
+    /// Returns the type of a variable, unknown for missing ones
+    pub fn variable(&self, id: &str) -> Option<DataType> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(id))
+            .copied()
+            .flatten()
+    }
+
+    /// Checks arguments of a function call, returning the type of the returned value
+    pub fn call(&mut self, id: &str, args: Vec<Option<DataType>>) -> Option<DataType> {
+        if let Some((params, returns)) = self.functions.get(id) {
+            let returns = *returns;
+            let checks: Vec<_> = params.iter().copied().zip(args).collect();
+            for (param, arg) in checks {
+                self.expect(param, arg);
+            }
+            Some(returns)
+        } else {
+            let signature = signature(id)?;
+            if let (false, Some(Some(arg))) = (signature.accepts.is_empty(), args.first()) {
+                if !signature.accepts.contains(arg) {
+                    self.error(ExecutionErrorVariant::InvalidType {
+                        expected: signature.accepts.to_vec(),
+                        actual: *arg,
+                    });
+                }
+            }
+            signature.returns
+        }
+    }
+
+    /// Checks a returned value against the return type of the function
+    pub fn ret(&mut self, value: Option<DataType>) {
+        self.returns += 1;
+        self.expect(self.returning, value);
+    }
+
+    /// Checks if a value of a known type can be used where the expected type is required
+    pub fn expect(&mut self, expected: DataType, actual: Option<DataType>) {
+        if let Some(actual) = actual {
+            if let Err(e) = validate_type(expected, &sample(actual)) {
+                self.error(e.variant);
+            }
+        }
+    }
+
+    /// Reports the error of a runtime rule applied to types, returning the resulting type
+    pub fn report(&mut self, result: Result<DataType, ExecutionError>) -> Option<DataType> {
+        result.map_err(|e| self.error(e.variant)).ok()
+    }
+
+    /// Reports a mismatch in the expression being checked
+    pub fn error(&mut self, variant: ExecutionErrorVariant) {
+        self.errors.push(TypeError {
+            variant,
+            span: self.span,
+        });
+    }
+
+    /// Returns all mismatches, ordered by position
+    pub fn finish(mut self) -> Vec<TypeError> {
+        self.errors.sort_by_key(|e| e.span);
+        self.errors
+    }
+}
+
+/// Infers the type of every visited value, `None` when it's only known at runtime
+impl Visitor for TypeChecker {
+    type Output = Option<DataType>;
+
+    fn span(&mut self) -> &mut Span {
+        &mut self.span
+    }
+
+    fn enter_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn exit_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn visit_variable(&mut self, id: &str, data_type: Option<DataType>) {
+        if let Some(innermost) = self.scopes.last_mut() {
+            innermost.insert(id.to_owned(), data_type);
+        }
+    }
+
+    /// Checks the body and the value of the last expression against the return type
+    fn visit_function(&mut self, function: &FunctionDefinition) -> Option<DataType> {
+        self.returning = function.data_type;
+        let returning = walk_function(self, function);
+        let last = function.statements.iter().rev().find_map(|s| match s {
+            Statement::Expression(expression) => Some(expression.span),
+            Statement::Semicolon => None,
+        });
+        self.at(last.unwrap_or_default(), |t| {
+            t.expect(function.data_type, returning)
+        });
+        Some(function.data_type)
+    }
+
+    /// Infers the value of a block like `alternate_statements` does.
+    /// A block which may have returned from the function in its last expression has an unknown value.
+    fn visit_statements(&mut self, statements: &[Statement]) -> Option<DataType> {
+        let mut returning = Some(DataType::None);
+        let mut returned = false;
+        for statement in statements {
+            match statement {
+                Statement::Expression(expression) => {
+                    let returns = self.returns;
+                    returning = self.visit_expression(expression);
+                    returned = self.returns > returns && returning.is_none();
+                }
+                Statement::Semicolon if !returned => returning = Some(DataType::None),
+                Statement::Semicolon => {}
+            }
+        }
+        returning
+    }
+
+    fn visit_assignment(&mut self, e: &AssignmentExpr) -> Option<DataType> {
+        let value = self.visit_expression(&e.expression);
+        if let ExpressionKind::Identifier(identifier) = &e.identifier.kind {
+            if let (Some(current), Some(value)) = (self.variable(&identifier.0), value) {
+                self.report(validate_types(&sample(current), &sample(value)).map(|_| value));
+            }
+        }
+        value
+    }
+
+    fn visit_binary(&mut self, e: &BinaryExpr) -> Option<DataType> {
+        let lhs = self.visit_expression(&e.lhs);
+        let rhs = self.visit_expression(&e.rhs);
+        let result = binary::apply(sample(lhs?), e.operator, sample(rhs?));
+        self.report(result.map(|v| DataType::from(&v)))
+    }
+
+    fn visit_declaration(&mut self, e: &DeclarationExpr) -> Option<DataType> {
+        let value = self.visit_expression(&e.expression);
+        self.expect(e.data_type, value);
+        self.visit_variable(&e.identifier, Some(e.data_type));
+        Some(e.data_type)
+    }
+
+    fn visit_for(&mut self, e: &ForExpr) -> Option<DataType> {
+        let provider = self.visit_expression(&e.provider);
+        self.expect(DataType::List, provider);
+        let returns = self.returns;
+        self.scoped(|t| {
+            t.visit_variable(&e.variable, None);
+            t.visit_statements(&e.body)
+        });
+        (self.returns == returns).then_some(DataType::List)
+    }
+
+    fn visit_function_call(&mut self, e: &FunctionCallExpr) -> Option<DataType> {
+        let arguments = e
+            .arguments
+            .iter()
+            .map(|v| self.visit_expression(v))
+            .collect();
+        if let ExpressionKind::Identifier(identifier) = &e.identifier.kind {
+            self.call(&identifier.0, arguments)
+        } else {
+            None
+        }
+    }
+
+    fn visit_identifier(&mut self, e: &IdentifierExpr) -> Option<DataType> {
+        self.variable(&e.0)
+    }
+
+    fn visit_if_else(&mut self, e: &IfElseExpr) -> Option<DataType> {
+        let condition = self.visit_expression(&e.condition);
+        self.expect(DataType::Bool, condition);
+        let true_case = self.scoped(|t| t.visit_statements(&e.true_case));
+        let false_case = match &e.false_case {
+            Some(statements) => self.scoped(|t| t.visit_statements(statements)),
+            None => Some(DataType::None),
+        };
+        if true_case == false_case {
+            true_case
+        } else {
+            None
+        }
+    }
+
+    /// Elements of lists are only known at runtime
+    fn visit_list_access(&mut self, e: &ListAccessExpr) -> Option<DataType> {
+        let list = self.visit_expression(&e.list);
+        let indices = match &e.access {
+            IndexOrRange::Index(index) => vec![self.visit_expression(index)],
+            IndexOrRange::Range(left, right) => {
+                vec![self.visit_expression(left), self.visit_expression(right)]
+            }
+        };
+        match list? {
+            DataType::List | DataType::String => {}
+            _ => {
+                self.error(ExecutionErrorVariant::UnsupportedListAccess);
+                return None;
+            }
+        }
+        if indices
+            .into_iter()
+            .flatten()
+            .any(|i| i != DataType::Integer)
+        {
+            self.error(ExecutionErrorVariant::NonIntegerIndex);
+            return None;
+        }
+        match (list, &e.access) {
+            (Some(DataType::List), IndexOrRange::Index(_)) => None,
+            _ => list,
+        }
+    }
+
+    fn visit_list(&mut self, e: &ListExpr) -> Option<DataType> {
+        walk_list(self, e);
+        Some(DataType::List)
+    }
+
+    fn visit_literal(&mut self, e: &LiteralExpr) -> Option<DataType> {
+        Some(DataType::from(&e.0))
+    }
+
+    /// The value of a return expression is never used, as the function ends
+    fn visit_return(&mut self, e: &ReturnExpr) -> Option<DataType> {
+        let value = match &e.0 {
+            Some(value) => self.visit_expression(value),
+            None => Some(DataType::None),
+        };
+        self.ret(value);
+        None
+    }
+
+    fn visit_unary(&mut self, e: &UnaryExpr) -> Option<DataType> {
+        let value = self.visit_expression(&e.expression)?;
+        let result = unary::apply(e.operator, sample(value));
+        self.report(result.map(|v| DataType::from(&v)))
+    }
+
+    fn visit_while(&mut self, e: &WhileExpr) -> Option<DataType> {
+        let returns = self.returns;
+        self.scoped(|t| {
+            let condition = t.visit_expression(&e.condition);
+            t.expect(DataType::Bool, condition);
+            t.visit_statements(&e.body);
+        });
+        (self.returns == returns).then_some(DataType::List)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::ExecutionErrorVariant,
+        parser::{
+            grammar::DataType,
+            parse_source,
+            position::{Position, Span},
+        },
+    };
+
+    use super::TypeError;
+
+    fn check(source: &str) -> Vec<TypeError> {
+        parse_source(source, |p| p.parse())
+            .complete()
+            .unwrap()
+            .check_types()
+    }
+
+    fn variants(source: &str) -> Vec<ExecutionErrorVariant> {
+        check(source).into_iter().map(|e| e.variant).collect()
+    }
+
+    fn invalid(expected: &[DataType], actual: DataType) -> ExecutionErrorVariant {
+        ExecutionErrorVariant::InvalidType {
+            expected: expected.to_vec(),
+            actual,
+        }
+    }
+
+    #[test]
+    fn valid() {
+        let source = "
+            fn main(args: []) -> int {
+                let a: int = 1 + cast_int(\"2\") * length(args);
+                let b: string = \"a\" + type(a);
+                let c: bool = a > 2 & !(b == \"b\");
+                let d: [] = for arg in args { arg };
+                let e: float = -1.5;
+                if c { return a; };
+                d = push(d, args[0]);
+                square(a)
+            }
+            fn square(x: int) -> int { x * x }
+            fn first(list: []) -> string { list[0] }
+            fn forever() -> int { while true { return 1; } }";
+        assert_eq!(variants(source), vec![]);
+    }
+
+    #[test]
+    fn binary() {
+        let source = "fn main() {\n    let a: int = 1 + \"s\";\n}";
+        assert_eq!(
+            check(source),
+            vec![TypeError {
+                variant: invalid(&[DataType::Integer], DataType::String),
+                span: Span::new(Position::new(2, 18), Position::new(2, 25)),
+            }]
+        );
+        assert_eq!(
+            variants("fn main() { true + false; \"a\" * \"b\"; -\"a\"; }"),
+            vec![
+                ExecutionErrorVariant::UnsupportedBinaryOperation,
+                ExecutionErrorVariant::UnsupportedBinaryOperation,
+                ExecutionErrorVariant::UnsupportedUnaryOperation,
+            ]
+        );
+    }
+
+    #[test]
+    fn declarations() {
+        let source = "
+            fn main() {
+                let a: int = 1.5;
+                let b: string = \"\";
+                b = 2;
+                a + b;
+            }";
+        assert_eq!(
+            variants(source),
+            vec![
+                invalid(&[DataType::Integer], DataType::Float),
+                invalid(&[DataType::String], DataType::Integer),
+                invalid(&[DataType::Integer], DataType::String),
+            ]
+        );
+    }
+
+    #[test]
+    fn functions() {
+        let source = "
+            fn main() {
+                half(\"1\");
+                length(5);
+                if 1 { print(); };
+                for x in \"abc\" {};
+            }
+            fn half(a: float) -> int { cast_int(a / 2.0) }
+            fn name() -> int { return \"a\"; }
+            fn implicit() -> int { \"a\" }";
+        assert_eq!(
+            variants(source),
+            vec![
+                invalid(&[DataType::Float], DataType::String),
+                invalid(&[DataType::List, DataType::String], DataType::Integer),
+                invalid(&[DataType::Bool], DataType::Integer),
+                invalid(&[DataType::List], DataType::String),
+                invalid(&[DataType::Integer], DataType::String),
+                invalid(&[DataType::Integer], DataType::String),
+            ]
+        );
+    }
+
+    #[test]
+    fn list_access() {
+        let source = "
+            fn main() {
+                let a: string = \"abc\"[0];
+                let b: string = \"abc\"[0::2];
+                let c: int = [1, 2][\"0\"];
+                let d: string = [1, 2][0::1];
+                5[0];
+            }";
+        assert_eq!(
+            variants(source),
+            vec![
+                ExecutionErrorVariant::NonIntegerIndex,
+                invalid(&[DataType::String], DataType::List),
+                ExecutionErrorVariant::UnsupportedListAccess,
+            ]
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use crate::parser::{
+    grammar::{
+        expressions::{
+            assignment::AssignmentExpr,
+            binary::BinaryExpr,
+            code_block::CodeBlockExpr,
+            declaration::DeclarationExpr,
+            for_expr::ForExpr,
+            function_call::FunctionCallExpr,
+            identifier::IdentifierExpr,
+            if_else::IfElseExpr,
+            list::ListExpr,
+            list_access::{IndexOrRange, ListAccessExpr},
+            literal::LiteralExpr,
+            return_expr::ReturnExpr,
+            statement::Statement,
+            unary::UnaryExpr,
+            while_expr::WhileExpr,
+            Expression, ExpressionKind,
+        },
+        function::{FunctionDefinition, Parameter},
+        DataType,
+    },
+    position::Span,
+};
+
+/// Walks the functions of a program without running them.
+///
+/// Every node is visited by its own method, which by default visits the children of the node
+/// and returns the default output. Blocks, branches, loops and function bodies are visited in
+/// scopes, opened and closed the same way the interpreter creates and drops its contexts.
+/// Passes override the methods of the nodes they're interested in,
+/// calling the matching `walk_*` function to continue into the children.
+pub trait Visitor: Sized {
+    /// Result of visiting a node, such as the inferred type of an expression
+    type Output: Default;
+
+    /// Span of the expression being visited
+    fn span(&mut self) -> &mut Span;
+
+    /// Visits a part of the program, anything reported within points at the span
+    fn at<T>(&mut self, span: Span, visit: impl FnOnce(&mut Self) -> T) -> T {
+        let outer = std::mem::replace(self.span(), span);
+        let result = visit(self);
+        *self.span() = outer;
+        result
+    }
+
+    /// Opens a context for the variables declared in a block
+    fn enter_scope(&mut self) {}
+
+    /// Closes the innermost context, its variables go out of scope
+    fn exit_scope(&mut self) {}
+
+    /// Visits a part of the program in a new context
+    fn scoped<T>(&mut self, visit: impl FnOnce(&mut Self) -> T) -> T {
+        self.enter_scope();
+        let result = visit(self);
+        self.exit_scope();
+        result
+    }
+
+    /// Brings a declared variable, a loop variable or a parameter into the innermost context,
+    /// along with its type if it's known before running the program
+    fn visit_variable(&mut self, _id: &str, _data_type: Option<DataType>) {}
+
+    fn visit_function(&mut self, function: &FunctionDefinition) -> Self::Output {
+        walk_function(self, function)
+    }
+
+    fn visit_parameter(&mut self, parameter: &Parameter) {
+        self.at(parameter.span, |v| {
+            v.visit_variable(&parameter.name, Some(parameter.data_type))
+        });
+    }
+
+    /// Visits the statements of a block in the current context
+    fn visit_statements(&mut self, statements: &[Statement]) -> Self::Output {
+        walk_statements(self, statements)
+    }
+
+    /// Anything reported within the expression points at it, unless a more specific one is found
+    fn visit_expression(&mut self, expression: &Expression) -> Self::Output {
+        self.at(expression.span, |v| walk_expression(v, expression))
+    }
+
+    fn visit_assignment(&mut self, e: &AssignmentExpr) -> Self::Output {
+        walk_assignment(self, e)
+    }
+
+    fn visit_binary(&mut self, e: &BinaryExpr) -> Self::Output {
+        walk_binary(self, e)
+    }
+
+    fn visit_code_block(&mut self, e: &CodeBlockExpr) -> Self::Output {
+        walk_code_block(self, e)
+    }
+
+    fn visit_declaration(&mut self, e: &DeclarationExpr) -> Self::Output {
+        walk_declaration(self, e)
+    }
+
+    fn visit_for(&mut self, e: &ForExpr) -> Self::Output {
+        walk_for(self, e)
+    }
+
+    fn visit_function_call(&mut self, e: &FunctionCallExpr) -> Self::Output {
+        walk_function_call(self, e)
+    }
+
+    fn visit_identifier(&mut self, _e: &IdentifierExpr) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_if_else(&mut self, e: &IfElseExpr) -> Self::Output {
+        walk_if_else(self, e)
+    }
+
+    fn visit_list_access(&mut self, e: &ListAccessExpr) -> Self::Output {
+        walk_list_access(self, e)
+    }
+
+    fn visit_list(&mut self, e: &ListExpr) -> Self::Output {
+        walk_list(self, e)
+    }
+
+    fn visit_literal(&mut self, _e: &LiteralExpr) -> Self::Output {
+        Self::Output::default()
+    }
+
+    fn visit_return(&mut self, e: &ReturnExpr) -> Self::Output {
+        walk_return(self, e)
+    }
+
+    fn visit_unary(&mut self, e: &UnaryExpr) -> Self::Output {
+        walk_unary(self, e)
+    }
+
+    fn visit_while(&mut self, e: &WhileExpr) -> Self::Output {
+        walk_while(self, e)
+    }
+}
+
+/// Visits the parameters and the body of a function in its context,
+/// returning the output of the body
+pub fn walk_function<V: Visitor>(v: &mut V, function: &FunctionDefinition) -> V::Output {
+    v.scoped(|v| {
+        for parameter in &function.params {
+            v.visit_parameter(parameter);
+        }
+        v.visit_statements(&function.statements)
+    })
+}
+
+/// Visits every expression of a block in order
+pub fn walk_statements<V: Visitor>(v: &mut V, statements: &[Statement]) -> V::Output {
+    for statement in statements {
+        if let Statement::Expression(expression) = statement {
+            v.visit_expression(expression);
+        }
+    }
+    V::Output::default()
+}
+
+/// Visits an expression by its kind
+pub fn walk_expression<V: Visitor>(v: &mut V, expression: &Expression) -> V::Output {
+    match &expression.kind {
+        ExpressionKind::Assignment(e) => v.visit_assignment(e),
+        ExpressionKind::Binary(e) => v.visit_binary(e),
+        ExpressionKind::CodeBlock(e) => v.visit_code_block(e),
+        ExpressionKind::Declaration(e) => v.visit_declaration(e),
+        ExpressionKind::For(e) => v.visit_for(e),
+        ExpressionKind::FunctionCall(e) => v.visit_function_call(e),
+        ExpressionKind::Identifier(e) => v.visit_identifier(e),
+        ExpressionKind::IfElse(e) => v.visit_if_else(e),
+        ExpressionKind::ListAccess(e) => v.visit_list_access(e),
+        ExpressionKind::List(e) => v.visit_list(e),
+        ExpressionKind::Literal(e) => v.visit_literal(e),
+        ExpressionKind::Return(e) => v.visit_return(e),
+        ExpressionKind::Unary(e) => v.visit_unary(e),
+        ExpressionKind::While(e) => v.visit_while(e),
+    }
+}
+
+/// Visits the assigned value, the assigned variable isn't read
+pub fn walk_assignment<V: Visitor>(v: &mut V, e: &AssignmentExpr) -> V::Output {
+    v.visit_expression(&e.expression);
+    V::Output::default()
+}
+
+pub fn walk_binary<V: Visitor>(v: &mut V, e: &BinaryExpr) -> V::Output {
+    v.visit_expression(&e.lhs);
+    v.visit_expression(&e.rhs);
+    V::Output::default()
+}
+
+/// Visits the statements in a new context, returning their output
+pub fn walk_code_block<V: Visitor>(v: &mut V, e: &CodeBlockExpr) -> V::Output {
+    v.scoped(|v| v.visit_statements(&e.0))
+}
+
+/// Visits the value, then brings the variable into the context
+pub fn walk_declaration<V: Visitor>(v: &mut V, e: &DeclarationExpr) -> V::Output {
+    v.visit_expression(&e.expression);
+    v.visit_variable(&e.identifier, Some(e.data_type));
+    V::Output::default()
+}
+
+/// Visits the iterated list, then the body in a new context along with the loop variable,
+/// which points at the loop header
+pub fn walk_for<V: Visitor>(v: &mut V, e: &ForExpr) -> V::Output {
+    v.visit_expression(&e.provider);
+    let header = Span::new(v.span().start, e.provider.span.stop);
+    v.scoped(|v| {
+        v.at(header, |v| v.visit_variable(&e.variable, None));
+        v.visit_statements(&e.body);
+    });
+    V::Output::default()
+}
+
+/// Visits the arguments, the called function is only a name
+pub fn walk_function_call<V: Visitor>(v: &mut V, e: &FunctionCallExpr) -> V::Output {
+    for argument in &e.arguments {
+        v.visit_expression(argument);
+    }
+    V::Output::default()
+}
+
+/// Visits the condition, then each branch in a new context
+pub fn walk_if_else<V: Visitor>(v: &mut V, e: &IfElseExpr) -> V::Output {
+    v.visit_expression(&e.condition);
+    v.scoped(|v| v.visit_statements(&e.true_case));
+    if let Some(statements) = &e.false_case {
+        v.scoped(|v| v.visit_statements(statements));
+    }
+    V::Output::default()
+}
+
+pub fn walk_list_access<V: Visitor>(v: &mut V, e: &ListAccessExpr) -> V::Output {
+    v.visit_expression(&e.list);
+    match &e.access {
+        IndexOrRange::Index(index) => {
+            v.visit_expression(index);
+        }
+        IndexOrRange::Range(left, right) => {
+            v.visit_expression(left);
+            v.visit_expression(right);
+        }
+    }
+    V::Output::default()
+}
+
+pub fn walk_list<V: Visitor>(v: &mut V, e: &ListExpr) -> V::Output {
+    for expression in &e.0 {
+        v.visit_expression(expression);
+    }
+    V::Output::default()
+}
+
+pub fn walk_return<V: Visitor>(v: &mut V, e: &ReturnExpr) -> V::Output {
+    if let Some(value) = &e.0 {
+        v.visit_expression(value);
+    }
+    V::Output::default()
+}
+
+pub fn walk_unary<V: Visitor>(v: &mut V, e: &UnaryExpr) -> V::Output {
+    v.visit_expression(&e.expression);
+    V::Output::default()
+}
+
+/// Visits the condition and the body in a new context, like a single iteration
+pub fn walk_while<V: Visitor>(v: &mut V, e: &WhileExpr) -> V::Output {
+    v.scoped(|v| {
+        v.visit_expression(&e.condition);
+        v.visit_statements(&e.body);
+    });
+    V::Output::default()
+}
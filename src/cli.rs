@@ -0,0 +1,803 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    analysis::linter::{Lint, Lints},
+    interpreter::{alternatives, trace::TraceLevel},
+    locale::Language,
+};
+
+/// Source of code
+#[derive(Debug, PartialEq, Eq)]
+pub enum InputType {
+    Standard,
+    File(PathBuf),
+}
+
+impl Display for InputType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputType::Standard => f.write_str("stdin"),
+            InputType::File(path) => f.write_fmt(format_args!("{}", path.to_string_lossy())),
+        }
+    }
+}
+
+/// Serialization format of machine-readable output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Ron,
+    Json,
+    Sarif,
+}
+
+impl OutputFormat {
+    /// Serializes a value into a pretty string.
+    /// SARIF logs are built separately, other values fall back to JSON.
+    pub fn serialize<T: Serialize>(&self, value: &T) -> String {
+        match self {
+            OutputFormat::Ron => {
+                ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::new()).unwrap()
+            }
+            OutputFormat::Json | OutputFormat::Sarif => {
+                serde_json::to_string_pretty(value).unwrap()
+            }
+        }
+    }
+}
+
+/// Optional passes over the parsed program, run before running or checking it
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Analysis {
+    pub typecheck: bool,
+    pub lints: Lints,
+}
+
+/// Where to write the variables of the contexts which were active when a program failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorDump {
+    /// Printed after the runtime error
+    Print,
+    /// Serialized into a file
+    File(PathBuf, OutputFormat),
+}
+
+/// Optional observation of a running program
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Instrumentation {
+    pub dump_on_error: Option<ErrorDump>,
+    pub trace: Option<TraceLevel>,
+    pub debug: bool,
+    pub profile: bool,
+    /// File to write the profiled stacks of functions to
+    pub folded_stacks: Option<PathBuf>,
+    pub mem_profile: bool,
+    /// Directory to write the coverage reports to
+    pub coverage: Option<PathBuf>,
+}
+
+/// Information about execution derived from input parameters
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParsedArgs {
+    InstructionManual,
+    Interactive,
+    Explain(String),
+    Eval(String),
+    /// Runs the scripts of a directory, comparing their output with the expected one
+    Test(PathBuf),
+    Run(InputType, Vec<String>, Analysis, Instrumentation),
+    DumpTokens(InputType, OutputFormat),
+    DumpAst(InputType, OutputFormat),
+    Check(InputType, OutputFormat, bool, Analysis),
+}
+
+/// What to do with the input
+enum Mode {
+    Run,
+    DumpTokens,
+    DumpAst,
+    Check,
+}
+
+/// Instruction manual
+pub static MANUAL: &str = include_str!("manual.txt");
+
+/// Process exit codes which aren't caused by runtime errors
+pub mod exit_codes {
+    pub const NO_FILE: u8 = 1;
+    pub const MISSING_ARGUMENT: u8 = 2;
+    pub const INVALID_ARGUMENT: u8 = 3;
+    pub const WARNINGS: u8 = 4;
+    pub const PARSER_ERROR: u8 = 5;
+    pub const TESTS_FAILED: u8 = 6;
+}
+
+/// Application error containing message and process return code
+pub struct AppError {
+    pub msg: String,
+    pub code: u8,
+}
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}", self.msg))
+    }
+}
+
+/// Parses the input path argument
+fn parse_input(path: Option<String>) -> Result<InputType, AppError> {
+    match path {
+        Some(path) if path.eq("-") => Ok(InputType::Standard),
+        Some(path) => Ok(InputType::File(PathBuf::from(path))),
+        None => Err(AppError {
+            msg: "Missing input file path argument.".to_owned(),
+            code: exit_codes::MISSING_ARGUMENT,
+        }),
+    }
+}
+
+/// Parses the output format argument
+fn parse_format(format: Option<String>) -> Result<OutputFormat, AppError> {
+    match format.as_deref() {
+        Some("ron") => Ok(OutputFormat::Ron),
+        Some("json") => Ok(OutputFormat::Json),
+        Some("sarif") => Ok(OutputFormat::Sarif),
+        Some(format) => Err(AppError {
+            msg: format!("Invalid output format `{format}`."),
+            code: exit_codes::INVALID_ARGUMENT,
+        }),
+        None => Err(AppError {
+            msg: "Missing output format argument.".to_owned(),
+            code: exit_codes::MISSING_ARGUMENT,
+        }),
+    }
+}
+
+/// Parses the name of a lint
+fn parse_lint(name: Option<String>) -> Result<Lint, AppError> {
+    let name = name.ok_or_else(|| AppError {
+        msg: "Missing lint name argument.".to_owned(),
+        code: exit_codes::MISSING_ARGUMENT,
+    })?;
+    Lint::from_name(&name).ok_or_else(|| AppError {
+        msg: format!(
+            "Invalid lint `{name}`, expected {}.",
+            alternatives(&Lint::ALL)
+        ),
+        code: exit_codes::INVALID_ARGUMENT,
+    })
+}
+
+/// Parses the language argument
+fn parse_language(tag: Option<String>) -> Result<Language, AppError> {
+    let tag = tag.ok_or_else(|| AppError {
+        msg: "Missing language argument.".to_owned(),
+        code: exit_codes::MISSING_ARGUMENT,
+    })?;
+    Language::from_tag(&tag).ok_or_else(|| AppError {
+        msg: format!(
+            "Invalid language `{tag}`, expected {}.",
+            alternatives(&Language::ALL)
+        ),
+        code: exit_codes::INVALID_ARGUMENT,
+    })
+}
+
+/// Parses the path of a file to write to
+fn parse_output_path(path: Option<String>) -> Result<PathBuf, AppError> {
+    path.map(PathBuf::from).ok_or_else(|| AppError {
+        msg: "Missing output path argument.".to_owned(),
+        code: exit_codes::MISSING_ARGUMENT,
+    })
+}
+
+/// Picks the format of a dump file, `--format` takes precedence over the extension
+fn dump_format(path: &Path, format: Option<OutputFormat>) -> Result<OutputFormat, AppError> {
+    match format {
+        Some(OutputFormat::Sarif) => Err(AppError {
+            msg: "SARIF output format is only supported with `--check`.".to_owned(),
+            code: exit_codes::INVALID_ARGUMENT,
+        }),
+        Some(format) => Ok(format),
+        None if path.extension().is_some_and(|e| e == "json") => Ok(OutputFormat::Json),
+        None => Ok(OutputFormat::Ron),
+    }
+}
+
+/// Parses the arguments of `test <directory>`, which only takes the language of diagnostics
+fn parse_test_args(
+    mut args: impl Iterator<Item = String>,
+) -> Result<(ParsedArgs, Option<Language>), AppError> {
+    let dir = args.next().map(PathBuf::from).ok_or_else(|| AppError {
+        msg: "Missing test directory argument.".to_owned(),
+        code: exit_codes::MISSING_ARGUMENT,
+    })?;
+    let mut language = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--lang" => language = Some(parse_language(args.next())?),
+            _ => {
+                return Err(AppError {
+                    msg: format!("Invalid argument `{}`.", arg),
+                    code: exit_codes::INVALID_ARGUMENT,
+                })
+            }
+        }
+    }
+    Ok((ParsedArgs::Test(dir), language))
+}
+
+/// Parses arguments, excluding the executable path.
+/// Returns the language of diagnostics along with the parsed arguments, if one was chosen.
+pub fn parse_args(
+    args: impl Iterator<Item = String>,
+) -> Result<(ParsedArgs, Option<Language>), AppError> {
+    let mut args = args.peekable();
+    if args.next_if(|arg| arg == "test").is_some() {
+        return parse_test_args(args);
+    }
+    let mut interactive = false;
+    let mut eval = None;
+    let mut explain = None;
+    let mut input = None;
+    let mut mode = Mode::Run;
+    let mut format = None;
+    let mut deny_warnings = false;
+    let mut analysis = Analysis::default();
+    let mut lint_all = false;
+    let mut allowed = vec![];
+    let mut language = None;
+    let mut dump_on_error = false;
+    let mut dump_path = None;
+    let mut trace = None;
+    let mut debug = false;
+    let mut profile = false;
+    let mut mem_profile = false;
+    let mut folded_stacks = None;
+    let mut coverage = None;
+    let mut script_args = None;
+    let mut empty = true;
+    while let Some(arg) = args.next() {
+        empty = false;
+        match arg.as_str() {
+            "-i" | "--interactive" => interactive = true,
+            "-f" | "--file" => input = Some(parse_input(args.next())?),
+            "-e" | "--eval" => {
+                eval = Some(args.next().ok_or_else(|| AppError {
+                    msg: "Missing source code argument.".to_owned(),
+                    code: exit_codes::MISSING_ARGUMENT,
+                })?)
+            }
+            "--explain" => {
+                explain = Some(args.next().ok_or_else(|| AppError {
+                    msg: "Missing diagnostic code argument.".to_owned(),
+                    code: exit_codes::MISSING_ARGUMENT,
+                })?)
+            }
+            "--dump-tokens" => mode = Mode::DumpTokens,
+            "--dump-ast" => mode = Mode::DumpAst,
+            "--check" => mode = Mode::Check,
+            "--deny-warnings" => deny_warnings = true,
+            "--typecheck" => analysis.typecheck = true,
+            "--lint" => lint_all = true,
+            "--warn" => analysis.lints = analysis.lints.with(parse_lint(args.next())?),
+            "--allow" => allowed.push(parse_lint(args.next())?),
+            "--format" => format = Some(parse_format(args.next())?),
+            "--lang" => language = Some(parse_language(args.next())?),
+            "--dump-on-error" => dump_on_error = true,
+            "--dump-to" => dump_path = Some(parse_output_path(args.next())?),
+            "--trace" => trace = trace.or(Some(TraceLevel::Calls)),
+            "--trace-expressions" => trace = Some(TraceLevel::Expressions),
+            "--debug" => debug = true,
+            "--profile" => profile = true,
+            "--profile-folded" => folded_stacks = Some(parse_output_path(args.next())?),
+            "--mem-profile" => mem_profile = true,
+            "--coverage" => coverage = Some(parse_output_path(args.next())?),
+            "--" => {
+                script_args = Some(args.by_ref().collect());
+            }
+            _ => {
+                return Err(AppError {
+                    msg: format!("Invalid argument `{}`.", arg),
+                    code: exit_codes::INVALID_ARGUMENT,
+                })
+            }
+        }
+    }
+    if empty {
+        return Ok((ParsedArgs::InstructionManual, language));
+    }
+    if lint_all {
+        analysis.lints = Lints::all();
+    }
+    analysis.lints = allowed.into_iter().fold(analysis.lints, Lints::without);
+    if interactive {
+        return Ok((ParsedArgs::Interactive, language));
+    }
+    if let Some(code) = explain {
+        return Ok((ParsedArgs::Explain(code), language));
+    }
+    if let Some(source) = eval {
+        if input.is_some() || script_args.is_some() || !matches!(mode, Mode::Run) {
+            return Err(AppError {
+                msg: "`--eval` can't be combined with an input file, script arguments or other modes."
+                    .to_owned(),
+                code: exit_codes::INVALID_ARGUMENT,
+            });
+        }
+        return Ok((ParsedArgs::Eval(source), language));
+    }
+    let input = input.ok_or_else(|| AppError {
+        msg: "Missing input file argument.".to_owned(),
+        code: exit_codes::MISSING_ARGUMENT,
+    })?;
+    if let (Mode::DumpTokens | Mode::DumpAst, Some(OutputFormat::Sarif)) = (&mode, format) {
+        return Err(AppError {
+            msg: "SARIF output format is only supported with `--check`.".to_owned(),
+            code: exit_codes::INVALID_ARGUMENT,
+        });
+    }
+    if let (Mode::DumpTokens | Mode::DumpAst | Mode::Check, Some(_)) = (&mode, &script_args) {
+        return Err(AppError {
+            msg: "Script arguments are only supported when running a program.".to_owned(),
+            code: exit_codes::INVALID_ARGUMENT,
+        });
+    }
+    let dump_on_error = match dump_path {
+        Some(path) => {
+            let format = dump_format(&path, format)?;
+            Some(ErrorDump::File(path, format))
+        }
+        None if dump_on_error => Some(ErrorDump::Print),
+        None => None,
+    };
+    let instrumentation = Instrumentation {
+        dump_on_error,
+        trace,
+        debug,
+        profile: profile || folded_stacks.is_some(),
+        folded_stacks,
+        mem_profile,
+        coverage,
+    };
+    if !matches!(mode, Mode::Run) && instrumentation != Instrumentation::default() {
+        return Err(AppError {
+            msg: "Dumping variables, tracing, debugging, profiling and coverage are only supported when running a program."
+                .to_owned(),
+            code: exit_codes::INVALID_ARGUMENT,
+        });
+    }
+    if debug && input == InputType::Standard {
+        return Err(AppError {
+            msg: "The debugger reads commands from standard input, so the program must be read from a file."
+                .to_owned(),
+            code: exit_codes::INVALID_ARGUMENT,
+        });
+    }
+    let parsed = match mode {
+        Mode::Run => ParsedArgs::Run(
+            input,
+            script_args.unwrap_or_default(),
+            analysis,
+            instrumentation,
+        ),
+        Mode::DumpTokens => ParsedArgs::DumpTokens(input, format.unwrap_or(OutputFormat::Ron)),
+        Mode::DumpAst => ParsedArgs::DumpAst(input, format.unwrap_or(OutputFormat::Ron)),
+        Mode::Check => ParsedArgs::Check(
+            input,
+            format.unwrap_or(OutputFormat::Json),
+            deny_warnings,
+            analysis,
+        ),
+    };
+    Ok((parsed, language))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{
+        analysis::linter::{Lint, Lints},
+        interpreter::trace::TraceLevel,
+        locale::Language,
+    };
+
+    use super::{
+        parse_args, Analysis, ErrorDump, InputType, Instrumentation, OutputFormat, ParsedArgs,
+    };
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn parse(arguments: &[&str]) -> Option<ParsedArgs> {
+        parse_args(args(arguments)).ok().map(|(parsed, _)| parsed)
+    }
+
+    #[test]
+    fn manual() {
+        assert_eq!(parse(&[]), Some(ParsedArgs::InstructionManual));
+    }
+
+    #[test]
+    fn run() {
+        assert_eq!(
+            parse(&["-f", "a.txt"]),
+            Some(ParsedArgs::Run(
+                InputType::File(PathBuf::from("a.txt")),
+                vec![],
+                Analysis::default(),
+                Instrumentation::default()
+            ))
+        );
+        assert_eq!(
+            parse(&["--file", "-"]),
+            Some(ParsedArgs::Run(
+                InputType::Standard,
+                vec![],
+                Analysis::default(),
+                Instrumentation::default()
+            ))
+        );
+        assert_eq!(parse_args(args(&["-f"])).err().unwrap().code, 2);
+    }
+
+    #[test]
+    fn eval() {
+        assert_eq!(
+            parse(&["-e", "print(1);"]),
+            Some(ParsedArgs::Eval("print(1);".to_owned()))
+        );
+        assert_eq!(
+            parse(&["--eval", "1 + 2"]),
+            Some(ParsedArgs::Eval("1 + 2".to_owned()))
+        );
+        assert_eq!(parse_args(args(&["-e"])).err().unwrap().code, 2);
+        assert_eq!(
+            parse_args(args(&["-e", "1", "-f", "a.txt"]))
+                .err()
+                .unwrap()
+                .code,
+            3
+        );
+        assert_eq!(
+            parse_args(args(&["-e", "1", "--dump-ast"]))
+                .err()
+                .unwrap()
+                .code,
+            3
+        );
+    }
+
+    #[test]
+    fn script_args() {
+        assert_eq!(
+            parse(&["-f", "a.txt", "--", "b", "-f", "--"]),
+            Some(ParsedArgs::Run(
+                InputType::File(PathBuf::from("a.txt")),
+                vec!["b".to_owned(), "-f".to_owned(), "--".to_owned()],
+                Analysis::default(),
+                Instrumentation::default()
+            ))
+        );
+        assert_eq!(
+            parse_args(args(&["--check", "-f", "a.txt", "--", "b"]))
+                .err()
+                .unwrap()
+                .code,
+            3
+        );
+    }
+
+    #[test]
+    fn dumps() {
+        assert_eq!(
+            parse(&["--dump-tokens", "-f", "-"]),
+            Some(ParsedArgs::DumpTokens(
+                InputType::Standard,
+                OutputFormat::Ron
+            ))
+        );
+        assert_eq!(
+            parse(&["-f", "-", "--dump-ast", "--format", "json"]),
+            Some(ParsedArgs::DumpAst(InputType::Standard, OutputFormat::Json))
+        );
+        assert_eq!(
+            parse_args(args(&["--dump-ast", "--format", "xml", "-f", "-"]))
+                .err()
+                .unwrap()
+                .code,
+            3
+        );
+        assert_eq!(parse_args(args(&["--dump-ast"])).err().unwrap().code, 2);
+    }
+
+    #[test]
+    fn check() {
+        assert_eq!(
+            parse(&["--check", "-f", "-"]),
+            Some(ParsedArgs::Check(
+                InputType::Standard,
+                OutputFormat::Json,
+                false,
+                Analysis::default()
+            ))
+        );
+        assert_eq!(
+            parse(&["--check", "--deny-warnings", "--format", "sarif", "-f", "-"]),
+            Some(ParsedArgs::Check(
+                InputType::Standard,
+                OutputFormat::Sarif,
+                true,
+                Analysis::default()
+            ))
+        );
+        assert_eq!(
+            parse_args(args(&["--dump-ast", "--format", "sarif", "-f", "-"]))
+                .err()
+                .unwrap()
+                .code,
+            3
+        );
+    }
+
+    #[test]
+    fn explain() {
+        assert_eq!(
+            parse(&["--explain", "P0007"]),
+            Some(ParsedArgs::Explain("P0007".to_owned()))
+        );
+        assert_eq!(parse_args(args(&["--explain"])).err().unwrap().code, 2);
+    }
+
+    #[test]
+    fn language() {
+        let language = |arguments: &[&str]| {
+            parse_args(args(arguments))
+                .map(|(_, l)| l)
+                .map_err(|e| e.code)
+        };
+        assert_eq!(language(&["-f", "-"]), Ok(None));
+        assert_eq!(
+            language(&["--lang", "pl", "-f", "-"]),
+            Ok(Some(Language::Polish))
+        );
+        assert_eq!(
+            language(&["-i", "--lang", "en_GB"]),
+            Ok(Some(Language::English))
+        );
+        assert_eq!(language(&["--lang", "de", "-f", "-"]), Err(3));
+        assert_eq!(language(&["-f", "-", "--lang"]), Err(2));
+    }
+
+    #[test]
+    fn dump_on_error() {
+        let dump = |arguments: &[&str]| match parse_args(args(arguments)) {
+            Ok((ParsedArgs::Run(_, _, _, instrumentation), _)) => Ok(instrumentation.dump_on_error),
+            Ok(_) => unreachable!(),
+            Err(e) => Err(e.code),
+        };
+        assert_eq!(dump(&["-f", "-"]), Ok(None));
+        assert_eq!(
+            dump(&["--dump-on-error", "-f", "-"]),
+            Ok(Some(ErrorDump::Print))
+        );
+        assert_eq!(
+            dump(&["-f", "-", "--dump-to", "vars.json"]),
+            Ok(Some(ErrorDump::File(
+                PathBuf::from("vars.json"),
+                OutputFormat::Json
+            )))
+        );
+        assert_eq!(
+            dump(&["-f", "-", "--dump-to", "vars.txt"]),
+            Ok(Some(ErrorDump::File(
+                PathBuf::from("vars.txt"),
+                OutputFormat::Ron
+            )))
+        );
+        assert_eq!(
+            dump(&["-f", "-", "--dump-to", "vars.json", "--format", "ron"]),
+            Ok(Some(ErrorDump::File(
+                PathBuf::from("vars.json"),
+                OutputFormat::Ron
+            )))
+        );
+        assert_eq!(dump(&["-f", "-", "--dump-to"]), Err(2));
+        assert_eq!(
+            dump(&["-f", "-", "--dump-to", "a", "--format", "sarif"]),
+            Err(3)
+        );
+        assert_eq!(
+            parse_args(args(&["--check", "-f", "-", "--dump-on-error"]))
+                .err()
+                .unwrap()
+                .code,
+            3
+        );
+    }
+
+    #[test]
+    fn trace() {
+        let trace = |arguments: &[&str]| match parse_args(args(arguments)) {
+            Ok((ParsedArgs::Run(_, _, _, instrumentation), _)) => Ok(instrumentation.trace),
+            Ok(_) => unreachable!(),
+            Err(e) => Err(e.code),
+        };
+        assert_eq!(trace(&["-f", "-"]), Ok(None));
+        assert_eq!(trace(&["-f", "-", "--trace"]), Ok(Some(TraceLevel::Calls)));
+        assert_eq!(
+            trace(&["--trace-expressions", "--trace", "-f", "-"]),
+            Ok(Some(TraceLevel::Expressions))
+        );
+        assert_eq!(trace(&["--dump-ast", "-f", "-", "--trace"]), Err(3));
+    }
+
+    #[test]
+    fn debug() {
+        assert_eq!(
+            parse(&["--debug", "-f", "a.txt"]),
+            Some(ParsedArgs::Run(
+                InputType::File(PathBuf::from("a.txt")),
+                vec![],
+                Analysis::default(),
+                Instrumentation {
+                    debug: true,
+                    ..Default::default()
+                }
+            ))
+        );
+        assert_eq!(
+            parse_args(args(&["--debug", "-f", "-"]))
+                .err()
+                .unwrap()
+                .code,
+            3
+        );
+    }
+
+    #[test]
+    fn profile() {
+        let profile = |arguments: &[&str]| match parse_args(args(arguments)) {
+            Ok((ParsedArgs::Run(_, _, _, i), _)) => Ok((i.profile, i.folded_stacks)),
+            Ok(_) => unreachable!(),
+            Err(e) => Err(e.code),
+        };
+        assert_eq!(profile(&["-f", "-"]), Ok((false, None)));
+        assert_eq!(profile(&["-f", "-", "--profile"]), Ok((true, None)));
+        assert_eq!(
+            profile(&["--profile-folded", "out.folded", "-f", "-"]),
+            Ok((true, Some(PathBuf::from("out.folded"))))
+        );
+        assert_eq!(profile(&["-f", "-", "--profile-folded"]), Err(2));
+        assert_eq!(profile(&["--check", "-f", "-", "--profile"]), Err(3));
+    }
+
+    #[test]
+    fn test() {
+        assert_eq!(
+            parse(&["test", "scripts"]),
+            Some(ParsedArgs::Test(PathBuf::from("scripts")))
+        );
+        assert_eq!(
+            parse_args(args(&["test", "scripts", "--lang", "pl"])).ok(),
+            Some((
+                ParsedArgs::Test(PathBuf::from("scripts")),
+                Some(Language::Polish)
+            ))
+        );
+        let code = |arguments: &[&str]| parse_args(args(arguments)).err().unwrap().code;
+        assert_eq!(code(&["test"]), 2);
+        assert_eq!(code(&["test", "scripts", "-f", "a.txt"]), 3);
+        assert_eq!(code(&["-f", "a.txt", "test"]), 3);
+    }
+
+    #[test]
+    fn mem_profile() {
+        assert_eq!(
+            parse(&["-f", "-", "--mem-profile"]),
+            Some(ParsedArgs::Run(
+                InputType::Standard,
+                vec![],
+                Analysis::default(),
+                Instrumentation {
+                    mem_profile: true,
+                    ..Default::default()
+                }
+            ))
+        );
+        assert_eq!(
+            parse_args(args(&["--dump-tokens", "-f", "-", "--mem-profile"]))
+                .err()
+                .unwrap()
+                .code,
+            3
+        );
+    }
+
+    #[test]
+    fn coverage() {
+        let coverage = |arguments: &[&str]| match parse_args(args(arguments)) {
+            Ok((ParsedArgs::Run(_, _, _, i), _)) => Ok(i.coverage),
+            Ok(_) => unreachable!(),
+            Err(e) => Err(e.code),
+        };
+        assert_eq!(coverage(&["-f", "-"]), Ok(None));
+        assert_eq!(
+            coverage(&["-f", "-", "--coverage", "out"]),
+            Ok(Some(PathBuf::from("out")))
+        );
+        assert_eq!(coverage(&["-f", "-", "--coverage"]), Err(2));
+        assert_eq!(
+            coverage(&["--check", "--coverage", "out", "-f", "-"]),
+            Err(3)
+        );
+    }
+
+    #[test]
+    fn typecheck() {
+        let analysis = Analysis {
+            typecheck: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            parse(&["--typecheck", "-f", "-"]),
+            Some(ParsedArgs::Run(
+                InputType::Standard,
+                vec![],
+                analysis,
+                Instrumentation::default()
+            ))
+        );
+        assert_eq!(
+            parse(&["--check", "-f", "-", "--typecheck"]),
+            Some(ParsedArgs::Check(
+                InputType::Standard,
+                OutputFormat::Json,
+                false,
+                analysis
+            ))
+        );
+    }
+
+    #[test]
+    fn lints() {
+        let lints = |arguments: &[&str]| match parse_args(args(arguments)) {
+            Ok((ParsedArgs::Check(_, _, _, analysis), _)) => Ok(analysis.lints),
+            Ok(_) => unreachable!(),
+            Err(e) => Err(e.code),
+        };
+        assert_eq!(lints(&["--check", "-f", "-"]), Ok(Lints::default()));
+        assert_eq!(lints(&["--check", "-f", "-", "--lint"]), Ok(Lints::all()));
+        assert_eq!(
+            lints(&[
+                "--allow",
+                "unused_functions",
+                "--check",
+                "--lint",
+                "-f",
+                "-"
+            ]),
+            Ok(Lints::all().without(Lint::UnusedFunctions))
+        );
+        assert_eq!(
+            lints(&["--check", "--warn", "infinite_loops", "-f", "-"]),
+            Ok(Lints::default().with(Lint::InfiniteLoops))
+        );
+        assert_eq!(lints(&["--check", "-f", "-", "--warn", "loops"]), Err(3));
+        assert_eq!(lints(&["--check", "-f", "-", "--allow"]), Err(2));
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(parse_args(args(&["--nope"])).err().unwrap().code, 3);
+    }
+}
@@ -0,0 +1,153 @@
+/// Long-form explanations of diagnostic codes, each in a section starting with
+/// `## <code> <variant>` and containing a wrong and a corrected example
+const EXPLANATIONS: &str = include_str!("explanations.txt");
+
+/// Returns the explanation of a code, ignoring its case
+pub fn explain(code: &str) -> Option<&'static str> {
+    EXPLANATIONS
+        .split("\n## ")
+        .map(|section| section.trim_start_matches("## ").trim_end())
+        .find(|section| {
+            section
+                .split_whitespace()
+                .next()
+                .is_some_and(|c| c.eq_ignore_ascii_case(code))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::linter::LintWarningVariant,
+        diagnostics::variant_name,
+        interpreter::ExecutionErrorVariant,
+        lexer::lexem::LexerWarningVariant,
+        locale,
+        parser::{grammar::DataType, ParserErrorVariant, ParserWarningVariant},
+    };
+
+    use super::{explain, EXPLANATIONS};
+
+    /// Codes of all variants, along with their names
+    fn codes() -> Vec<(&'static str, String)> {
+        let lexer = [
+            LexerWarningVariant::CommentNeverEnds,
+            LexerWarningVariant::CommentTooLong,
+            LexerWarningVariant::StringNeverEnds,
+            LexerWarningVariant::StringTooLong,
+            LexerWarningVariant::IntegerPartTooBig,
+            LexerWarningVariant::DecimalPartTooBig,
+            LexerWarningVariant::IdentifierTooLong,
+            LexerWarningVariant::InvalidEscapeCharacter('a'),
+            LexerWarningVariant::InvalidSequence(String::new()),
+        ];
+        let parser_errors = [
+            ParserErrorVariant::FunctionParameterMissingType,
+            ParserErrorVariant::FunctionMissingIdentifier,
+            ParserErrorVariant::FunctionMissingReturnType,
+            ParserErrorVariant::FunctionMissingBody,
+            ParserErrorVariant::IfMissingCondition,
+            ParserErrorVariant::IfMissingTrueBranch,
+            ParserErrorVariant::IfMissingFalseBranch,
+            ParserErrorVariant::WhileLoopMissingCondition,
+            ParserErrorVariant::WhileLoopMissingBody,
+            ParserErrorVariant::ForLoopMissingVariable,
+            ParserErrorVariant::ForLoopMissingProvider,
+            ParserErrorVariant::ForLoopMissingBody,
+            ParserErrorVariant::InvalidBracketExpression,
+            ParserErrorVariant::ListRangeAccessIncomplete,
+            ParserErrorVariant::ListAccessEmpty,
+            ParserErrorVariant::UnaryOperatorMissingExpression,
+            ParserErrorVariant::BinaryOperatorMissingRHS,
+            ParserErrorVariant::AssignmentMissingExpression,
+            ParserErrorVariant::VariableDeclarationMissingType,
+            ParserErrorVariant::VariableDeclarationMissingIdentifier,
+            ParserErrorVariant::VariableDeclarationMissingExpression,
+            ParserErrorVariant::TooManyWarnings,
+            ParserErrorVariant::DuplicateParameter(String::new()),
+            ParserErrorVariant::FunctionAlredayExists(String::new()),
+            ParserErrorVariant::UnexpectedToken,
+            ParserErrorVariant::ExpectedSemicolon,
+        ];
+        let parser_warnings = [
+            ParserWarningVariant::ExpectedExpression,
+            ParserWarningVariant::MissingOpeningRoundBracket,
+            ParserWarningVariant::MissingClosingRoundBracket,
+            ParserWarningVariant::MissingClosingSquareBracket,
+            ParserWarningVariant::MissingClosingCurlyBracket,
+            ParserWarningVariant::MissingColon,
+            ParserWarningVariant::VariableDeclarationMissingEqualsSign,
+            ParserWarningVariant::VariableDeclarationMissingTypeSeparator,
+            ParserWarningVariant::ForLoopMissingInKeyword,
+            ParserWarningVariant::ExpectedParameter,
+        ];
+        let execution = [
+            ExecutionErrorVariant::VariableDoesNotExist(String::new()),
+            ExecutionErrorVariant::VariableAlreadyExists(String::new()),
+            ExecutionErrorVariant::FunctionDoesNotExist(String::new()),
+            ExecutionErrorVariant::UnsupportedBinaryOperation,
+            ExecutionErrorVariant::UnsupportedUnaryOperation,
+            ExecutionErrorVariant::UnsupportedListAccess,
+            ExecutionErrorVariant::NonIntegerIndex,
+            ExecutionErrorVariant::IndexOutOfBounds {
+                index: 0,
+                length: 0,
+            },
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 0,
+                given: 0,
+            },
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![],
+                actual: DataType::None,
+            },
+            ExecutionErrorVariant::CastFailed,
+            ExecutionErrorVariant::DivisionByZero,
+            ExecutionErrorVariant::Overflow,
+            ExecutionErrorVariant::MissingMainFunction,
+            ExecutionErrorVariant::ExpectedIdentifier,
+            ExecutionErrorVariant::ExpectedSemicolon,
+            ExecutionErrorVariant::Aborted,
+        ];
+        let lints = [
+            LintWarningVariant::UnusedVariable(String::new()),
+            LintWarningVariant::UnusedParameter(String::new()),
+            LintWarningVariant::UnusedFunction(String::new()),
+            LintWarningVariant::UnreachableCode,
+            LintWarningVariant::InfiniteLoop,
+            LintWarningVariant::ShadowedVariable(String::new()),
+            LintWarningVariant::ConstantCondition(true),
+            LintWarningVariant::UnusedLoopResult,
+        ];
+        let mut codes = vec![];
+        codes.extend(lexer.iter().map(|v| (v.code(), variant_name(v))));
+        codes.extend(parser_errors.iter().map(|v| (v.code(), variant_name(v))));
+        codes.extend(parser_warnings.iter().map(|v| (v.code(), variant_name(v))));
+        codes.extend(execution.iter().map(|v| (v.code(), variant_name(v))));
+        codes.extend(lints.iter().map(|v| (v.code(), variant_name(v))));
+        codes
+    }
+
+    #[test]
+    fn every_code_explained() {
+        let codes = codes();
+        for (code, name) in &codes {
+            let explanation = explain(code).unwrap_or_else(|| panic!("{code} isn't explained"));
+            assert!(explanation.starts_with(&format!("{code} {name}\n")));
+            assert!(explanation.contains("\nWrong:\n"));
+            assert!(explanation.contains("\nCorrect:\n"));
+            assert!(locale::text(code).is_some(), "{code} has no message");
+        }
+        assert_eq!(EXPLANATIONS.matches("## ").count(), codes.len());
+    }
+
+    #[test]
+    fn lookup() {
+        assert!(explain("p0007")
+            .unwrap()
+            .starts_with("P0007 IfMissingFalseBranch\n\nThe `else` keyword"));
+        assert!(explain("A0008").unwrap().ends_with("{ print(arg); };"));
+        assert_eq!(explain("P9999"), None);
+        assert_eq!(explain(""), None);
+    }
+}
@@ -0,0 +1,470 @@
+pub mod explanations;
+pub mod render;
+pub mod sarif;
+
+use std::fmt::{Debug, Display};
+
+use serde::Serialize;
+
+use crate::{
+    analysis::{linter::LintWarning, resolver::ResolverError, type_checker::TypeError},
+    interpreter::{alternatives, ExecutionError},
+    lexer::lexem::LexerWarning,
+    locale,
+    parser::{
+        directives::{applicable, Directive, Level},
+        position::Position,
+        Parsed, ParserError, ParserWarning,
+    },
+};
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => f.write_str(&locale::message("error", &[])),
+            Severity::Warning => f.write_str(&locale::message("warning", &[])),
+        }
+    }
+}
+
+/// Layer of the application which reported a diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Origin {
+    Lexer,
+    Parser,
+    Resolver,
+    TypeChecker,
+    Linter,
+    Interpreter,
+}
+
+/// Common, machine-readable representation of lexer, parser and runtime problems
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub origin: Origin,
+    pub variant: String,
+    /// Stable code of the variant, explained by `--explain`
+    pub code: String,
+    pub start: Position,
+    pub stop: Position,
+    pub message: String,
+    pub hint: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
+}
+
+/// Returns the name of an enum variant without its payload
+fn variant_name(variant: &impl Debug) -> String {
+    let name = format!("{:?}", variant);
+    name.split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_owned()
+}
+
+impl From<&LexerWarning> for Diagnostic {
+    fn from(w: &LexerWarning) -> Self {
+        Self {
+            severity: Severity::Warning,
+            origin: Origin::Lexer,
+            variant: variant_name(&w.warning),
+            code: w.warning.code().to_owned(),
+            start: w.start.into(),
+            stop: w.end.into(),
+            message: w.warning.to_string(),
+            hint: w.warning.hint().to_owned(),
+            notes: vec![],
+        }
+    }
+}
+
+impl From<&ParserWarning> for Diagnostic {
+    fn from(w: &ParserWarning) -> Self {
+        Self {
+            severity: Severity::Warning,
+            origin: Origin::Parser,
+            variant: variant_name(&w.warning),
+            code: w.warning.code().to_owned(),
+            start: w.start,
+            stop: w.stop,
+            message: w.warning.to_string(),
+            hint: w.warning.hint().to_owned(),
+            notes: vec![],
+        }
+    }
+}
+
+impl From<&LintWarning> for Diagnostic {
+    fn from(w: &LintWarning) -> Self {
+        Self {
+            severity: Severity::Warning,
+            origin: Origin::Linter,
+            variant: variant_name(&w.warning),
+            code: w.warning.code().to_owned(),
+            start: w.start,
+            stop: w.stop,
+            message: w.warning.to_string(),
+            hint: w.warning.hint().to_owned(),
+            notes: vec![locale::message("lint", &[&w.warning.lint()])],
+        }
+    }
+}
+
+impl From<&ParserError> for Diagnostic {
+    fn from(e: &ParserError) -> Self {
+        Self {
+            severity: Severity::Error,
+            origin: Origin::Parser,
+            variant: variant_name(&e.error),
+            code: e.error.code().to_owned(),
+            start: e.pos,
+            stop: e.pos,
+            message: e.error.to_string(),
+            hint: e.error.hint().to_owned(),
+            notes: vec![],
+        }
+    }
+}
+
+/// Hint listing similar names, empty when there are none
+fn suggestion_hint(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        locale::message("suggestion", &[&alternatives(suggestions)])
+    }
+}
+
+impl From<&ResolverError> for Diagnostic {
+    fn from(e: &ResolverError) -> Self {
+        Self {
+            severity: Severity::Error,
+            origin: Origin::Resolver,
+            variant: variant_name(&e.variant),
+            code: e.variant.code().to_owned(),
+            start: e.span.start,
+            stop: e.span.stop,
+            message: e.variant.to_string(),
+            hint: suggestion_hint(&e.suggestions),
+            notes: vec![],
+        }
+    }
+}
+
+impl From<&TypeError> for Diagnostic {
+    fn from(e: &TypeError) -> Self {
+        Self {
+            severity: Severity::Error,
+            origin: Origin::TypeChecker,
+            variant: variant_name(&e.variant),
+            code: e.variant.code().to_owned(),
+            start: e.span.start,
+            stop: e.span.stop,
+            message: e.variant.to_string(),
+            hint: String::new(),
+            notes: vec![],
+        }
+    }
+}
+
+/// Runtime errors without a position have both positions at `Ln 0, Col 0`
+impl From<&ExecutionError> for Diagnostic {
+    fn from(e: &ExecutionError) -> Self {
+        let span = e.span.unwrap_or_default();
+        Self {
+            severity: Severity::Error,
+            origin: Origin::Interpreter,
+            variant: variant_name(&e.variant),
+            code: e.variant.code().to_owned(),
+            start: span.start,
+            stop: span.stop,
+            message: e.variant.to_string(),
+            hint: suggestion_hint(&e.details.suggestions),
+            notes: e.details.frames.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+}
+
+/// Gathers all problems of lexing and parsing, ordered by position
+pub fn collect<T>(parsed: &Parsed<T>) -> Vec<Diagnostic> {
+    collect_with(parsed, vec![])
+}
+
+/// Gathers all problems of lexing and parsing along with the ones found in the parsed program,
+/// ordered by position. Warnings are allowed or denied by the directives found in the source.
+pub fn collect_with<T>(parsed: &Parsed<T>, analysed: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    let diagnostics = parsed
+        .lexer_warnings
+        .iter()
+        .map(Diagnostic::from)
+        .chain(parsed.parser_warnings.iter().map(Diagnostic::from))
+        .chain(parsed.errors.iter().map(Diagnostic::from))
+        .chain(analysed)
+        .collect();
+    let mut diagnostics = apply_directives(&parsed.directives, diagnostics);
+    diagnostics.sort_by_key(|d| (d.start, d.severity));
+    diagnostics
+}
+
+/// Drops allowed warnings and turns denied ones into errors, errors are never affected
+fn apply_directives(directives: &[Directive], diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+    diagnostics
+        .into_iter()
+        .filter_map(|mut d| {
+            if d.severity == Severity::Error {
+                return Some(d);
+            }
+            match applicable(directives, &[&d.variant, &d.code], d.start) {
+                Some(directive) if directive.level == Level::Allow => None,
+                Some(directive) => {
+                    d.severity = Severity::Error;
+                    d.notes.push(locale::message("denied", &[&directive.pos]));
+                    Some(d)
+                }
+                None => Some(d),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analysis::linter::{LintWarning, LintWarningVariant},
+        interpreter::{ExecutionError, ExecutionErrorVariant},
+        lexer::lexem::{LexerWarning, LexerWarningVariant},
+        locale::{set_language, Language},
+        parser::{
+            grammar::DataType,
+            position::{Position, Span},
+            Parsed, ParserError, ParserErrorVariant, ParserWarning, ParserWarningVariant,
+        },
+    };
+
+    use super::{collect, Diagnostic, Origin, Severity};
+
+    use crate::parser::parse_source;
+
+    #[test]
+    fn lexer_warning() {
+        let warning = LexerWarning {
+            start: (2, 5).into(),
+            end: (2, 8).into(),
+            warning: LexerWarningVariant::InvalidSequence("#$@".to_owned()),
+        };
+        assert_eq!(
+            Diagnostic::from(&warning),
+            Diagnostic {
+                severity: Severity::Warning,
+                origin: Origin::Lexer,
+                variant: "InvalidSequence".to_owned(),
+                code: "L0009".to_owned(),
+                start: Position::new(2, 5),
+                stop: Position::new(2, 8),
+                message: "invalid character sequence `#$@`".to_owned(),
+                hint: "remove the characters or put them in a string".to_owned(),
+                notes: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn parser_error() {
+        let error = ParserError {
+            error: ParserErrorVariant::VariableDeclarationMissingType,
+            pos: Position::new(4, 11),
+        };
+        assert_eq!(
+            Diagnostic::from(&error),
+            Diagnostic {
+                severity: Severity::Error,
+                origin: Origin::Parser,
+                variant: "VariableDeclarationMissingType".to_owned(),
+                code: "P0019".to_owned(),
+                start: Position::new(4, 11),
+                stop: Position::new(4, 11),
+                message: "variable declaration is missing a type".to_owned(),
+                hint: "add a type after the variable name, e.g. `let a: int = 1;`".to_owned(),
+                notes: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn lint_warning() {
+        let warning = LintWarning {
+            warning: LintWarningVariant::UnusedVariable("a".to_owned()),
+            start: Position::new(3, 5),
+            stop: Position::new(3, 20),
+        };
+        assert_eq!(
+            Diagnostic::from(&warning),
+            Diagnostic {
+                severity: Severity::Warning,
+                origin: Origin::Linter,
+                variant: "UnusedVariable".to_owned(),
+                code: "A0001".to_owned(),
+                start: Position::new(3, 5),
+                stop: Position::new(3, 20),
+                message: "unused variable `a`".to_owned(),
+                hint: "remove it or prefix its name with `_`".to_owned(),
+                notes: vec!["reported by the `unused_variables` lint".to_owned()],
+            }
+        );
+    }
+
+    #[test]
+    fn execution_error() {
+        let error = ExecutionError::new(ExecutionErrorVariant::DivisionByZero)
+            .at(Span::new(Position::new(2, 5), Position::new(2, 10)))
+            .called_from("f", Span::new(Position::new(5, 5), Position::new(5, 6)))
+            .in_main();
+        assert_eq!(
+            Diagnostic::from(&error),
+            Diagnostic {
+                severity: Severity::Error,
+                origin: Origin::Interpreter,
+                variant: "DivisionByZero".to_owned(),
+                code: "R0012".to_owned(),
+                start: Position::new(2, 5),
+                stop: Position::new(2, 10),
+                message: "division by zero".to_owned(),
+                hint: String::new(),
+                notes: vec![
+                    "in `f` called at Ln 5, Col 5".to_owned(),
+                    "in `main`".to_owned()
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn execution_error_suggestions() {
+        let mut error = ExecutionError::new(ExecutionErrorVariant::FunctionDoesNotExist(
+            "lenght".to_owned(),
+        ));
+        error.details.suggestions = vec!["length".to_owned(), "lengths".to_owned()];
+        let diagnostic = Diagnostic::from(&error);
+        assert_eq!(diagnostic.message, "function `lenght` does not exist");
+        assert_eq!(diagnostic.hint, "did you mean `length` or `lengths`?");
+    }
+
+    #[test]
+    fn ordered() {
+        let parsed: Parsed<()> = Parsed {
+            result: None,
+            errors: vec![
+                ParserError {
+                    error: ParserErrorVariant::VariableDeclarationMissingType,
+                    pos: Position::new(4, 11),
+                },
+                ParserError {
+                    error: ParserErrorVariant::FunctionMissingBody,
+                    pos: Position::new(2, 7),
+                },
+            ],
+            parser_warnings: vec![ParserWarning {
+                warning: ParserWarningVariant::MissingColon,
+                start: Position::new(3, 1),
+                stop: Position::new(3, 2),
+            }],
+            lexer_warnings: vec![LexerWarning {
+                start: (5, 1).into(),
+                end: (5, 2).into(),
+                warning: LexerWarningVariant::StringNeverEnds,
+            }],
+            directives: vec![],
+        };
+        let diagnostics = collect(&parsed);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| &d.variant[..])
+                .collect::<Vec<_>>(),
+            vec![
+                "FunctionMissingBody",
+                "MissingColon",
+                "VariableDeclarationMissingType",
+                "StringNeverEnds"
+            ]
+        );
+    }
+
+    #[test]
+    fn localized() {
+        set_language(Language::Polish);
+        let source = "fn main() {
+    let a int = 1; // deny(P0108)
+}";
+        let diagnostics = collect(&parse_source(source, |p| p.parse()));
+        assert_eq!(
+            diagnostics[0].message,
+            "deklaracja zmiennej nie ma separatora typu `:`"
+        );
+        assert_eq!(
+            diagnostics[0].hint,
+            "oddziel nazwę od typu za pomocą `:`, np. `let a: int = 1;`"
+        );
+        assert_eq!(
+            diagnostics[0].notes,
+            vec!["zabronione przez dyrektywę (w. 2, kol. 20)".to_owned()]
+        );
+        let error = ExecutionError::new(ExecutionErrorVariant::InvalidType {
+            expected: vec![DataType::Integer, DataType::Float],
+            actual: DataType::String,
+        })
+        .at(Span::new(Position::new(2, 5), Position::new(2, 10)))
+        .called_from("f", Span::new(Position::new(5, 5), Position::new(5, 6)));
+        let diagnostic = Diagnostic::from(&error);
+        assert_eq!(
+            diagnostic.message,
+            "nieprawidłowy typ, oczekiwano `int` lub `float`, otrzymano `string`"
+        );
+        assert_eq!(diagnostic.notes, vec!["w `f` wywołanej (w. 5, kol. 5)"]);
+    }
+
+    #[test]
+    fn directives() {
+        let source = "fn main() {
+    let a int = 1; // allow(VariableDeclarationMissingTypeSeparator)
+    let b int = 2;
+    // deny(all)
+    let c int = 3 $;
+}";
+        let diagnostics = collect(&parse_source(source, |p| p.parse()));
+        let reported: Vec<(&str, usize, Severity)> = diagnostics
+            .iter()
+            .map(|d| (&d.variant[..], d.start.row, d.severity))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    "VariableDeclarationMissingTypeSeparator",
+                    3,
+                    Severity::Warning
+                ),
+                (
+                    "VariableDeclarationMissingTypeSeparator",
+                    5,
+                    Severity::Error
+                ),
+                ("InvalidSequence", 5, Severity::Error),
+            ]
+        );
+        assert_eq!(
+            diagnostics[1].notes,
+            vec!["denied by the directive at Ln 4, Col 5".to_owned()]
+        );
+    }
+}
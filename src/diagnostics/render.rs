@@ -0,0 +1,283 @@
+use std::{
+    env,
+    io::{stderr, IsTerminal},
+};
+
+use crate::locale;
+
+use super::{Diagnostic, Severity};
+
+/// ANSI escape sequences used for highlighting
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+
+/// Whether diagnostics printed to the standard error output stream should be colored
+pub fn use_colors() -> bool {
+    stderr().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+/// Wraps text in an escape sequence, if colors are enabled
+fn paint(text: &str, style: &str, color: bool) -> String {
+    if color {
+        format!("{style}{text}{RESET}")
+    } else {
+        text.to_owned()
+    }
+}
+
+/// Underlines columns `[from, to)` of a line, keeping tabs so the carets stay aligned
+fn underline(line: &str, from: usize, to: usize) -> String {
+    let padding: String = line
+        .chars()
+        .take(from.saturating_sub(1))
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let width = to.saturating_sub(from).max(1);
+    format!("{padding}{}", "^".repeat(width))
+}
+
+/// Renders a diagnostic with the source lines it spans, rustc style:
+///
+/// ```text
+/// warning[P0106]: missing colon `:`
+///  --> script.txt:3:5
+///   |
+/// 3 |     a int
+///   |       ^^^
+///   = hint: add `:`
+/// ```
+///
+/// Spans of more than two lines show only the first and the last one, separated by `...`.
+/// Diagnostics without a position (at `Ln 0`) only point at the file.
+pub fn render(diagnostic: &Diagnostic, source: &str, uri: &str, color: bool) -> String {
+    let (start, stop) = (diagnostic.start, diagnostic.stop);
+    let lines: Vec<&str> = source.lines().collect();
+    // Spans end exclusively, a span ending at the start of a line doesn't cover it
+    let last_row = if stop.row > start.row && stop.col <= 1 {
+        stop.row - 1
+    } else {
+        stop.row.max(start.row)
+    };
+    let gutter = " ".repeat(last_row.to_string().len());
+    let bar = paint("|", BLUE, color);
+    let style = match diagnostic.severity {
+        Severity::Error => RED,
+        Severity::Warning => YELLOW,
+    };
+
+    let mut out = format!(
+        "{}{}\n",
+        paint(
+            &format!("{}[{}]", diagnostic.severity, diagnostic.code),
+            style,
+            color
+        ),
+        paint(&format!(": {}", diagnostic.message), BOLD, color)
+    );
+    if start.row == 0 {
+        out += &format!("{gutter}{} {uri}\n", paint("-->", BLUE, color));
+    } else {
+        out += &format!(
+            "{gutter}{} {uri}:{}:{}\n",
+            paint("-->", BLUE, color),
+            start.row,
+            start.col
+        );
+        out += &format!("{gutter} {bar}\n");
+        // Long spans only show their first and last line, like rustc does
+        let rows = if last_row > start.row + 1 {
+            vec![start.row, last_row]
+        } else {
+            (start.row..=last_row).collect()
+        };
+        for row in rows {
+            if row == last_row && row > start.row + 1 {
+                out += &format!("{}\n", paint("...", BLUE, color));
+            }
+            let line = lines.get(row - 1).copied().unwrap_or_default();
+            let from = if row == start.row { start.col } else { 1 };
+            let to = if row == stop.row {
+                stop.col
+            } else {
+                line.chars().count() + 1
+            };
+            out += &format!(
+                "{} {bar} {line}\n",
+                paint(&format!("{row:>width$}", width = gutter.len()), BLUE, color)
+            );
+            out += &format!(
+                "{gutter} {bar} {}\n",
+                paint(&underline(line, from, to), style, color)
+            );
+        }
+    }
+    for note in &diagnostic.notes {
+        out += &format!(
+            "{gutter} {} {}: {note}\n",
+            paint("=", BLUE, color),
+            locale::message("note", &[])
+        );
+    }
+    if !diagnostic.hint.is_empty() {
+        out += &format!(
+            "{gutter} {} {}\n",
+            paint("=", BLUE, color),
+            paint(
+                &format!("{}: {}", locale::message("hint", &[]), diagnostic.hint),
+                CYAN,
+                color
+            )
+        );
+    }
+    out
+}
+
+/// Prints diagnostics to the standard error output stream
+pub fn eprint_all(diagnostics: &[Diagnostic], source: &str, uri: &str) {
+    let color = use_colors();
+    for diagnostic in diagnostics {
+        eprintln!("{}", render(diagnostic, source, uri, color));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        diagnostics::{Diagnostic, Origin, Severity},
+        locale::{set_language, Language},
+        parser::position::Position,
+    };
+
+    use super::render;
+
+    fn diagnostic(severity: Severity, start: (usize, usize), stop: (usize, usize)) -> Diagnostic {
+        Diagnostic {
+            severity,
+            origin: Origin::Parser,
+            variant: "MissingColon".to_owned(),
+            code: "P0106".to_owned(),
+            start: Position::new(start.0, start.1),
+            stop: Position::new(stop.0, stop.1),
+            message: "missing colon `:`".to_owned(),
+            hint: "add `:`".to_owned(),
+            notes: vec![],
+        }
+    }
+
+    #[test]
+    fn single_line() {
+        let source = "fn main() {\n    let a int = 1;\n}";
+        let rendered = render(
+            &diagnostic(Severity::Warning, (2, 11), (2, 14)),
+            source,
+            "a.txt",
+            false,
+        );
+        assert_eq!(
+            rendered,
+            "warning[P0106]: missing colon `:`\n --> a.txt:2:11\n  |\n2 |     let a int = 1;\n  |           ^^^\n  = hint: add `:`\n"
+        );
+    }
+
+    #[test]
+    fn point() {
+        let source = "let";
+        let rendered = render(
+            &diagnostic(Severity::Error, (1, 4), (1, 4)),
+            source,
+            "a.txt",
+            false,
+        );
+        assert_eq!(
+            rendered,
+            "error[P0106]: missing colon `:`\n --> a.txt:1:4\n  |\n1 | let\n  |    ^\n  = hint: add `:`\n"
+        );
+    }
+
+    #[test]
+    fn multi_line() {
+        let source = "a\n\"bc\nde\"\n";
+        let rendered = render(
+            &diagnostic(Severity::Warning, (2, 1), (3, 4)),
+            source,
+            "a.txt",
+            false,
+        );
+        assert_eq!(
+            rendered,
+            "warning[P0106]: missing colon `:`\n --> a.txt:2:1\n  |\n2 | \"bc\n  | ^^^\n3 | de\"\n  | ^^^\n  = hint: add `:`\n"
+        );
+    }
+
+    #[test]
+    fn long_span() {
+        let source = "while true {\n    a;\n    b;\n    c;\n}\n";
+        let rendered = render(
+            &diagnostic(Severity::Warning, (1, 1), (5, 2)),
+            source,
+            "a.txt",
+            false,
+        );
+        assert_eq!(
+            rendered,
+            "warning[P0106]: missing colon `:`\n --> a.txt:1:1\n  |\n1 | while true {\n  | ^^^^^^^^^^^^\n...\n5 | }\n  | ^\n  = hint: add `:`\n"
+        );
+    }
+
+    #[test]
+    fn tabs_and_missing_lines() {
+        let source = "\tab";
+        let rendered = render(
+            &diagnostic(Severity::Warning, (1, 2), (1, 3)),
+            source,
+            "a.txt",
+            false,
+        );
+        assert!(rendered.contains("1 | \tab\n  | \t^\n"));
+        let rendered = render(
+            &diagnostic(Severity::Error, (5, 1), (5, 1)),
+            source,
+            "a.txt",
+            false,
+        );
+        assert!(rendered.contains("5 | \n  | ^\n"));
+    }
+
+    #[test]
+    fn notes_without_position() {
+        let mut diagnostic = diagnostic(Severity::Error, (0, 0), (0, 0));
+        diagnostic.hint.clear();
+        diagnostic.notes = vec!["in `main`".to_owned()];
+        assert_eq!(
+            render(&diagnostic, "a", "a.txt", false),
+            "error[P0106]: missing colon `:`\n --> a.txt\n  = note: in `main`\n"
+        );
+    }
+
+    #[test]
+    fn localized() {
+        set_language(Language::Polish);
+        let mut diagnostic = diagnostic(Severity::Error, (0, 0), (0, 0));
+        diagnostic.notes = vec!["w `main`".to_owned()];
+        assert_eq!(
+            render(&diagnostic, "a", "a.txt", false),
+            "błąd[P0106]: missing colon `:`\n --> a.txt\n  = uwaga: w `main`\n  = wskazówka: add `:`\n"
+        );
+    }
+
+    #[test]
+    fn colors() {
+        let rendered = render(
+            &diagnostic(Severity::Error, (1, 1), (1, 2)),
+            "a",
+            "a.txt",
+            true,
+        );
+        assert!(rendered.starts_with("\x1b[1;31merror[P0106]\x1b[0m"));
+        assert!(rendered.contains("\x1b[1;36mhint: add `:`\x1b[0m"));
+    }
+}
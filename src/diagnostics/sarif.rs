@@ -0,0 +1,112 @@
+use serde_json::{json, Value};
+
+use super::{Diagnostic, Severity};
+
+/// Version of the SARIF specification the log conforms to
+const SARIF_VERSION: &str = "2.1.0";
+
+/// Location of the SARIF schema
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Converts a single diagnostic into a SARIF result object
+fn result(diagnostic: &Diagnostic, uri: &str) -> Value {
+    let level = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    };
+    json!({
+        "ruleId": diagnostic.code,
+        "level": level,
+        "message": { "text": diagnostic.message },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": { "uri": uri },
+                "region": {
+                    "startLine": diagnostic.start.row,
+                    "startColumn": diagnostic.start.col,
+                    "endLine": diagnostic.stop.row,
+                    "endColumn": diagnostic.stop.col,
+                }
+            }
+        }]
+    })
+}
+
+/// Creates a SARIF log with a single run of the interpreter
+pub fn to_sarif(diagnostics: &[Diagnostic], uri: &str) -> Value {
+    let mut rules: Vec<(&str, &str)> = diagnostics
+        .iter()
+        .map(|d| (&d.code[..], &d.variant[..]))
+        .collect();
+    rules.sort_unstable();
+    rules.dedup();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": SARIF_VERSION,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules
+                        .iter()
+                        .map(|(id, name)| json!({ "id": id, "name": name }))
+                        .collect::<Vec<_>>(),
+                }
+            },
+            "results": diagnostics.iter().map(|d| result(d, uri)).collect::<Vec<_>>(),
+        }]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{
+        diagnostics::{Diagnostic, Origin, Severity},
+        parser::position::Position,
+    };
+
+    use super::to_sarif;
+
+    #[test]
+    fn log() {
+        let diagnostics = vec![Diagnostic {
+            severity: Severity::Warning,
+            origin: Origin::Parser,
+            variant: "MissingColon".to_owned(),
+            code: "P0106".to_owned(),
+            start: Position::new(3, 1),
+            stop: Position::new(3, 2),
+            message: "missing colon `:`".to_owned(),
+            hint: "add `:`".to_owned(),
+            notes: vec![],
+        }];
+        let log = to_sarif(&diagnostics, "script.txt");
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(
+            log["runs"][0]["tool"]["driver"]["rules"],
+            json!([{ "id": "P0106", "name": "MissingColon" }])
+        );
+        assert_eq!(
+            log["runs"][0]["results"][0],
+            json!({
+                "ruleId": "P0106",
+                "level": "warning",
+                "message": { "text": "missing colon `:`" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "script.txt" },
+                        "region": {
+                            "startLine": 3,
+                            "startColumn": 1,
+                            "endLine": 3,
+                            "endColumn": 2,
+                        }
+                    }
+                }]
+            })
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use std::{
+    fs, io,
+    io::Cursor,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    diagnostics::Diagnostic,
+    lexer::{lexem::LexemType, Lexer},
+};
+
+/// Extension of the scripts
+const SCRIPT: &str = "txt";
+
+/// Extension of the files holding the expected output of the scripts next to them
+const SIDECAR: &str = "out";
+
+/// What a script is expected to print and how it's expected to end
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Expectation {
+    pub output: Vec<String>,
+    /// Variant name or code of the error the script is expected to fail with
+    pub error: Option<String>,
+}
+
+impl Expectation {
+    /// Reads the expectation from the comments of the source.
+    /// Each `// expect: <line>` comment adds a line of the output, in the order of the source,
+    /// unless the whole output is given by a sidecar file.
+    /// `// expect-error: <variant or code>` expects the script to fail.
+    pub fn parse(source: &str, sidecar: Option<&str>) -> Self {
+        let mut expectation = Self::default();
+        let mut lexer = Lexer::new_with_defaults(Cursor::new(source.to_owned().into_bytes()));
+        for lexem in lexer.all() {
+            let LexemType::Comment(comment) = lexem.lexem_type else {
+                continue;
+            };
+            let comment = comment.trim_start();
+            if let Some(line) = comment.strip_prefix("expect:") {
+                let line = line.strip_prefix(' ').unwrap_or(line);
+                expectation.output.push(line.trim_end().to_owned());
+            } else if let Some(error) = comment.strip_prefix("expect-error:") {
+                expectation.error = Some(error.trim().to_owned());
+            }
+        }
+        if let Some(sidecar) = sidecar {
+            expectation.output = sidecar.lines().map(str::to_owned).collect();
+        }
+        expectation
+    }
+
+    /// Reads the expectation of a script, along with the sidecar file next to it if there is one
+    pub fn read(script: &Path, source: &str) -> Self {
+        let sidecar = fs::read_to_string(script.with_extension(SIDECAR)).ok();
+        Self::parse(source, sidecar.as_deref())
+    }
+
+    /// Compares the output and the error of a script with the expected ones,
+    /// returning a description of every difference
+    pub fn compare(&self, output: &str, error: Option<&Diagnostic>) -> Result<(), String> {
+        let mut problems = vec![];
+        match (&self.error, error) {
+            (Some(expected), Some(error))
+                if expected == &error.variant || expected.eq_ignore_ascii_case(&error.code) => {}
+            (Some(expected), Some(error)) => problems.push(format!(
+                "expected error `{expected}`, got {}",
+                describe(error)
+            )),
+            (Some(expected), None) => problems.push(format!(
+                "expected error `{expected}`, but the script succeeded"
+            )),
+            (None, Some(error)) => problems.push(format!("unexpected {}", describe(error))),
+            (None, None) => {}
+        }
+        let actual: Vec<String> = output.lines().map(str::to_owned).collect();
+        if actual != self.output {
+            problems.push(format!("output differs\n{}", diff(&self.output, &actual)));
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems.join("\n"))
+        }
+    }
+}
+
+/// Error along with its position and message
+fn describe(error: &Diagnostic) -> String {
+    format!(
+        "error `{}` ({}) at {}: {}",
+        error.variant, error.code, error.start, error.message
+    )
+}
+
+/// Lines of both outputs, the ones only expected prefixed with `-`, the ones only printed with `+`
+pub fn diff(expected: &[String], actual: &[String]) -> String {
+    // Lengths of the longest common subsequences of the remaining lines
+    let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = if expected[i] == actual[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut lines = vec!["--- expected".to_owned(), "+++ actual".to_owned()];
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            lines.push(format!("  {}", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1])
+        {
+            lines.push(format!("- {}", expected[i]));
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", actual[j]));
+            j += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+/// Finds all scripts in a directory and its subdirectories, in alphabetical order.
+/// Only files with the script extension count, so sidecars and other files are skipped.
+pub fn scripts(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut found = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            found.extend(scripts(&path)?);
+        } else if path.extension().is_some_and(|e| e == SCRIPT) {
+            found.push(path);
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use crate::{
+        diagnostics::{Diagnostic, Origin, Severity},
+        parser::position::Position,
+    };
+
+    use super::{diff, scripts, Expectation};
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(str::to_owned).collect()
+    }
+
+    fn error(variant: &str, code: &str) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            origin: Origin::Interpreter,
+            variant: variant.to_owned(),
+            code: code.to_owned(),
+            start: Position::new(3, 5),
+            stop: Position::new(3, 10),
+            message: "division by zero".to_owned(),
+            hint: String::new(),
+            notes: vec![],
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let source = "fn main() {
+    print(\"a\");  // expect: a
+    // expect:
+    print(1 / 0); /* expect-error: DivisionByZero */
+    // expect:   b // c
+    print(\"// expect: not a comment\");
+}";
+        assert_eq!(
+            Expectation::parse(source, None),
+            Expectation {
+                output: lines("a\n\n  b // c"),
+                error: Some("DivisionByZero".to_owned())
+            }
+        );
+        assert_eq!(
+            Expectation::parse(source, Some("x\ny\n")).output,
+            lines("x\ny")
+        );
+        assert_eq!(Expectation::parse("", None), Expectation::default());
+    }
+
+    #[test]
+    fn compare() {
+        let expectation = Expectation {
+            output: lines("1\n2"),
+            error: None,
+        };
+        assert_eq!(expectation.compare("1\n2\n", None), Ok(()));
+        assert_eq!(
+            expectation.compare("1\n3\n", None),
+            Err("output differs\n--- expected\n+++ actual\n  1\n- 2\n+ 3".to_owned())
+        );
+        assert_eq!(
+            expectation.compare("1\n2\n", Some(&error("DivisionByZero", "R0012"))),
+            Err(
+                "unexpected error `DivisionByZero` (R0012) at Ln 3, Col 5: division by zero"
+                    .to_owned()
+            )
+        );
+
+        let expectation = Expectation {
+            output: vec![],
+            error: Some("r0012".to_owned()),
+        };
+        assert_eq!(
+            expectation.compare("", Some(&error("DivisionByZero", "R0012"))),
+            Ok(())
+        );
+        assert_eq!(
+            expectation.compare("", None),
+            Err("expected error `r0012`, but the script succeeded".to_owned())
+        );
+        assert!(expectation
+            .compare("", Some(&error("Overflow", "R0013")))
+            .unwrap_err()
+            .starts_with("expected error `r0012`, got error `Overflow`"));
+    }
+
+    #[test]
+    fn diffs() {
+        assert_eq!(
+            diff(&lines("a\nb\nc\nd"), &lines("a\nc\nx\nd\ne")),
+            "--- expected\n+++ actual\n  a\n- b\n  c\n+ x\n  d\n+ e"
+        );
+        assert_eq!(diff(&[], &[]), "--- expected\n+++ actual");
+    }
+
+    #[test]
+    fn finds_scripts() {
+        let dir = env::temp_dir().join(format!("golden-scripts-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        for file in ["b.txt", "b.out", "notes.md", "Makefile", "nested/a.txt"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let found = scripts(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            found.unwrap(),
+            vec![dir.join("b.txt"), dir.join("nested").join("a.txt")]
+        );
+    }
+}
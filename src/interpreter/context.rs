@@ -1,36 +1,48 @@
-use std::{cell::RefCell, collections::HashMap};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
 
-use crate::parser::grammar::Value;
+use serde::Serialize;
 
-use super::{types::validate_types, ExecutionError, ExecutionErrorVariant};
+use crate::{
+    locale,
+    parser::grammar::{DataType, Value},
+};
+
+use super::{hooks::Hooks, memory, types::validate_types, ExecutionError, ExecutionErrorVariant};
 
 /// Execution context, provides a stack-like architecture for execution
 pub trait Context {
     /// Returns the value of variable under id
-    fn get_variable(&self, _id: &str) -> Result<Value, ExecutionError> {
+    fn get_variable(&self, id: &str) -> Result<Value, ExecutionError> {
         Err(ExecutionError::new(
-            ExecutionErrorVariant::VariableDoesNotExist,
+            ExecutionErrorVariant::VariableDoesNotExist(id.to_owned()),
         ))
     }
 
     /// Sets the value of variable under id
-    fn set_variable(&self, _id: &str, _value: Value) -> Result<(), ExecutionError> {
+    fn set_variable(&self, id: &str, _value: Value) -> Result<(), ExecutionError> {
         Err(ExecutionError::new(
-            ExecutionErrorVariant::VariableDoesNotExist,
+            ExecutionErrorVariant::VariableDoesNotExist(id.to_owned()),
         ))
     }
 
     /// Creates a variable with id and value
-    fn new_variable(&self, _identifier: &str, _value: Value) -> Result<(), ExecutionError> {
+    fn new_variable(&self, identifier: &str, _value: Value) -> Result<(), ExecutionError> {
         Err(ExecutionError::new(
-            ExecutionErrorVariant::VariableDoesNotExist,
+            ExecutionErrorVariant::VariableDoesNotExist(identifier.to_owned()),
         ))
     }
 
-    /// Extends the error message and propagates it
+    /// Records the variables of the context in the error and propagates it
     fn escalate_error(&self, r: Result<Value, ExecutionError>) -> Result<Value, ExecutionError> {
         r.map_err(|mut e| {
-            e.contexts.push(self.name());
+            e.details.snapshots.push(Snapshot {
+                context: self.name(),
+                variables: self.local_variables(),
+            });
             e
         })
     }
@@ -46,12 +58,57 @@ pub trait Context {
 
     /// Get the name of the context
     fn name(&self) -> String;
+
+    /// Names of all variables visible in the context
+    fn variable_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Names of all functions callable within the context
+    fn function_names(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Variables declared directly in the context, without the ones of its parents
+    fn local_variables(&self) -> BTreeMap<String, Value> {
+        BTreeMap::new()
+    }
+
+    /// Tools observing the run the context belongs to, found in its outermost context
+    fn hooks(&self) -> Option<&Hooks> {
+        self.parent()?.hooks()
+    }
+
+    /// Context this one is nested in, `None` for the outermost ones
+    fn parent(&self) -> Option<&dyn Context> {
+        None
+    }
+}
+
+/// Variables of a context which was active when an error occurred
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Snapshot {
+    pub context: String,
+    pub variables: BTreeMap<String, Value>,
+}
+
+impl Display for Snapshot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&locale::message("snapshot", &[&self.context]))?;
+        for (name, value) in &self.variables {
+            let data_type = DataType::from(value);
+            write!(f, "\n    {name}: {data_type} = {}", value.repr())?;
+        }
+        Ok(())
+    }
 }
 
 /// General purpose context
 pub struct BlockCtx<'a> {
     name: String,
     parent: &'a dyn Context,
+    /// Hooks of the outermost context, looked up once instead of on every expression
+    hooks: Option<&'a Hooks>,
     pub variables: RefCell<HashMap<String, Value>>,
 }
 
@@ -60,14 +117,25 @@ impl<'a> BlockCtx<'a> {
         Self {
             name,
             parent,
+            hooks: parent.hooks(),
             variables: RefCell::new(HashMap::new()),
         }
     }
 }
 
+/// Variables go out of scope along with the context
+impl Drop for BlockCtx<'_> {
+    fn drop(&mut self) {
+        for value in self.variables.get_mut().values() {
+            memory::stored(self.parent, Some(value), None);
+        }
+    }
+}
+
 impl Context for BlockCtx<'_> {
     fn get_variable(&self, id: &str) -> Result<Value, ExecutionError> {
         if let Some(v) = self.variables.borrow().get(id) {
+            memory::copied(self, v);
             Ok(v.clone())
         } else {
             self.parent.get_variable(id)
@@ -77,6 +145,7 @@ impl Context for BlockCtx<'_> {
     fn set_variable(&self, id: &str, value: Value) -> Result<(), ExecutionError> {
         if let Some(v) = self.variables.borrow_mut().get_mut(id) {
             validate_types(v, &value)?;
+            memory::stored(self, Some(v), Some(&value));
             *v = value;
             Ok(())
         } else {
@@ -87,9 +156,10 @@ impl Context for BlockCtx<'_> {
     fn new_variable(&self, id: &str, value: Value) -> Result<(), ExecutionError> {
         if self.variables.borrow().contains_key(id) {
             return Err(ExecutionError::new(
-                ExecutionErrorVariant::VariableAlreadyExists,
+                ExecutionErrorVariant::VariableAlreadyExists(id.to_owned()),
             ));
         }
+        memory::stored(self, None, Some(&value));
         self.variables.borrow_mut().insert(id.to_owned(), value);
         Ok(())
     }
@@ -109,4 +179,26 @@ impl Context for BlockCtx<'_> {
     fn name(&self) -> String {
         self.name.clone()
     }
+
+    fn variable_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.variables.borrow().keys().cloned().collect();
+        names.extend(self.parent.variable_names());
+        names
+    }
+
+    fn function_names(&self) -> Vec<String> {
+        self.parent.function_names()
+    }
+
+    fn local_variables(&self) -> BTreeMap<String, Value> {
+        self.variables.borrow().clone().into_iter().collect()
+    }
+
+    fn hooks(&self) -> Option<&Hooks> {
+        self.hooks
+    }
+
+    fn parent(&self) -> Option<&dyn Context> {
+        Some(self.parent)
+    }
 }
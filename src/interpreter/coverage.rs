@@ -0,0 +1,276 @@
+use std::{cell::RefCell, collections::BTreeMap};
+
+use crate::{
+    analysis::coverage::{Probe, ProbeKind},
+    parser::position::Span,
+};
+
+use super::context::Context;
+
+/// Counts the executions of functions, statements, branches and loop bodies
+pub struct Coverage {
+    hits: RefCell<BTreeMap<Probe, usize>>,
+}
+
+impl Coverage {
+    /// Starts counting the probes found in the program, none of them executed yet
+    pub fn new(probes: Vec<Probe>) -> Self {
+        Self {
+            hits: RefCell::new(probes.into_iter().map(|probe| (probe, 0)).collect()),
+        }
+    }
+
+    fn hit(&self, probe: Probe) {
+        *self.hits.borrow_mut().entry(probe).or_default() += 1;
+    }
+
+    /// Probes with their counts, in the order of the source
+    fn counts(&self) -> Vec<(Probe, usize)> {
+        self.hits
+            .borrow()
+            .iter()
+            .map(|(probe, hits)| (probe.clone(), *hits))
+            .collect()
+    }
+
+    /// Executions of each line with statements, the most executed statement starting on it counts
+    fn lines(&self) -> BTreeMap<usize, usize> {
+        let mut lines = BTreeMap::new();
+        for (probe, hits) in self.counts() {
+            if probe.kind == ProbeKind::Statement {
+                let line = lines.entry(probe.line()).or_default();
+                *line = hits.max(*line);
+            }
+        }
+        lines
+    }
+
+    /// Branches as `(line, block, branch, hits)`, an `if` has two branches, a loop body one
+    fn branches(&self) -> Vec<(usize, usize, usize, usize)> {
+        let mut blocks: Vec<Span> = vec![];
+        let mut branches = vec![];
+        for (probe, hits) in self.counts() {
+            let branch = match probe.kind {
+                ProbeKind::TrueBranch | ProbeKind::LoopBody => 0,
+                ProbeKind::FalseBranch => 1,
+                _ => continue,
+            };
+            let block = match blocks.iter().position(|span| *span == probe.span) {
+                Some(block) => block,
+                None => {
+                    blocks.push(probe.span);
+                    blocks.len() - 1
+                }
+            };
+            branches.push((probe.line(), block, branch, hits));
+        }
+        branches
+    }
+
+    /// Report in the lcov tracefile format, for a single source file
+    pub fn lcov(&self, file: &str) -> String {
+        let mut report = format!("TN:\nSF:{file}\n");
+        let functions: Vec<(Probe, usize)> = self
+            .counts()
+            .into_iter()
+            .filter(|(probe, _)| matches!(probe.kind, ProbeKind::Function(_)))
+            .collect();
+        for (probe, _) in &functions {
+            if let ProbeKind::Function(name) = &probe.kind {
+                report.push_str(&format!("FN:{},{name}\n", probe.line()));
+            }
+        }
+        for (probe, hits) in &functions {
+            if let ProbeKind::Function(name) = &probe.kind {
+                report.push_str(&format!("FNDA:{hits},{name}\n"));
+            }
+        }
+        let called = functions.iter().filter(|(_, hits)| *hits > 0).count();
+        report.push_str(&format!("FNF:{}\nFNH:{called}\n", functions.len()));
+        let branches = self.branches();
+        for (line, block, branch, hits) in &branches {
+            report.push_str(&format!("BRDA:{line},{block},{branch},{hits}\n"));
+        }
+        let taken = branches.iter().filter(|(.., hits)| *hits > 0).count();
+        report.push_str(&format!("BRF:{}\nBRH:{taken}\n", branches.len()));
+        let lines = self.lines();
+        for (line, hits) in &lines {
+            report.push_str(&format!("DA:{line},{hits}\n"));
+        }
+        let executed = lines.values().filter(|hits| **hits > 0).count();
+        report.push_str(&format!("LF:{}\nLH:{executed}\n", lines.len()));
+        report.push_str("end_of_record\n");
+        report
+    }
+
+    /// Source with the executions of each line in front of it, `-` for lines without statements
+    /// and `#####` for lines never executed, followed by the counts of functions and branches
+    pub fn annotated(&self, source: &str) -> String {
+        let counts = self.counts();
+        let lines = self.lines();
+        let mut report = String::new();
+        for (i, text) in source.lines().enumerate() {
+            let row = i + 1;
+            for (probe, hits) in counts.iter().filter(|(p, _)| p.line() == row) {
+                if let ProbeKind::Function(name) = &probe.kind {
+                    report.push_str(&format!("function {name} called {hits}\n"));
+                }
+            }
+            let count = match lines.get(&row) {
+                None => "-".to_owned(),
+                Some(0) => "#####".to_owned(),
+                Some(hits) => hits.to_string(),
+            };
+            report.push_str(&format!("{count:>9}:{row:>5}:{text}\n"));
+            for (probe, hits) in counts.iter().filter(|(p, _)| p.line() == row) {
+                let branch = match probe.kind {
+                    ProbeKind::TrueBranch => "if true",
+                    ProbeKind::FalseBranch => "if false",
+                    ProbeKind::LoopBody => "loop body",
+                    _ => continue,
+                };
+                report.push_str(&format!("{branch} at {} taken {hits}\n", probe.span.start));
+            }
+        }
+        report
+    }
+
+    /// Executed and all lines, branches and functions, on a single line
+    pub fn summary(&self) -> String {
+        let ratio = |executed: usize, all: usize| match all {
+            0 => "0/0".to_owned(),
+            _ => format!(
+                "{executed}/{all} ({:.1}%)",
+                executed as f64 * 100.0 / all as f64
+            ),
+        };
+        let lines = self.lines();
+        let branches = self.branches();
+        let functions: Vec<usize> = self
+            .counts()
+            .into_iter()
+            .filter(|(probe, _)| matches!(probe.kind, ProbeKind::Function(_)))
+            .map(|(_, hits)| hits)
+            .collect();
+        format!(
+            "lines: {}, branches: {}, functions: {}",
+            ratio(lines.values().filter(|h| **h > 0).count(), lines.len()),
+            ratio(
+                branches.iter().filter(|(.., h)| *h > 0).count(),
+                branches.len()
+            ),
+            ratio(
+                functions.iter().filter(|h| **h > 0).count(),
+                functions.len()
+            ),
+        )
+    }
+}
+
+/// Counts an execution of a probe, if the coverage is measured
+pub fn hit(ctx: &dyn Context, kind: ProbeKind, span: Span) {
+    if let Some(coverage) = ctx.hooks().and_then(|h| h.coverage.as_ref()) {
+        coverage.hit(Probe::new(kind, span));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::{hooks::Hooks, test_utils::tests::run_with};
+
+    use super::Coverage;
+
+    const SOURCE: &str = "fn sign(n: int) -> int {
+    if n < 0 {
+        return -1;
+    };
+    1
+}
+fn unused() {}
+fn main() {
+    let i: int = 0;
+    while i < 2 {
+        print(sign(i));
+        i = i + 1;
+    };
+}";
+
+    #[test]
+    fn lcov() {
+        let (result, hooks) = run_with(SOURCE, |program| Hooks {
+            coverage: Some(Coverage::new(program.probes())),
+            ..Hooks::default()
+        });
+        result.unwrap();
+        let coverage = hooks.coverage.unwrap();
+        assert_eq!(
+            coverage.lcov("sign.txt"),
+            "TN:
+SF:sign.txt
+FN:1,sign
+FN:7,unused
+FN:8,main
+FNDA:2,sign
+FNDA:0,unused
+FNDA:1,main
+FNF:3
+FNH:2
+BRDA:2,0,0,0
+BRDA:2,0,1,2
+BRDA:10,1,0,2
+BRF:3
+BRH:2
+DA:2,2
+DA:3,0
+DA:5,2
+DA:9,1
+DA:10,1
+DA:11,2
+DA:12,2
+LF:7
+LH:6
+end_of_record
+"
+        );
+    }
+
+    #[test]
+    fn annotated() {
+        let (result, hooks) = run_with(SOURCE, |program| Hooks {
+            coverage: Some(Coverage::new(program.probes())),
+            ..Hooks::default()
+        });
+        result.unwrap();
+        let coverage = hooks.coverage.unwrap();
+        let annotated = coverage.annotated(SOURCE);
+        let lines: Vec<&str> = annotated.lines().collect();
+        assert_eq!(
+            lines[..6],
+            [
+                "function sign called 2",
+                "        -:    1:fn sign(n: int) -> int {",
+                "        2:    2:    if n < 0 {",
+                "if true at Ln 2, Col 8 taken 0",
+                "if false at Ln 2, Col 8 taken 2",
+                "    #####:    3:        return -1;",
+            ]
+        );
+        assert!(lines.contains(&"function unused called 0"));
+        assert!(lines.contains(&"loop body at Ln 10, Col 11 taken 2"));
+        assert_eq!(lines.last(), Some(&"        -:   14:}"));
+    }
+
+    #[test]
+    fn summary() {
+        let (result, hooks) = run_with(SOURCE, |program| Hooks {
+            coverage: Some(Coverage::new(program.probes())),
+            ..Hooks::default()
+        });
+        result.unwrap();
+        let coverage = hooks.coverage.unwrap();
+        assert_eq!(
+            coverage.summary(),
+            "lines: 6/7 (85.7%), branches: 2/3 (66.7%), functions: 2/3 (66.7%)"
+        );
+    }
+}
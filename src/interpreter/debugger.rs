@@ -0,0 +1,510 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    io::{BufRead, Write},
+};
+
+use crate::{
+    parser::{
+        grammar::{
+            expressions::statement::{alternate_statements, Statement},
+            repl::ReplItem,
+            Value,
+        },
+        position::{Position, Span},
+    },
+    repl,
+};
+
+use super::{
+    context::Context, context::Snapshot, standard_library::PrintOuts, ExecutionError,
+    ExecutionErrorVariant,
+};
+
+/// Prompt displayed when waiting for a command
+const PROMPT: &str = "(debug) ";
+
+/// Command manual
+static HELP: &str = "\
+break <line> [if <condition>]   - Pause before the line, only when the condition is `true` if given
+delete <line>                   - Remove the breakpoint of the line
+breakpoints                     - List all breakpoints
+continue                        - Run until the next breakpoint
+step                            - Run until the next line, entering called functions
+next                            - Run until the next line of the current function
+finish                          - Run until the current function returns
+print <expression>              - Evaluate an expression in the paused frame and print the result
+locals                          - Print the variables of the paused frame
+backtrace                       - Print the active functions and contexts
+help                            - Display this message
+quit                            - Stop the program";
+
+/// Debugger commands
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Break(usize, Option<Condition>),
+    Delete(usize),
+    Breakpoints,
+    Continue,
+    Step,
+    Next,
+    Finish,
+    Print(Vec<Statement>),
+    Locals,
+    Backtrace,
+    Help,
+    Quit,
+}
+
+/// Condition of a breakpoint along with its source code
+#[derive(Debug, PartialEq)]
+pub struct Condition {
+    source: String,
+    statements: Vec<Statement>,
+}
+
+/// Parses statements which can be evaluated in the paused frame
+fn parse_statements(input: &str) -> Result<Vec<Statement>, String> {
+    let items = repl::parse(input)
+        .complete()
+        .map_err(|errors| match errors.first() {
+            Some(error) => format!("Invalid expression: {}.", error.error),
+            None => "Invalid expression.".to_owned(),
+        })?;
+    if items.is_empty() {
+        return Err("Missing expression argument.".to_owned());
+    }
+    items
+        .into_iter()
+        .map(|item| match item {
+            ReplItem::Statement(statement) => Ok(statement),
+            ReplItem::Function(_) => Err("Functions can't be defined while debugging.".to_owned()),
+        })
+        .collect()
+}
+
+/// Parses a line number
+fn parse_line(argument: &str) -> Result<usize, String> {
+    argument
+        .parse()
+        .map_err(|_| format!("Invalid line number `{argument}`."))
+}
+
+impl Command {
+    /// Parses a command along with its arguments
+    pub fn parse(input: &str) -> Result<Command, String> {
+        let input = input.trim();
+        let (name, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let rest = rest.trim();
+        Ok(match name {
+            "break" | "b" => {
+                let (line, condition) = match rest.split_once(" if ") {
+                    Some((line, condition)) => (line.trim(), Some(condition.trim())),
+                    None => (rest, None),
+                };
+                let condition = match condition {
+                    Some(source) => Some(Condition {
+                        source: source.to_owned(),
+                        statements: parse_statements(source)?,
+                    }),
+                    None => None,
+                };
+                Command::Break(parse_line(line)?, condition)
+            }
+            "delete" | "d" => Command::Delete(parse_line(rest)?),
+            "breakpoints" => Command::Breakpoints,
+            "continue" | "c" => Command::Continue,
+            "step" | "s" => Command::Step,
+            "next" | "n" => Command::Next,
+            "finish" | "f" => Command::Finish,
+            "print" | "p" => Command::Print(parse_statements(rest)?),
+            "locals" | "l" => Command::Locals,
+            "backtrace" | "bt" => Command::Backtrace,
+            "help" | "h" => Command::Help,
+            "quit" | "q" => Command::Quit,
+            _ => return Err(format!("Unknown command `{name}`, try `help`.")),
+        })
+    }
+}
+
+/// When to pause next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Only at breakpoints
+    Continue,
+    /// At the next line
+    Step,
+    /// At the next line of a frame at most this deep
+    Next(usize),
+    /// At the next line of a frame less deep than this one
+    Finish(usize),
+}
+
+/// A running user function
+struct Frame {
+    function: String,
+    /// Starts of the expressions being evaluated, innermost last
+    positions: Vec<Position>,
+}
+
+/// Command-line debugger, pauses the program at breakpoints and steps,
+/// reading commands and printing their results
+pub struct Debugger {
+    source: Vec<String>,
+    input: RefCell<Box<dyn BufRead>>,
+    pub out: RefCell<PrintOuts>,
+    /// Whether to display a prompt
+    interactive: bool,
+    breakpoints: RefCell<BTreeMap<usize, Option<Condition>>>,
+    mode: Cell<Mode>,
+    frames: RefCell<Vec<Frame>>,
+    /// Set while evaluating expressions for the user, which can't be paused at
+    evaluating: Cell<bool>,
+}
+
+impl Debugger {
+    /// Creates a debugger which pauses at the first line of the program
+    pub fn new(source: &str, input: Box<dyn BufRead>, out: PrintOuts, interactive: bool) -> Self {
+        Self {
+            source: source.lines().map(str::to_owned).collect(),
+            input: RefCell::new(input),
+            out: RefCell::new(out),
+            interactive,
+            breakpoints: RefCell::new(BTreeMap::new()),
+            mode: Cell::new(Mode::Step),
+            frames: RefCell::new(vec![]),
+            evaluating: Cell::new(false),
+        }
+    }
+
+    fn print(&self, message: &str) {
+        // The debugger must not stop the program if its output is closed
+        let _ = writeln!(self.out.borrow_mut(), "{message}");
+    }
+
+    /// Evaluates statements in the context without pausing at them
+    fn evaluate(&self, statements: &Vec<Statement>, ctx: &dyn Context) -> Result<Value, String> {
+        self.evaluating.set(true);
+        let result = alternate_statements(statements, ctx);
+        self.evaluating.set(false);
+        result.map_err(|e| e.variant.to_string())
+    }
+
+    /// Checks whether to pause at the start of a line
+    fn should_pause(&self, ctx: &dyn Context, row: usize) -> bool {
+        let depth = self.frames.borrow().len();
+        let stepped = match self.mode.get() {
+            Mode::Continue => false,
+            Mode::Step => true,
+            Mode::Next(d) => depth <= d,
+            Mode::Finish(d) => depth < d,
+        };
+        if stepped {
+            return true;
+        }
+        match self.breakpoints.borrow().get(&row) {
+            Some(None) => true,
+            Some(Some(condition)) => match self.evaluate(&condition.statements, ctx) {
+                Ok(value) => value == Value::Bool(true),
+                Err(e) => {
+                    self.print(&format!("Condition `{}` failed: {e}.", condition.source));
+                    true
+                }
+            },
+            None => false,
+        }
+    }
+
+    /// Contexts of the paused frame, innermost first
+    fn contexts(ctx: &dyn Context) -> Vec<&dyn Context> {
+        let mut contexts = vec![];
+        let mut current = ctx;
+        while let Some(parent) = current.parent() {
+            contexts.push(current);
+            current = parent;
+        }
+        contexts
+    }
+
+    /// Prints the contexts of the paused frame, then the calling functions
+    fn backtrace(&self, ctx: &dyn Context, position: Position) {
+        let names: Vec<String> = Self::contexts(ctx).iter().map(|c| c.name()).collect();
+        self.print(&format!("#0 {position} in {}", names.join(", ")));
+        for (i, frame) in self.frames.borrow().iter().rev().enumerate().skip(1) {
+            let position = frame.positions.last().copied().unwrap_or_default();
+            self.print(&format!("#{i} {position} in `{}`", frame.function));
+        }
+    }
+
+    /// Shows where the program is paused and executes commands until one resumes it.
+    /// Returns an error when the program should be stopped.
+    fn pause(&self, ctx: &dyn Context, position: Position) -> Result<(), ExecutionError> {
+        let function = match self.frames.borrow().last() {
+            Some(frame) => frame.function.clone(),
+            None => return Ok(()),
+        };
+        self.print(&format!("Paused at {position} in `{function}`"));
+        if let Some(line) = self.source.get(position.row.wrapping_sub(1)) {
+            self.print(&format!("{:>4} | {line}", position.row));
+        }
+        let depth = self.frames.borrow().len();
+        loop {
+            if self.interactive {
+                let _ = write!(self.out.borrow_mut(), "{PROMPT}");
+                let _ = self.out.borrow_mut().flush();
+            }
+            let mut line = String::new();
+            if !matches!(self.input.borrow_mut().read_line(&mut line), Ok(1..)) {
+                // Without more commands the program runs to its end
+                self.breakpoints.borrow_mut().clear();
+                self.mode.set(Mode::Continue);
+                return Ok(());
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            let command = match Command::parse(&line) {
+                Ok(command) => command,
+                Err(message) => {
+                    self.print(&message);
+                    continue;
+                }
+            };
+            let mode = match command {
+                Command::Break(line, condition) => {
+                    self.print(&format!("Breakpoint at line {line}."));
+                    self.breakpoints.borrow_mut().insert(line, condition);
+                    continue;
+                }
+                Command::Delete(line) => {
+                    match self.breakpoints.borrow_mut().remove(&line) {
+                        Some(_) => self.print(&format!("Removed the breakpoint at line {line}.")),
+                        None => self.print(&format!("There is no breakpoint at line {line}.")),
+                    }
+                    continue;
+                }
+                Command::Breakpoints => {
+                    for (line, condition) in self.breakpoints.borrow().iter() {
+                        match condition {
+                            Some(condition) => {
+                                self.print(&format!("line {line} if {}", condition.source))
+                            }
+                            None => self.print(&format!("line {line}")),
+                        }
+                    }
+                    continue;
+                }
+                Command::Print(statements) => {
+                    match self.evaluate(&statements, ctx) {
+                        Ok(value) => self.print(&value.repr()),
+                        Err(e) => self.print(&format!("Error: {e}.")),
+                    }
+                    continue;
+                }
+                Command::Locals => {
+                    for context in Self::contexts(ctx) {
+                        let snapshot = Snapshot {
+                            context: context.name(),
+                            variables: context.local_variables(),
+                        };
+                        self.print(&snapshot.to_string());
+                    }
+                    continue;
+                }
+                Command::Backtrace => {
+                    self.backtrace(ctx, position);
+                    continue;
+                }
+                Command::Help => {
+                    self.print(HELP);
+                    continue;
+                }
+                Command::Quit => {
+                    return Err(ExecutionError::new(ExecutionErrorVariant::Aborted));
+                }
+                Command::Continue => Mode::Continue,
+                Command::Step => Mode::Step,
+                Command::Next => Mode::Next(depth),
+                Command::Finish => Mode::Finish(depth),
+            };
+            self.mode.set(mode);
+            return Ok(());
+        }
+    }
+}
+
+/// Runs a user function in a new frame of the debugger
+pub fn call<T>(ctx: &dyn Context, function: &str, body: impl FnOnce() -> T) -> T {
+    let Some(debugger) = ctx.hooks().and_then(|h| h.debugger.as_ref()) else {
+        return body();
+    };
+    debugger.frames.borrow_mut().push(Frame {
+        function: function.to_owned(),
+        positions: vec![],
+    });
+    let result = body();
+    debugger.frames.borrow_mut().pop();
+    result
+}
+
+/// Evaluates an expression, first pausing if it starts a line a breakpoint or a step stops at.
+/// Expressions start a line when it differs from the line of the expression enclosing them.
+pub fn expression(
+    ctx: &dyn Context,
+    span: Span,
+    body: impl FnOnce() -> Result<Value, ExecutionError>,
+) -> Result<Value, ExecutionError> {
+    let debugger = match ctx.hooks().and_then(|h| h.debugger.as_ref()) {
+        Some(debugger) if !debugger.evaluating.get() => debugger,
+        _ => return body(),
+    };
+    let row = span.start.row;
+    let starts_line = match debugger.frames.borrow().last() {
+        Some(frame) => frame.positions.last().map(|p| p.row) != Some(row),
+        None => return body(),
+    };
+    if starts_line && debugger.should_pause(ctx, row) {
+        debugger.pause(ctx, span.start)?;
+    }
+    if let Some(frame) = debugger.frames.borrow_mut().last_mut() {
+        frame.positions.push(span.start);
+    }
+    let result = body();
+    if let Some(frame) = debugger.frames.borrow_mut().last_mut() {
+        frame.positions.pop();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::interpreter::{
+        hooks::Hooks,
+        standard_library::PrintOuts,
+        test_utils::tests::{captured, run_with},
+        ExecutionErrorVariant,
+    };
+
+    use super::{Command, Debugger};
+
+    const SOURCE: &str = "fn square(x: int) -> int {
+    let y: int = x * x;
+    y
+}
+fn main() {
+    let total: int = 0;
+    for i in [1, 2, 3] {
+        total = total + square(i);
+    };
+    print(total);
+}";
+
+    /// Debugger reading the commands, which isn't interactive so the prompts aren't shown
+    fn debugger(commands: &str) -> Debugger {
+        let input = Box::new(Cursor::new(commands.to_owned().into_bytes()));
+        Debugger::new(SOURCE, input, PrintOuts::Vec(vec![]), false)
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(Command::parse(" c "), Ok(Command::Continue));
+        assert_eq!(Command::parse("delete 3"), Ok(Command::Delete(3)));
+        assert!(matches!(
+            Command::parse("break 8 if i == 2"),
+            Ok(Command::Break(8, Some(_)))
+        ));
+        assert!(matches!(Command::parse("p total"), Ok(Command::Print(_))));
+        assert_eq!(
+            Command::parse("b x"),
+            Err("Invalid line number `x`.".to_owned())
+        );
+        assert_eq!(
+            Command::parse("print"),
+            Err("Missing expression argument.".to_owned())
+        );
+        assert_eq!(
+            Command::parse("jump"),
+            Err("Unknown command `jump`, try `help`.".to_owned())
+        );
+    }
+
+    #[test]
+    fn steps() {
+        let (result, hooks) = run_with(SOURCE, |_| Hooks {
+            debugger: Some(debugger(
+                "next\nnext\nstep\nfinish\nprint total\nnext\nprint total\ncontinue\n",
+            )),
+            ..Hooks::default()
+        });
+        result.unwrap();
+        assert_eq!(
+            captured(hooks.debugger.unwrap().out),
+            "Paused at Ln 6, Col 5 in `main`
+   6 |     let total: int = 0;
+Paused at Ln 7, Col 5 in `main`
+   7 |     for i in [1, 2, 3] {
+Paused at Ln 8, Col 9 in `main`
+   8 |         total = total + square(i);
+Paused at Ln 2, Col 5 in `square`
+   2 |     let y: int = x * x;
+Paused at Ln 8, Col 9 in `main`
+   8 |         total = total + square(i);
+1
+Paused at Ln 8, Col 9 in `main`
+   8 |         total = total + square(i);
+5
+"
+        );
+    }
+
+    #[test]
+    fn breakpoints() {
+        let (result, hooks) = run_with(SOURCE, |_| {
+            Hooks {
+            debugger: Some(debugger("break 3 if x == 2\nb 10\nbreakpoints\nc\nlocals\nbacktrace\ndelete 3\nc\np total + 1\n")),
+            ..Hooks::default()
+        }
+        });
+        result.unwrap();
+        assert_eq!(
+            captured(hooks.debugger.unwrap().out),
+            "Paused at Ln 6, Col 5 in `main`
+   6 |     let total: int = 0;
+Breakpoint at line 3.
+Breakpoint at line 10.
+line 3 if x == 2
+line 10
+Paused at Ln 3, Col 5 in `square`
+   3 |     y
+variables of the `square` function:
+    x: int = 2
+    y: int = 4
+#0 Ln 3, Col 5 in `square` function
+#1 Ln 8, Col 25 in `main`
+Removed the breakpoint at line 3.
+Paused at Ln 10, Col 5 in `main`
+  10 |     print(total);
+15
+"
+        );
+    }
+
+    #[test]
+    fn quit() {
+        let (result, hooks) = run_with(SOURCE, |_| Hooks {
+            debugger: Some(debugger("next\nquit\nnext\n")),
+            ..Hooks::default()
+        });
+        assert_eq!(
+            captured(hooks.debugger.unwrap().out),
+            "Paused at Ln 6, Col 5 in `main`
+   6 |     let total: int = 0;
+Paused at Ln 7, Col 5 in `main`
+   7 |     for i in [1, 2, 3] {
+"
+        );
+        assert_eq!(result.unwrap_err().variant, ExecutionErrorVariant::Aborted);
+    }
+}
@@ -0,0 +1,25 @@
+use super::{
+    coverage::Coverage, debugger::Debugger, memory::MemoryProfiler, profiler::Profiler,
+    trace::Tracer,
+};
+
+/// Tools observing a run of a program, each one is only present when it was asked for
+#[derive(Default)]
+pub struct Hooks {
+    pub tracer: Option<Tracer>,
+    pub debugger: Option<Debugger>,
+    pub profiler: Option<Profiler>,
+    pub coverage: Option<Coverage>,
+    pub memory_profiler: Option<MemoryProfiler>,
+}
+
+impl Hooks {
+    /// Whether no tool observes the run, so evaluation can skip looking for them
+    pub fn is_empty(&self) -> bool {
+        self.tracer.is_none()
+            && self.debugger.is_none()
+            && self.profiler.is_none()
+            && self.coverage.is_none()
+            && self.memory_profiler.is_none()
+    }
+}
@@ -0,0 +1,302 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    mem::size_of,
+};
+
+use crate::parser::{grammar::Value, position::Span};
+
+use super::context::Context;
+
+/// Allocations of `list` and `string` values
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub allocations: usize,
+    pub bytes: usize,
+}
+
+impl Stats {
+    /// Allocations made to copy a value along with all the values it contains
+    fn deep(value: &Value) -> Self {
+        match value {
+            Value::List(list) => list.iter().map(Stats::deep).fold(
+                Stats {
+                    allocations: 1,
+                    bytes: list.len() * size_of::<Value>(),
+                },
+                |a, b| Stats {
+                    allocations: a.allocations + b.allocations,
+                    bytes: a.bytes + b.bytes,
+                },
+            ),
+            _ => Self::shallow(value),
+        }
+    }
+
+    /// Allocation of a value whose contained values were moved into it
+    fn shallow(value: &Value) -> Self {
+        match value {
+            Value::List(list) => Stats {
+                allocations: 1,
+                bytes: list.len() * size_of::<Value>(),
+            },
+            Value::String(string) => Stats {
+                allocations: 1,
+                bytes: string.len(),
+            },
+            _ => Stats::default(),
+        }
+    }
+
+    fn add(&mut self, other: Stats) {
+        self.allocations += other.allocations;
+        self.bytes += other.bytes;
+    }
+}
+
+/// Memory taken by a variable holding the value
+fn footprint(value: &Value) -> usize {
+    size_of::<Value>() + Stats::deep(value).bytes
+}
+
+/// Counts the allocations of values by function and by line,
+/// and the memory taken by the variables of all active contexts
+#[derive(Default)]
+pub struct MemoryProfiler {
+    /// Running functions, innermost last
+    functions: RefCell<Vec<String>>,
+    /// Lines of the evaluated expressions, innermost last
+    lines: RefCell<Vec<usize>>,
+    by_function: RefCell<HashMap<String, Stats>>,
+    by_line: RefCell<HashMap<usize, Stats>>,
+    live: Cell<usize>,
+    peak: Cell<usize>,
+}
+
+impl MemoryProfiler {
+    fn record(&self, stats: Stats) {
+        if stats.allocations == 0 {
+            return;
+        }
+        if let Some(function) = self.functions.borrow().last() {
+            let mut by_function = self.by_function.borrow_mut();
+            by_function.entry(function.clone()).or_default().add(stats);
+        }
+        if let Some(line) = self.lines.borrow().last() {
+            self.by_line
+                .borrow_mut()
+                .entry(*line)
+                .or_default()
+                .add(stats);
+        }
+    }
+
+    fn store(&self, old: Option<&Value>, new: Option<&Value>) {
+        let live = self.live.get() + new.map(footprint).unwrap_or_default();
+        let live = live.saturating_sub(old.map(footprint).unwrap_or_default());
+        self.live.set(live);
+        self.peak.set(self.peak.get().max(live));
+    }
+
+    /// Most memory taken by variables at once, in bytes
+    pub fn peak(&self) -> usize {
+        self.peak.get()
+    }
+
+    /// Allocations of each function, the most memory consuming first
+    pub fn by_function(&self) -> Vec<(String, Stats)> {
+        sorted(&self.by_function.borrow())
+    }
+
+    /// Allocations of each line, the most memory consuming first
+    pub fn by_line(&self) -> Vec<(usize, Stats)> {
+        sorted(&self.by_line.borrow())
+    }
+
+    /// Tables of allocations by function and by line, followed by the peak memory of variables
+    pub fn summary(&self) -> String {
+        let functions = self.by_function();
+        let width = functions
+            .iter()
+            .map(|(function, _)| function.len())
+            .chain(["function".len()])
+            .max()
+            .unwrap_or_default();
+        let mut summary = format!(
+            "{:<width$}  {:>11}  {:>12}",
+            "function", "allocations", "bytes"
+        );
+        for (function, stats) in functions {
+            summary.push_str(&format!(
+                "\n{function:<width$}  {:>11}  {:>12}",
+                stats.allocations, stats.bytes
+            ));
+        }
+        summary.push_str(&format!(
+            "\n\n{:>5}  {:>11}  {:>12}",
+            "line", "allocations", "bytes"
+        ));
+        for (line, stats) in self.by_line() {
+            summary.push_str(&format!(
+                "\n{line:>5}  {:>11}  {:>12}",
+                stats.allocations, stats.bytes
+            ));
+        }
+        summary.push_str(&format!(
+            "\n\npeak memory of variables: {} bytes",
+            self.peak()
+        ));
+        summary
+    }
+}
+
+/// Entries sorted by bytes descending, then by key
+fn sorted<K: Clone + Ord>(stats: &HashMap<K, Stats>) -> Vec<(K, Stats)> {
+    let mut stats: Vec<(K, Stats)> = stats.iter().map(|(k, s)| (k.clone(), *s)).collect();
+    stats.sort_by(|(k1, s1), (k2, s2)| s2.bytes.cmp(&s1.bytes).then(k1.cmp(k2)));
+    stats
+}
+
+/// Runs a user function, attributing the allocations within to it
+pub fn call<T>(ctx: &dyn Context, name: &str, body: impl FnOnce() -> T) -> T {
+    let Some(profiler) = ctx.hooks().and_then(|h| h.memory_profiler.as_ref()) else {
+        return body();
+    };
+    profiler.functions.borrow_mut().push(name.to_owned());
+    let result = body();
+    profiler.functions.borrow_mut().pop();
+    result
+}
+
+/// Evaluates an expression, attributing the allocations within to its line
+pub fn expression<T>(ctx: &dyn Context, span: Span, body: impl FnOnce() -> T) -> T {
+    let Some(profiler) = ctx.hooks().and_then(|h| h.memory_profiler.as_ref()) else {
+        return body();
+    };
+    profiler.lines.borrow_mut().push(span.start.row);
+    let result = body();
+    profiler.lines.borrow_mut().pop();
+    result
+}
+
+/// Records a copy of a value along with all the values it contains
+pub fn copied(ctx: &dyn Context, value: &Value) {
+    if let Some(profiler) = ctx.hooks().and_then(|h| h.memory_profiler.as_ref()) {
+        profiler.record(Stats::deep(value));
+    }
+}
+
+/// Records a new value, whose contained values were moved into it
+pub fn allocated(ctx: &dyn Context, value: &Value) {
+    if let Some(profiler) = ctx.hooks().and_then(|h| h.memory_profiler.as_ref()) {
+        profiler.record(Stats::shallow(value));
+    }
+}
+
+/// Records a variable taking a new value, `None` for a variable being created or dropped
+pub fn stored(ctx: &dyn Context, old: Option<&Value>, new: Option<&Value>) {
+    if let Some(profiler) = ctx.hooks().and_then(|h| h.memory_profiler.as_ref()) {
+        profiler.store(old, new);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem::size_of;
+
+    use crate::{
+        interpreter::{hooks::Hooks, test_utils::tests::run_with},
+        parser::grammar::Value,
+    };
+
+    use super::{MemoryProfiler, Stats};
+
+    const VALUE: usize = size_of::<Value>();
+
+    #[test]
+    fn sizes() {
+        let value = Value::List(vec![
+            Value::String("abc".to_owned()),
+            Value::List(vec![Value::Int(1)]),
+        ]);
+        assert_eq!(
+            Stats::deep(&value),
+            Stats {
+                allocations: 3,
+                bytes: 3 * VALUE + 3
+            }
+        );
+        assert_eq!(
+            Stats::shallow(&value),
+            Stats {
+                allocations: 1,
+                bytes: 2 * VALUE
+            }
+        );
+        assert_eq!(Stats::deep(&Value::Int(1)), Stats::default());
+    }
+
+    #[test]
+    fn allocations() {
+        let source = "fn name() -> string {
+    \"ab\"
+}
+fn main() {
+    let s: string = name();
+    let l: [] = [s, s];
+    l = push(l, 1);
+}";
+        let (result, hooks) = run_with(source, |_| Hooks {
+            memory_profiler: Some(MemoryProfiler::default()),
+            ..Hooks::default()
+        });
+        result.unwrap();
+        let profiler = hooks.memory_profiler.unwrap();
+        let stats = |allocations, bytes| Stats { allocations, bytes };
+        // line 5: `s` declared with a copy of the string
+        let line5 = stats(1, 2);
+        // line 6: two copies of `s`, the list holding them and its copy declared as `l`
+        let line6 = stats(2 + 1 + 3, 2 * 2 + 2 * VALUE + (2 * VALUE + 4));
+        // line 7: `l` copied into the arguments, the list returned by `push`
+        // and its copy assigned to `l`
+        let line7 = stats(3 + 1 + 3, (2 * VALUE + 4) + 3 * VALUE + (3 * VALUE + 4));
+        assert_eq!(
+            profiler.by_function(),
+            vec![
+                (
+                    "main".to_owned(),
+                    stats(
+                        line5.allocations + line6.allocations + line7.allocations,
+                        line5.bytes + line6.bytes + line7.bytes
+                    )
+                ),
+                // the literal
+                ("name".to_owned(), stats(1, 2)),
+            ]
+        );
+        assert_eq!(
+            profiler.by_line(),
+            vec![(7, line7), (6, line6), (2, stats(1, 2)), (5, line5)]
+        );
+    }
+
+    #[test]
+    fn peak() {
+        let source = "fn big() {
+    let l: [] = [\"abcd\", \"efgh\"];
+}
+fn main() {
+    let i: int = 0;
+    big();
+    big();
+}";
+        let (result, hooks) = run_with(source, |_| Hooks {
+            memory_profiler: Some(MemoryProfiler::default()),
+            ..Hooks::default()
+        });
+        result.unwrap();
+        let profiler = hooks.memory_profiler.unwrap();
+        assert_eq!(profiler.peak(), 2 * VALUE + 2 * VALUE + 8);
+        assert_eq!(profiler.live.get(), 0);
+    }
+}
@@ -1,26 +1,54 @@
 pub mod callable;
 pub mod context;
+pub mod coverage;
+pub mod debugger;
+pub mod hooks;
+pub mod memory;
+pub mod profiler;
 pub mod standard_library;
+pub mod suggestions;
 pub mod test_utils;
+pub mod trace;
 pub mod types;
 
 use std::{error::Error, fmt::Display};
 
+use crate::{
+    locale,
+    parser::{grammar::DataType, position::Span},
+};
+
+use self::{
+    context::{Context, Snapshot},
+    standard_library::alias_of,
+    suggestions::closest,
+};
+
 /// Different kinds of interpretation errors
 #[derive(Debug, PartialEq, Eq)]
 pub enum ExecutionErrorVariant {
-    VariableDoesNotExist,
-    VariableAlreadyExists,
-    FunctionDoesNotExist,
+    VariableDoesNotExist(String),
+    VariableAlreadyExists(String),
+    FunctionDoesNotExist(String),
 
     UnsupportedBinaryOperation,
     UnsupportedUnaryOperation,
     UnsupportedListAccess,
     NonIntegerIndex,
-    IndexOutOfBounds,
+    IndexOutOfBounds {
+        index: i64,
+        length: usize,
+    },
 
-    InvalidArgumentCount,
-    InvalidType,
+    InvalidArgumentCount {
+        expected: usize,
+        given: usize,
+    },
+    /// Any of the expected types would be valid
+    InvalidType {
+        expected: Vec<DataType>,
+        actual: DataType,
+    },
 
     CastFailed,
 
@@ -31,33 +59,210 @@ pub enum ExecutionErrorVariant {
     ExpectedIdentifier,
 
     ExpectedSemicolon,
+
+    /// Stopped with the `quit` command of the debugger
+    Aborted,
+}
+
+impl Display for ExecutionErrorVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            ExecutionErrorVariant::VariableDoesNotExist(id)
+            | ExecutionErrorVariant::VariableAlreadyExists(id)
+            | ExecutionErrorVariant::FunctionDoesNotExist(id) => {
+                locale::message(self.code(), &[id])
+            }
+            ExecutionErrorVariant::IndexOutOfBounds { index, length } => {
+                locale::message(self.code(), &[index, length])
+            }
+            ExecutionErrorVariant::InvalidArgumentCount { expected, given } => {
+                locale::counted(self.code(), *expected, &[expected, given])
+            }
+            ExecutionErrorVariant::InvalidType { expected, actual } => {
+                locale::message(self.code(), &[&alternatives(expected), actual])
+            }
+            _ => locale::message(self.code(), &[]),
+        };
+        f.write_str(&message)
+    }
+}
+
+/// Lists items as "`a`, `b` or `c`"
+pub fn alternatives<T: Display>(items: &[T]) -> String {
+    let items: Vec<String> = items.iter().map(|t| format!("`{t}`")).collect();
+    match items.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => locale::message("alternatives", &[&rest.join(", "), last]),
+        None => locale::message("nothing", &[]),
+    }
+}
+
+impl ExecutionErrorVariant {
+    /// Stable code identifying the problem, explained by `--explain`
+    pub fn code(&self) -> &'static str {
+        match self {
+            ExecutionErrorVariant::VariableDoesNotExist(_) => "R0001",
+            ExecutionErrorVariant::VariableAlreadyExists(_) => "R0002",
+            ExecutionErrorVariant::FunctionDoesNotExist(_) => "R0003",
+            ExecutionErrorVariant::UnsupportedBinaryOperation => "R0004",
+            ExecutionErrorVariant::UnsupportedUnaryOperation => "R0005",
+            ExecutionErrorVariant::UnsupportedListAccess => "R0006",
+            ExecutionErrorVariant::NonIntegerIndex => "R0007",
+            ExecutionErrorVariant::IndexOutOfBounds { .. } => "R0008",
+            ExecutionErrorVariant::InvalidArgumentCount { .. } => "R0009",
+            ExecutionErrorVariant::InvalidType { .. } => "R0010",
+            ExecutionErrorVariant::CastFailed => "R0011",
+            ExecutionErrorVariant::DivisionByZero => "R0012",
+            ExecutionErrorVariant::Overflow => "R0013",
+            ExecutionErrorVariant::MissingMainFunction => "R0014",
+            ExecutionErrorVariant::ExpectedIdentifier => "R0015",
+            ExecutionErrorVariant::ExpectedSemicolon => "R0016",
+            ExecutionErrorVariant::Aborted => "R0017",
+        }
+    }
+
+    /// Process exit code of a program terminated by this error
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            ExecutionErrorVariant::VariableDoesNotExist(_)
+            | ExecutionErrorVariant::VariableAlreadyExists(_)
+            | ExecutionErrorVariant::FunctionDoesNotExist(_)
+            | ExecutionErrorVariant::ExpectedIdentifier => 10,
+            ExecutionErrorVariant::UnsupportedBinaryOperation
+            | ExecutionErrorVariant::UnsupportedUnaryOperation
+            | ExecutionErrorVariant::UnsupportedListAccess
+            | ExecutionErrorVariant::NonIntegerIndex
+            | ExecutionErrorVariant::InvalidArgumentCount { .. }
+            | ExecutionErrorVariant::InvalidType { .. }
+            | ExecutionErrorVariant::CastFailed => 11,
+            ExecutionErrorVariant::IndexOutOfBounds { .. } => 12,
+            ExecutionErrorVariant::DivisionByZero | ExecutionErrorVariant::Overflow => 13,
+            ExecutionErrorVariant::MissingMainFunction
+            | ExecutionErrorVariant::ExpectedSemicolon => 14,
+            // Stopping the program on request isn't a failure
+            ExecutionErrorVariant::Aborted => 0,
+        }
+    }
+
+    /// Similar names which might have been meant instead of a missing variable or function,
+    /// chosen from the names visible where it was looked up
+    pub fn suggestions(
+        &self,
+        variables: impl FnOnce() -> Vec<String>,
+        functions: impl FnOnce() -> Vec<String>,
+    ) -> Vec<String> {
+        match self {
+            ExecutionErrorVariant::VariableDoesNotExist(name) => closest(name, variables()),
+            ExecutionErrorVariant::FunctionDoesNotExist(name) => {
+                let mut names: Vec<String> =
+                    alias_of(name).into_iter().map(str::to_owned).collect();
+                names.extend(closest(name, functions()));
+                names
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// Function which was being executed when an error occurred
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackFrame {
+    pub function: String,
+    /// Identifier of the function call, `None` for `main`
+    pub call_site: Option<Span>,
+}
+
+impl Display for StackFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self.call_site {
+            Some(span) => locale::message("frame.call", &[&self.function, &span.start]),
+            None => locale::message("frame", &[&self.function]),
+        };
+        f.write_str(&message)
+    }
+}
+
+/// Parts of an error which are only needed to report it, boxed so results stay small
+#[derive(Debug, Default, PartialEq)]
+pub struct ErrorDetails {
+    /// Variables of the contexts the error propagated through, innermost first
+    pub snapshots: Vec<Snapshot>,
+    /// Function frames, innermost first
+    pub frames: Vec<StackFrame>,
+    /// Similar names which might have been meant instead of a missing one
+    pub suggestions: Vec<String>,
 }
 
 /// Interpretation error with stack trace
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 pub struct ExecutionError {
-    pub contexts: Vec<String>,
     pub variant: ExecutionErrorVariant,
+    /// Expression which failed
+    pub span: Option<Span>,
+    pub details: Box<ErrorDetails>,
 }
 
 impl ExecutionError {
     pub fn new(variant: ExecutionErrorVariant) -> Self {
         Self {
-            contexts: vec![],
             variant,
+            span: None,
+            details: Box::default(),
+        }
+    }
+
+    /// Suggests similar names visible in the context for missing variables and functions.
+    /// Only errors which don't point at any expression yet come from a lookup in this context.
+    pub fn suggest(mut self, ctx: &dyn Context) -> Self {
+        if self.span.is_some() {
+            return self;
+        }
+        self.details.suggestions = self
+            .variant
+            .suggestions(|| ctx.variable_names(), || ctx.function_names());
+        self
+    }
+
+    /// Points the error at an expression, unless it already points at a more specific one
+    pub fn at(mut self, span: Span) -> Self {
+        self.span.get_or_insert(span);
+        self
+    }
+
+    /// Adds a function frame.
+    /// Errors which already point at an expression come from the body of the called function,
+    /// other errors happened during the call itself, so there is no frame to add.
+    pub fn called_from(mut self, function: &str, call_site: Span) -> Self {
+        if self.span.is_some() {
+            self.details.frames.push(StackFrame {
+                function: function.to_owned(),
+                call_site: Some(call_site),
+            });
         }
+        self
+    }
+
+    /// Adds the outermost frame of the `main` function
+    pub fn in_main(mut self) -> Self {
+        self.details.frames.push(StackFrame {
+            function: "main".to_owned(),
+            call_site: None,
+        });
+        self
     }
 }
 
 impl Display for ExecutionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for context in &self.contexts {
-            f.write_fmt(format_args!("In `{context}` context.\n"))?;
+        f.write_fmt(format_args!("Runtime error: {}", self.variant))?;
+        if let Some(span) = self.span {
+            f.write_fmt(format_args!(" at {}", span.start))?;
+        }
+        f.write_str(".\n")?;
+        for frame in &self.details.frames {
+            f.write_fmt(format_args!("  {frame}\n"))?;
         }
-        f.write_fmt(format_args!(
-            "Encountered runtime error {:?}.\n",
-            self.variant
-        ))
+        Ok(())
     }
 }
 
@@ -0,0 +1,266 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use super::context::Context;
+
+/// A user function or a builtin
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Function {
+    pub name: String,
+    pub builtin: bool,
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.builtin {
+            f.write_fmt(format_args!("{} (builtin)", self.name))
+        } else {
+            f.write_str(&self.name)
+        }
+    }
+}
+
+/// Measurements of a single function
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Stats {
+    pub calls: usize,
+    /// Time spent in the function and the functions it called,
+    /// recursive calls are counted once
+    pub inclusive: Duration,
+    /// Time spent in the function itself
+    pub exclusive: Duration,
+    /// Most calls of the function active at once
+    pub depth: usize,
+}
+
+/// A running function
+struct Frame {
+    function: Function,
+    start: Instant,
+    /// Time spent in the functions it called
+    children: Duration,
+}
+
+/// Measures the time spent in functions
+#[derive(Default)]
+pub struct Profiler {
+    frames: RefCell<Vec<Frame>>,
+    stats: RefCell<HashMap<Function, Stats>>,
+    /// Exclusive time of each stack of function names, outermost first
+    stacks: RefCell<HashMap<Vec<String>, Duration>>,
+}
+
+impl Profiler {
+    fn enter(&self, function: Function) {
+        let mut frames = self.frames.borrow_mut();
+        let depth = frames.iter().filter(|f| f.function == function).count() + 1;
+        let mut stats = self.stats.borrow_mut();
+        let stats = stats.entry(function.clone()).or_default();
+        stats.calls += 1;
+        stats.depth = stats.depth.max(depth);
+        frames.push(Frame {
+            function,
+            start: Instant::now(),
+            children: Duration::ZERO,
+        });
+    }
+
+    fn exit(&self) {
+        let mut frames = self.frames.borrow_mut();
+        let stack: Vec<String> = frames.iter().map(|f| f.function.name.clone()).collect();
+        let Some(frame) = frames.pop() else {
+            return;
+        };
+        let elapsed = frame.start.elapsed();
+        let exclusive = elapsed.saturating_sub(frame.children);
+        let recursive = frames.iter().any(|f| f.function == frame.function);
+        if let Some(parent) = frames.last_mut() {
+            parent.children += elapsed;
+        }
+        let mut stats = self.stats.borrow_mut();
+        let stats = stats.entry(frame.function).or_default();
+        stats.exclusive += exclusive;
+        if !recursive {
+            stats.inclusive += elapsed;
+        }
+        *self.stacks.borrow_mut().entry(stack).or_default() += exclusive;
+    }
+
+    /// Measurements of all called functions, the most time consuming first
+    pub fn stats(&self) -> Vec<(Function, Stats)> {
+        let mut stats: Vec<(Function, Stats)> = self
+            .stats
+            .borrow()
+            .iter()
+            .map(|(function, stats)| (function.clone(), stats.clone()))
+            .collect();
+        stats.sort_by(|(f1, s1), (f2, s2)| {
+            s2.exclusive
+                .cmp(&s1.exclusive)
+                .then(s2.inclusive.cmp(&s1.inclusive))
+                .then(f1.cmp(f2))
+        });
+        stats
+    }
+
+    /// Table of the measurements, times are in milliseconds
+    pub fn table(&self) -> String {
+        let stats = self.stats();
+        let width = stats
+            .iter()
+            .map(|(function, _)| function.to_string().len())
+            .chain(["function".len()])
+            .max()
+            .unwrap_or_default();
+        let mut table = format!(
+            "{:<width$}  {:>8}  {:>12}  {:>12}  {:>5}",
+            "function", "calls", "inclusive ms", "exclusive ms", "depth"
+        );
+        for (function, stats) in stats {
+            table.push_str(&format!(
+                "\n{:<width$}  {:>8}  {:>12.3}  {:>12.3}  {:>5}",
+                function.to_string(),
+                stats.calls,
+                stats.inclusive.as_secs_f64() * 1000.0,
+                stats.exclusive.as_secs_f64() * 1000.0,
+                stats.depth
+            ));
+        }
+        table
+    }
+
+    /// Stacks of functions with their exclusive time in nanoseconds, one per line,
+    /// in the folded format read by flamegraph tools
+    pub fn folded_stacks(&self) -> String {
+        let mut lines: Vec<String> = self
+            .stacks
+            .borrow()
+            .iter()
+            .map(|(stack, time)| format!("{} {}\n", stack.join(";"), time.as_nanos().max(1)))
+            .collect();
+        lines.sort();
+        lines.concat()
+    }
+}
+
+/// Runs a function, measuring the time spent in it
+pub fn call<T>(ctx: &dyn Context, name: &str, builtin: bool, body: impl FnOnce() -> T) -> T {
+    let Some(profiler) = ctx.hooks().and_then(|h| h.profiler.as_ref()) else {
+        return body();
+    };
+    profiler.enter(Function {
+        name: name.to_owned(),
+        builtin,
+    });
+    let result = body();
+    profiler.exit();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::{hooks::Hooks, test_utils::tests::run_with};
+
+    use super::{Function, Profiler};
+
+    const SOURCE: &str = "fn fib(n: int) -> int {
+    if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+fn main() {
+    print(fib(5));
+    print(length([1, 2]));
+}";
+
+    #[test]
+    fn stats() {
+        let (result, hooks) = run_with(SOURCE, |_| Hooks {
+            profiler: Some(Profiler::default()),
+            ..Hooks::default()
+        });
+        result.unwrap();
+        let profiler = hooks.profiler.unwrap();
+        let mut stats: Vec<(String, usize, usize)> = profiler
+            .stats()
+            .into_iter()
+            .map(|(function, stats)| {
+                assert!(stats.inclusive >= stats.exclusive);
+                (function.to_string(), stats.calls, stats.depth)
+            })
+            .collect();
+        stats.sort();
+        assert_eq!(
+            stats,
+            vec![
+                ("fib".to_owned(), 15, 5),
+                ("length (builtin)".to_owned(), 1, 1),
+                ("main".to_owned(), 1, 1),
+                ("print (builtin)".to_owned(), 2, 1)
+            ]
+        );
+        let function = |name: &str| Function {
+            name: name.to_owned(),
+            builtin: false,
+        };
+        let main = profiler.stats.borrow()[&function("main")].clone();
+        let fib = profiler.stats.borrow()[&function("fib")].clone();
+        assert!(main.inclusive >= fib.inclusive);
+    }
+
+    #[test]
+    fn table() {
+        let (result, hooks) = run_with(SOURCE, |_| Hooks {
+            profiler: Some(Profiler::default()),
+            ..Hooks::default()
+        });
+        result.unwrap();
+        let profiler = hooks.profiler.unwrap();
+        let table = profiler.table();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("function "));
+        assert!(lines[0].ends_with("   calls  inclusive ms  exclusive ms  depth"));
+        assert!(lines
+            .iter()
+            .any(|l| l.starts_with("print (builtin)") && l.ends_with("  1")));
+    }
+
+    #[test]
+    fn folded_stacks() {
+        let (result, hooks) = run_with(SOURCE, |_| Hooks {
+            profiler: Some(Profiler::default()),
+            ..Hooks::default()
+        });
+        result.unwrap();
+        let profiler = hooks.profiler.unwrap();
+        let folded = profiler.folded_stacks();
+        assert!(folded.ends_with('\n'));
+        assert!(folded.lines().all(|line| line
+            .rsplit_once(' ')
+            .unwrap()
+            .1
+            .parse::<u128>()
+            .unwrap()
+            > 0));
+        let stacks: Vec<&str> = folded
+            .lines()
+            .map(|line| line.rsplit_once(' ').unwrap().0)
+            .collect();
+        assert_eq!(
+            stacks,
+            vec![
+                "main",
+                "main;fib",
+                "main;fib;fib",
+                "main;fib;fib;fib",
+                "main;fib;fib;fib;fib",
+                "main;fib;fib;fib;fib;fib",
+                "main;length",
+                "main;print"
+            ]
+        );
+    }
+}
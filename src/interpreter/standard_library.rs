@@ -1,16 +1,18 @@
 use std::{
     cell::RefCell,
-    io::{Stdout, Write},
+    env,
+    io::{Stderr, Stdout, Write},
 };
 
-use crate::parser::grammar::Value;
+use crate::parser::grammar::{DataType, Value};
 
 use super::{callable::Callable, context::Context, ExecutionError, ExecutionErrorVariant};
 
-/// Possible write buffers for print function
+/// Possible write buffers for print function and traces
 #[allow(dead_code)]
 pub enum PrintOuts {
     Std(Stdout),
+    Err(Stderr),
     Vec(Vec<u8>),
 }
 
@@ -18,6 +20,7 @@ impl Write for PrintOuts {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         match self {
             PrintOuts::Std(s) => s.write(buf),
+            PrintOuts::Err(s) => s.write(buf),
             PrintOuts::Vec(s) => s.write(buf),
         }
     }
@@ -25,6 +28,7 @@ impl Write for PrintOuts {
     fn flush(&mut self) -> std::io::Result<()> {
         match self {
             PrintOuts::Std(s) => s.flush(),
+            PrintOuts::Err(s) => s.flush(),
             PrintOuts::Vec(s) => s.flush(),
         }
     }
@@ -59,7 +63,10 @@ impl Callable for CastInt {
     fn call(&self, _ctx: &dyn Context, args: Vec<Value>) -> Result<Value, ExecutionError> {
         if args.len() != 1 {
             return Err(ExecutionError::new(
-                ExecutionErrorVariant::InvalidArgumentCount,
+                ExecutionErrorVariant::InvalidArgumentCount {
+                    expected: 1,
+                    given: args.len(),
+                },
             ));
         }
         match &args[0] {
@@ -69,7 +76,10 @@ impl Callable for CastInt {
                 .parse::<i64>()
                 .map(Value::Int)
                 .map_err(|_| ExecutionError::new(ExecutionErrorVariant::CastFailed)),
-            _ => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType)),
+            v => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Integer, DataType::Float, DataType::String],
+                actual: v.into(),
+            })),
         }
     }
 }
@@ -84,7 +94,10 @@ impl Callable for CastFloat {
     fn call(&self, _ctx: &dyn Context, args: Vec<Value>) -> Result<Value, ExecutionError> {
         if args.len() != 1 {
             return Err(ExecutionError::new(
-                ExecutionErrorVariant::InvalidArgumentCount,
+                ExecutionErrorVariant::InvalidArgumentCount {
+                    expected: 1,
+                    given: args.len(),
+                },
             ));
         }
         match &args[0] {
@@ -94,7 +107,10 @@ impl Callable for CastFloat {
                 .parse::<f64>()
                 .map(Value::Float)
                 .map_err(|_| ExecutionError::new(ExecutionErrorVariant::CastFailed)),
-            _ => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType)),
+            v => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Integer, DataType::Float, DataType::String],
+                actual: v.into(),
+            })),
         }
     }
 }
@@ -109,7 +125,10 @@ impl Callable for CastString {
     fn call(&self, _ctx: &dyn Context, args: Vec<Value>) -> Result<Value, ExecutionError> {
         if args.len() != 1 {
             return Err(ExecutionError::new(
-                ExecutionErrorVariant::InvalidArgumentCount,
+                ExecutionErrorVariant::InvalidArgumentCount {
+                    expected: 1,
+                    given: args.len(),
+                },
             ));
         }
         match &args[0] {
@@ -129,7 +148,10 @@ impl Callable for CastBool {
     fn call(&self, _ctx: &dyn Context, args: Vec<Value>) -> Result<Value, ExecutionError> {
         if args.len() != 1 {
             return Err(ExecutionError::new(
-                ExecutionErrorVariant::InvalidArgumentCount,
+                ExecutionErrorVariant::InvalidArgumentCount {
+                    expected: 1,
+                    given: args.len(),
+                },
             ));
         }
         match &args[0] {
@@ -141,7 +163,15 @@ impl Callable for CastBool {
                 _ => Err(ExecutionError::new(ExecutionErrorVariant::CastFailed)),
             },
             Value::Bool(v) => Ok(Value::Bool(*v)),
-            _ => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType)),
+            v => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+                expected: vec![
+                    DataType::Integer,
+                    DataType::Float,
+                    DataType::String,
+                    DataType::Bool,
+                ],
+                actual: v.into(),
+            })),
         }
     }
 }
@@ -156,17 +186,13 @@ impl Callable for GetType {
     fn call(&self, _ctx: &dyn Context, args: Vec<Value>) -> Result<Value, ExecutionError> {
         if args.len() != 1 {
             return Err(ExecutionError::new(
-                ExecutionErrorVariant::InvalidArgumentCount,
+                ExecutionErrorVariant::InvalidArgumentCount {
+                    expected: 1,
+                    given: args.len(),
+                },
             ));
         }
-        match &args[0] {
-            Value::Int(_) => Ok(Value::String("int".to_owned())),
-            Value::Float(_) => Ok(Value::String("float".to_owned())),
-            Value::Bool(_) => Ok(Value::String("bool".to_owned())),
-            Value::String(_) => Ok(Value::String("string".to_owned())),
-            Value::List(_) => Ok(Value::String("list".to_owned())),
-            Value::None => Ok(Value::String("none".to_owned())),
-        }
+        Ok(Value::String(DataType::from(&args[0]).to_string()))
     }
 }
 
@@ -179,7 +205,10 @@ impl Callable for ListLength {
     fn call(&self, _ctx: &dyn Context, args: Vec<Value>) -> Result<Value, ExecutionError> {
         if args.len() != 1 {
             return Err(ExecutionError::new(
-                ExecutionErrorVariant::InvalidArgumentCount,
+                ExecutionErrorVariant::InvalidArgumentCount {
+                    expected: 1,
+                    given: args.len(),
+                },
             ));
         }
         if let Value::List(list) = &args[0] {
@@ -187,7 +216,10 @@ impl Callable for ListLength {
         } else if let Value::String(string) = &args[0] {
             Ok(Value::Int(string.len() as i64))
         } else {
-            Err(ExecutionError::new(ExecutionErrorVariant::InvalidType))
+            Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::List, DataType::String],
+                actual: (&args[0]).into(),
+            }))
         }
     }
 }
@@ -201,20 +233,126 @@ impl Callable for ListPush {
     fn call(&self, _ctx: &dyn Context, args: Vec<Value>) -> Result<Value, ExecutionError> {
         if args.len() != 2 {
             return Err(ExecutionError::new(
-                ExecutionErrorVariant::InvalidArgumentCount,
+                ExecutionErrorVariant::InvalidArgumentCount {
+                    expected: 2,
+                    given: args.len(),
+                },
             ));
         }
         let mut args = args.into_iter();
-        if let Value::List(mut list) = args.next().unwrap() {
-            let v = args.next().unwrap();
-            list.push(v);
-            Ok(Value::List(list))
-        } else {
-            Err(ExecutionError::new(ExecutionErrorVariant::InvalidType))
+        match args.next().unwrap() {
+            Value::List(mut list) => {
+                list.push(args.next().unwrap());
+                Ok(Value::List(list))
+            }
+            v => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::List],
+                actual: (&v).into(),
+            })),
         }
     }
 }
 
+/// Returns the value of an environment variable as a `string`.
+/// Accepts the variable name and an optional default value of any type.
+///
+/// Returns the default value or `none` when the variable isn't set.
+/// Fails when name is not a `string` or wrong amount of arguments.
+pub struct GetEnv;
+
+impl Callable for GetEnv {
+    fn call(&self, _ctx: &dyn Context, args: Vec<Value>) -> Result<Value, ExecutionError> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(ExecutionError::new(
+                ExecutionErrorVariant::InvalidArgumentCount {
+                    expected: args.len().clamp(1, 2),
+                    given: args.len(),
+                },
+            ));
+        }
+        let mut args = args.into_iter();
+        match args.next().unwrap() {
+            Value::String(name) => Ok(env::var(name)
+                .map(Value::String)
+                .unwrap_or_else(|_| args.next().unwrap_or(Value::None))),
+            v => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::String],
+                actual: (&v).into(),
+            })),
+        }
+    }
+}
+
+/// Names of all standard functions
+pub const BUILTINS: [&str; 9] = [
+    "print",
+    "cast_int",
+    "cast_float",
+    "cast_string",
+    "cast_bool",
+    "type",
+    "length",
+    "push",
+    "env",
+];
+
+/// Static description of a standard function
+pub struct Signature {
+    /// Types accepted as the first argument, any type when empty
+    pub accepts: &'static [DataType],
+    /// Type of the returned value, unknown when it depends on the arguments
+    pub returns: Option<DataType>,
+}
+
+/// Returns the signature of a standard function
+pub fn signature(name: &str) -> Option<Signature> {
+    let (accepts, returns): (&[DataType], _) = match name {
+        "print" => (&[], Some(DataType::None)),
+        "cast_int" => (
+            &[DataType::Integer, DataType::Float, DataType::String],
+            Some(DataType::Integer),
+        ),
+        "cast_float" => (
+            &[DataType::Integer, DataType::Float, DataType::String],
+            Some(DataType::Float),
+        ),
+        "cast_string" => (&[], Some(DataType::String)),
+        "cast_bool" => (
+            &[
+                DataType::Integer,
+                DataType::Float,
+                DataType::String,
+                DataType::Bool,
+            ],
+            Some(DataType::Bool),
+        ),
+        "type" => (&[], Some(DataType::String)),
+        "length" => (&[DataType::List, DataType::String], Some(DataType::Integer)),
+        "push" => (&[DataType::List], Some(DataType::List)),
+        "env" => (&[DataType::String], None),
+        _ => return None,
+    };
+    Some(Signature { accepts, returns })
+}
+
+/// Names of standard functions in other languages, which are too different to be suggested otherwise
+const ALIASES: [(&str, &str); 6] = [
+    ("append", "push"),
+    ("len", "length"),
+    ("size", "length"),
+    ("typeof", "type"),
+    ("println", "print"),
+    ("getenv", "env"),
+];
+
+/// Returns the standard function known under another name
+pub fn alias_of(name: &str) -> Option<&'static str> {
+    ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, builtin)| *builtin)
+}
+
 /// Standard library context.
 ///
 /// Provides standard functions without the ability to store variables
@@ -227,6 +365,7 @@ pub struct StandardCtx {
     pub std_type: GetType,
     pub std_length: ListLength,
     pub std_push: ListPush,
+    pub std_env: GetEnv,
 }
 
 impl StandardCtx {
@@ -240,26 +379,27 @@ impl StandardCtx {
             std_type: GetType,
             std_length: ListLength,
             std_push: ListPush,
+            std_env: GetEnv,
         }
     }
 }
 
 impl Context for StandardCtx {
-    fn get_variable(&self, _id: &str) -> Result<Value, ExecutionError> {
+    fn get_variable(&self, id: &str) -> Result<Value, ExecutionError> {
         Err(ExecutionError::new(
-            ExecutionErrorVariant::VariableDoesNotExist,
+            ExecutionErrorVariant::VariableDoesNotExist(id.to_owned()),
         ))
     }
 
-    fn set_variable(&self, _id: &str, _value: Value) -> Result<(), ExecutionError> {
+    fn set_variable(&self, id: &str, _value: Value) -> Result<(), ExecutionError> {
         Err(ExecutionError::new(
-            ExecutionErrorVariant::VariableDoesNotExist,
+            ExecutionErrorVariant::VariableDoesNotExist(id.to_owned()),
         ))
     }
 
-    fn new_variable(&self, _id: &str, _value: Value) -> Result<(), ExecutionError> {
+    fn new_variable(&self, id: &str, _value: Value) -> Result<(), ExecutionError> {
         Err(ExecutionError::new(
-            ExecutionErrorVariant::VariableAlreadyExists,
+            ExecutionErrorVariant::VariableAlreadyExists(id.to_owned()),
         ))
     }
 
@@ -281,8 +421,9 @@ impl Context for StandardCtx {
             "type" => self.std_type.call(self, args),
             "length" => self.std_length.call(self, args),
             "push" => self.std_push.call(self, args),
+            "env" => self.std_env.call(self, args),
             _ => Err(ExecutionError::new(
-                ExecutionErrorVariant::FunctionDoesNotExist,
+                ExecutionErrorVariant::FunctionDoesNotExist(id.to_owned()),
             )),
         }
     }
@@ -290,6 +431,10 @@ impl Context for StandardCtx {
     fn name(&self) -> String {
         unreachable!()
     }
+
+    fn function_names(&self) -> Vec<String> {
+        BUILTINS.iter().map(|b| b.to_string()).collect()
+    }
 }
 
 #[cfg(test)]
@@ -300,12 +445,13 @@ mod tests {
         interpreter::{
             callable::Callable,
             standard_library::{
-                CastFloat, CastInt, CastString, GetType, ListLength, ListPush, PrintOuts,
+                signature, CastFloat, CastInt, CastString, GetEnv, GetType, ListLength, ListPush,
+                PrintOuts, BUILTINS,
             },
             test_utils::tests::TestCtx,
             ExecutionErrorVariant,
         },
-        parser::grammar::Value,
+        parser::grammar::{DataType, Value},
     };
 
     use super::Print;
@@ -370,29 +516,44 @@ mod tests {
                 .call(&ctx, vec![Value::Bool(true)])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Integer, DataType::Float, DataType::String],
+                actual: DataType::Bool
+            }
         );
         assert_eq!(
             cast_int.call(&ctx, vec![Value::None]).unwrap_err().variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Integer, DataType::Float, DataType::String],
+                actual: DataType::None
+            }
         );
         assert_eq!(
             cast_int
                 .call(&ctx, vec![Value::List(vec![])])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Integer, DataType::Float, DataType::String],
+                actual: DataType::List
+            }
         );
         assert_eq!(
             cast_int
                 .call(&ctx, vec![Value::Int(8), Value::Int(8)])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 2
+            }
         );
         assert_eq!(
             cast_int.call(&ctx, vec![]).unwrap_err().variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 0
+            }
         );
     }
 
@@ -446,32 +607,47 @@ mod tests {
                 .call(&ctx, vec![Value::Bool(true)])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Integer, DataType::Float, DataType::String],
+                actual: DataType::Bool
+            }
         );
         assert_eq!(
             cast_float
                 .call(&ctx, vec![Value::None])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Integer, DataType::Float, DataType::String],
+                actual: DataType::None
+            }
         );
         assert_eq!(
             cast_float
                 .call(&ctx, vec![Value::List(vec![])])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Integer, DataType::Float, DataType::String],
+                actual: DataType::List
+            }
         );
         assert_eq!(
             cast_float
                 .call(&ctx, vec![Value::Float(8.0), Value::Float(8.0)])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 2
+            }
         );
         assert_eq!(
             cast_float.call(&ctx, vec![]).unwrap_err().variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 0
+            }
         );
     }
 
@@ -522,14 +698,20 @@ mod tests {
         let ctx = TestCtx::new();
         assert_eq!(
             cast_string.call(&ctx, vec![]).unwrap_err().variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 0
+            }
         );
         assert_eq!(
             cast_string
                 .call(&ctx, vec![Value::Float(8.5), Value::Float(8.5)])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 2
+            }
         );
     }
 
@@ -576,14 +758,20 @@ mod tests {
         let ctx = TestCtx::new();
         assert_eq!(
             get_type.call(&ctx, vec![]).unwrap_err().variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 0
+            }
         );
         assert_eq!(
             get_type
                 .call(&ctx, vec![Value::Float(8.5), Value::Float(8.5)])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 2
+            }
         );
     }
 
@@ -633,36 +821,54 @@ mod tests {
         let ctx = TestCtx::new();
         assert_eq!(
             length.call(&ctx, vec![]).unwrap_err().variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 0
+            }
         );
         assert_eq!(
             length.call(&ctx, vec![Value::Int(0)]).unwrap_err().variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::List, DataType::String],
+                actual: DataType::Integer
+            }
         );
         assert_eq!(
             length
                 .call(&ctx, vec![Value::Float(1.0)])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::List, DataType::String],
+                actual: DataType::Float
+            }
         );
         assert_eq!(
             length
                 .call(&ctx, vec![Value::Bool(true)])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::List, DataType::String],
+                actual: DataType::Bool
+            }
         );
         assert_eq!(
             length.call(&ctx, vec![Value::None]).unwrap_err().variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::List, DataType::String],
+                actual: DataType::None
+            }
         );
         assert_eq!(
             length
                 .call(&ctx, vec![Value::Int(8), Value::None])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 2
+            }
         );
     }
 
@@ -688,23 +894,101 @@ mod tests {
         let ctx = TestCtx::new();
         assert_eq!(
             push.call(&ctx, vec![]).unwrap_err().variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 2,
+                given: 0
+            }
         );
         assert_eq!(
             push.call(&ctx, vec![Value::Int(0)]).unwrap_err().variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 2,
+                given: 1
+            }
         );
         assert_eq!(
             push.call(&ctx, vec![Value::Int(0), Value::Int(0), Value::Int(0)])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 2,
+                given: 3
+            }
         );
         assert_eq!(
             push.call(&ctx, vec![Value::Int(0), Value::Int(0)])
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::List],
+                actual: DataType::Integer
+            }
         );
     }
+
+    #[test]
+    fn env_ok() {
+        let get_env = GetEnv;
+        let ctx = TestCtx::new();
+        std::env::set_var("INTERPRETER_TEST_ENV_OK", "value");
+        assert_eq!(
+            get_env
+                .call(
+                    &ctx,
+                    vec![Value::String("INTERPRETER_TEST_ENV_OK".to_owned())]
+                )
+                .unwrap(),
+            Value::String("value".to_owned())
+        );
+        assert_eq!(
+            get_env
+                .call(
+                    &ctx,
+                    vec![Value::String("INTERPRETER_TEST_ENV_UNSET".to_owned())]
+                )
+                .unwrap(),
+            Value::None
+        );
+        assert_eq!(
+            get_env
+                .call(
+                    &ctx,
+                    vec![
+                        Value::String("INTERPRETER_TEST_ENV_UNSET".to_owned()),
+                        Value::Int(3)
+                    ]
+                )
+                .unwrap(),
+            Value::Int(3)
+        );
+    }
+
+    #[test]
+    fn env_fail() {
+        let get_env = GetEnv;
+        let ctx = TestCtx::new();
+        assert_eq!(
+            get_env.call(&ctx, vec![Value::Int(8)]).unwrap_err().variant,
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::String],
+                actual: DataType::Integer
+            }
+        );
+        assert_eq!(
+            get_env.call(&ctx, vec![]).unwrap_err().variant,
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 0
+            }
+        );
+    }
+
+    #[test]
+    fn signatures() {
+        assert!(BUILTINS.iter().all(|b| signature(b).is_some()));
+        assert!(signature("main").is_none());
+        let length = signature("length").unwrap();
+        assert_eq!(length.accepts, &[DataType::List, DataType::String]);
+        assert_eq!(length.returns, Some(DataType::Integer));
+    }
 }
@@ -0,0 +1,75 @@
+/// Maximum number of suggested names
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Edit distance between 2 strings, counting insertions, deletions,
+/// substitutions and transpositions of adjacent characters
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            rows[i][j] = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                rows[i][j] = rows[i][j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Returns the candidates closest to the name, closest first.
+/// Candidates which differ in more than a third of the name are skipped.
+pub fn closest(name: &str, candidates: Vec<String>) -> Vec<String> {
+    let limit = name.chars().count().div_ceil(3);
+    let mut scored: Vec<(usize, String)> = candidates
+        .into_iter()
+        .filter(|c| c != name)
+        .map(|c| (edit_distance(name, &c), c))
+        .filter(|(distance, _)| *distance <= limit)
+        .collect();
+    scored.sort();
+    scored.dedup();
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, c)| c)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{closest, edit_distance};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", ""), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("pritn", "print"), 1);
+        assert_eq!(edit_distance("lenght", "length"), 1);
+    }
+
+    #[test]
+    fn closest_first() {
+        assert_eq!(
+            closest("lenght", names(&["length", "print", "lengths", "push"])),
+            names(&["length", "lengths"])
+        );
+        assert_eq!(closest("x", names(&["y", "x"])), names(&["y"]));
+        assert!(closest("append", names(&["push", "print"])).is_empty());
+    }
+}
@@ -10,6 +10,12 @@ pub mod tests {
         parser::grammar::Value,
     };
 
+    #[cfg(test)]
+    use crate::{
+        interpreter::{hooks::Hooks, standard_library::PrintOuts},
+        parser::{grammar::program::Program, parse_source},
+    };
+
     pub struct TestCtx {
         pub functions: HashMap<String, Box<dyn Callable>>,
         pub variables: RefCell<HashMap<String, Value>>,
@@ -33,7 +39,7 @@ pub mod tests {
                 Ok(v.clone())
             } else {
                 Err(ExecutionError::new(
-                    ExecutionErrorVariant::VariableDoesNotExist,
+                    ExecutionErrorVariant::VariableDoesNotExist(id.to_owned()),
                 ))
             }
         }
@@ -45,7 +51,7 @@ pub mod tests {
                 Ok(())
             } else {
                 Err(ExecutionError::new(
-                    ExecutionErrorVariant::VariableDoesNotExist,
+                    ExecutionErrorVariant::VariableDoesNotExist(id.to_owned()),
                 ))
             }
         }
@@ -56,7 +62,7 @@ pub mod tests {
                 Ok(())
             } else {
                 Err(ExecutionError::new(
-                    ExecutionErrorVariant::VariableAlreadyExists,
+                    ExecutionErrorVariant::VariableAlreadyExists(id.to_owned()),
                 ))
             }
         }
@@ -74,7 +80,7 @@ pub mod tests {
                 func.call(self, args)
             } else {
                 Err(ExecutionError::new(
-                    ExecutionErrorVariant::FunctionDoesNotExist,
+                    ExecutionErrorVariant::FunctionDoesNotExist(id.to_owned()),
                 ))
             }
         }
@@ -82,5 +88,35 @@ pub mod tests {
         fn name(&self) -> String {
             "test ctx".to_owned()
         }
+
+        fn variable_names(&self) -> Vec<String> {
+            self.variables.borrow().keys().cloned().collect()
+        }
+
+        fn function_names(&self) -> Vec<String> {
+            self.functions.keys().cloned().collect()
+        }
+    }
+
+    /// Runs a program with its prints captured and the hooks configured for it,
+    /// returning its result along with the hooks, whose reports can be read afterwards
+    #[cfg(test)]
+    pub fn run_with(
+        source: &str,
+        configure: impl FnOnce(&Program) -> Hooks,
+    ) -> (Result<Value, ExecutionError>, Hooks) {
+        let program: Program = parse_source(source, |p| p.parse()).complete().unwrap();
+        program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]));
+        let hooks = configure(&program);
+        (program.run(vec![], &hooks), hooks)
+    }
+
+    /// Returns the text written to a captured output
+    #[cfg(test)]
+    pub fn captured(out: RefCell<PrintOuts>) -> String {
+        match out.into_inner() {
+            PrintOuts::Vec(buffer) => String::from_utf8(buffer).unwrap(),
+            _ => unreachable!(),
+        }
     }
 }
@@ -0,0 +1,163 @@
+use std::{
+    cell::{Cell, RefCell},
+    io::Write,
+};
+
+use crate::parser::{grammar::Value, position::Span};
+
+use super::{context::Context, standard_library::PrintOuts, ExecutionError};
+
+/// How much of the execution is logged
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceLevel {
+    /// Function entries and exits, loop iterations
+    Calls,
+    /// Also every evaluated expression along with its result
+    Expressions,
+}
+
+/// Logs the execution of a program, indenting the events by the depth of calls and loops
+pub struct Tracer {
+    level: TraceLevel,
+    depth: Cell<usize>,
+    pub out: RefCell<PrintOuts>,
+}
+
+impl Tracer {
+    pub fn new(level: TraceLevel, out: PrintOuts) -> Self {
+        Self {
+            level,
+            depth: Cell::new(0),
+            out: RefCell::new(out),
+        }
+    }
+
+    fn log(&self, event: &str) {
+        let indent = "  ".repeat(self.depth.get());
+        // Tracing is best-effort, it must not stop the program
+        let _ = writeln!(self.out.borrow_mut(), "{indent}{event}");
+    }
+
+    /// Runs the body with the events logged within indented one level deeper
+    fn nested<T>(&self, body: impl FnOnce() -> T) -> T {
+        self.depth.set(self.depth.get() + 1);
+        let result = body();
+        self.depth.set(self.depth.get() - 1);
+        result
+    }
+}
+
+/// Runs a user function, logging its arguments and its result
+pub fn call(
+    ctx: &dyn Context,
+    function: &str,
+    args: Vec<Value>,
+    body: impl FnOnce(Vec<Value>) -> Result<Value, ExecutionError>,
+) -> Result<Value, ExecutionError> {
+    let Some(tracer) = ctx.hooks().and_then(|h| h.tracer.as_ref()) else {
+        return body(args);
+    };
+    let shown: Vec<String> = args.iter().map(Value::repr).collect();
+    tracer.log(&format!("call `{function}`({})", shown.join(", ")));
+    let result = tracer.nested(|| body(args));
+    match &result {
+        Ok(value) => tracer.log(&format!("`{function}` returned {}", value.repr())),
+        Err(e) => tracer.log(&format!("`{function}` failed: {}", e.variant)),
+    }
+    result
+}
+
+/// Runs an iteration of a loop, logging it along with the value of the loop variable
+pub fn iteration<T>(
+    ctx: &dyn Context,
+    name: &str,
+    number: usize,
+    variable: Option<&str>,
+    body: impl FnOnce() -> T,
+) -> T {
+    let Some(tracer) = ctx.hooks().and_then(|h| h.tracer.as_ref()) else {
+        return body();
+    };
+    match variable.map(|id| (id, ctx.get_variable(id))) {
+        Some((id, Ok(value))) => tracer.log(&format!(
+            "{name} iteration {number}: {id} = {}",
+            value.repr()
+        )),
+        _ => tracer.log(&format!("{name} iteration {number}")),
+    }
+    tracer.nested(body)
+}
+
+/// Evaluates an expression, logging its result when expressions are traced.
+/// Results of the inner expressions are logged before it, one level deeper.
+pub fn expression(
+    ctx: &dyn Context,
+    span: Span,
+    body: impl FnOnce() -> Result<Value, ExecutionError>,
+) -> Result<Value, ExecutionError> {
+    let tracer = match ctx.hooks().and_then(|h| h.tracer.as_ref()) {
+        Some(tracer) if tracer.level == TraceLevel::Expressions => tracer,
+        _ => return body(),
+    };
+    let result = tracer.nested(body);
+    match &result {
+        Ok(value) => tracer.log(&format!("{} = {}", span.start, value.repr())),
+        Err(e) => tracer.log(&format!("{} failed: {}", span.start, e.variant)),
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interpreter::{
+        hooks::Hooks,
+        standard_library::PrintOuts,
+        test_utils::tests::{captured, run_with},
+    };
+
+    use super::{TraceLevel, Tracer};
+
+    #[test]
+    fn calls() {
+        let source = "fn twice(s: string) -> string {\n    s + s\n}\nfn main() {\n    let i: int = 0;\n    while i < 2 {\n        for x in [twice(\"a\")] {};\n        i = i + 1;\n    };\n    i / 0;\n}";
+        let (_, hooks) = run_with(source, |_| Hooks {
+            tracer: Some(Tracer::new(TraceLevel::Calls, PrintOuts::Vec(vec![]))),
+            ..Hooks::default()
+        });
+        assert_eq!(
+            captured(hooks.tracer.unwrap().out),
+            "call `main`()
+  while loop iteration 1
+    call `twice`(\"a\")
+    `twice` returned \"aa\"
+    for loop iteration 1: x = \"aa\"
+  while loop iteration 2
+    call `twice`(\"a\")
+    `twice` returned \"aa\"
+    for loop iteration 1: x = \"aa\"
+`main` failed: division by zero
+"
+        );
+    }
+
+    #[test]
+    fn expressions() {
+        let source = "fn main() -> int {\n    1 + [2][0]\n}";
+        let (_, hooks) = run_with(source, |_| Hooks {
+            tracer: Some(Tracer::new(TraceLevel::Expressions, PrintOuts::Vec(vec![]))),
+            ..Hooks::default()
+        });
+        assert_eq!(
+            captured(hooks.tracer.unwrap().out),
+            "call `main`()
+    Ln 2, Col 5 = 1
+        Ln 2, Col 10 = 2
+      Ln 2, Col 9 = [2]
+      Ln 2, Col 13 = 0
+    Ln 2, Col 9 = 2
+  Ln 2, Col 5 = 3
+`main` returned 3
+"
+        );
+    }
+}
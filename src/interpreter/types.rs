@@ -2,6 +2,18 @@ use crate::parser::grammar::{DataType, Value};
 
 use super::{ExecutionError, ExecutionErrorVariant};
 
+/// Returns an arbitrary value of a data type, so runtime rules can be applied to types alone
+pub fn sample(t: DataType) -> Value {
+    match t {
+        DataType::Integer => Value::Int(1),
+        DataType::Float => Value::Float(1.0),
+        DataType::Bool => Value::Bool(true),
+        DataType::String => Value::String(String::new()),
+        DataType::List => Value::List(vec![]),
+        DataType::None => Value::None,
+    }
+}
+
 /// Validates a value against a data type
 pub fn validate_type(t: DataType, v: &Value) -> Result<(), ExecutionError> {
     match (t, v) {
@@ -11,11 +23,14 @@ pub fn validate_type(t: DataType, v: &Value) -> Result<(), ExecutionError> {
         (DataType::String, Value::String(_)) => Ok(()),
         (DataType::List, Value::List(_)) => Ok(()),
         (DataType::None, Value::None) => Ok(()),
-        _ => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType)),
+        _ => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+            expected: vec![t],
+            actual: v.into(),
+        })),
     }
 }
 
-/// Validates 2 values against each other
+/// Validates 2 values against each other, `none` is never valid
 pub fn validate_types(l: &Value, r: &Value) -> Result<(), ExecutionError> {
     match (r, l) {
         (Value::Int(_), Value::Int(_)) => Ok(()),
@@ -23,6 +38,19 @@ pub fn validate_types(l: &Value, r: &Value) -> Result<(), ExecutionError> {
         (Value::Bool(_), Value::Bool(_)) => Ok(()),
         (Value::String(_), Value::String(_)) => Ok(()),
         (Value::List(_), Value::List(_)) => Ok(()),
-        _ => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType)),
+        (_, Value::None) => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+            expected: vec![
+                DataType::Integer,
+                DataType::Float,
+                DataType::Bool,
+                DataType::String,
+                DataType::List,
+            ],
+            actual: l.into(),
+        })),
+        _ => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+            expected: vec![l.into()],
+            actual: r.into(),
+        })),
     }
 }
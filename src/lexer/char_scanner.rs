@@ -30,15 +30,10 @@ impl CharScanner {
 
     /// Returns next character, EOF is marked as end of text ASCII character
     fn next_char(&mut self) -> char {
-        if let Some(c) = self
-            .source
+        self.source
             .read_char()
             .expect("Unexpected error while reading input.")
-        {
-            c
-        } else {
-            '\x03'
-        }
+            .unwrap_or('\x03')
     }
 
     /// Replaces newlines with `'\n'`
@@ -1,5 +1,7 @@
+use serde::Serialize;
+
 /// Possible keywords
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum Keyword {
     Int,
     Float,
@@ -1,14 +1,17 @@
 use std::{error::Error, fmt::Display};
 
+use serde::Serialize;
+
 use crate::{
     lexer::position::Position,
     lexer::{keywords::Keyword, operators::Operator},
+    locale,
     scannable::Scannable,
 };
 
 use super::char_scanner::CharScanner;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub enum LexemType {
     Operator(Operator),
     Keyword(Keyword),
@@ -19,9 +22,7 @@ pub enum LexemType {
     Int(i64),
 }
 
-impl Eq for LexemType {
-    fn assert_receiver_is_total_eq(&self) {}
-}
+impl Eq for LexemType {}
 
 impl Display for LexemType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -37,7 +38,7 @@ impl Display for LexemType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Lexem {
     pub lexem_type: LexemType,
     pub start: Position,
@@ -79,22 +80,35 @@ pub enum LexerWarningVariant {
 
 impl Display for LexerWarningVariant {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            LexerWarningVariant::InvalidEscapeCharacter(c) => locale::message(self.code(), &[c]),
+            LexerWarningVariant::InvalidSequence(s) => locale::message(self.code(), &[s]),
+            _ => locale::message(self.code(), &[]),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl LexerWarningVariant {
+    /// Stable code identifying the problem, explained by `--explain`
+    pub fn code(&self) -> &'static str {
         match self {
-            LexerWarningVariant::CommentNeverEnds => f.write_str("comment never ends"),
-            LexerWarningVariant::CommentTooLong => f.write_str("comment too long"),
-            LexerWarningVariant::StringNeverEnds => f.write_str("string never ends"),
-            LexerWarningVariant::StringTooLong => f.write_str("string too long"),
-            LexerWarningVariant::IntegerPartTooBig => f.write_str("integer part too big"),
-            LexerWarningVariant::DecimalPartTooBig => f.write_str("decimal part too big"),
-            LexerWarningVariant::IdentifierTooLong => f.write_str("identifier too long"),
-            LexerWarningVariant::InvalidEscapeCharacter(c) => {
-                f.write_fmt(format_args!("invalid escape character `\\{}`", c))
-            }
-            LexerWarningVariant::InvalidSequence(s) => {
-                f.write_fmt(format_args!("invalid character sequence `{}`", s))
-            }
+            LexerWarningVariant::CommentNeverEnds => "L0001",
+            LexerWarningVariant::CommentTooLong => "L0002",
+            LexerWarningVariant::StringNeverEnds => "L0003",
+            LexerWarningVariant::StringTooLong => "L0004",
+            LexerWarningVariant::IntegerPartTooBig => "L0005",
+            LexerWarningVariant::DecimalPartTooBig => "L0006",
+            LexerWarningVariant::IdentifierTooLong => "L0007",
+            LexerWarningVariant::InvalidEscapeCharacter(_) => "L0008",
+            LexerWarningVariant::InvalidSequence(_) => "L0009",
         }
     }
+
+    /// Suggestion on how to fix the problem
+    pub fn hint(&self) -> &'static str {
+        locale::hint(self.code())
+    }
 }
 
 #[derive(Debug)]
@@ -117,7 +131,7 @@ impl Error for LexerWarning {}
 
 impl<'a> LexemBuilder<'a> {
     pub fn new(scanner: &'a mut CharScanner, errors: &'a mut Vec<LexerWarning>) -> Self {
-        let start = (&*scanner).last_pos();
+        let start = scanner.last_pos();
         Self {
             scanner,
             start,
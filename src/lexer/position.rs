@@ -1,7 +1,10 @@
 use std::fmt::Display;
 
+use crate::locale;
+use serde::Serialize;
+
 /// Position of a lexem
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub struct Position {
     pub row: usize,
     pub col: usize,
@@ -28,7 +31,7 @@ impl Default for Position {
 
 impl Display for Position {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("Ln {}, Col {}", self.row, self.col))
+        f.write_str(&locale::message("position", &[&self.row, &self.col]))
     }
 }
 
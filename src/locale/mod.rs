@@ -0,0 +1,175 @@
+use std::{cell::Cell, env, fmt::Display};
+
+/// Message catalogues, one `key = message` per line
+const ENGLISH: &str = include_str!("en.txt");
+const POLISH: &str = include_str!("pl.txt");
+
+/// Language of the diagnostics shown to the user
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    English,
+    Polish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Polish];
+
+    /// Parses a language tag like `pl` or a locale like `pl_PL.UTF-8`
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['_', '-', '.', '@']).next().unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|l| l.tag().eq_ignore_ascii_case(language))
+    }
+
+    /// Reads the language from the `LANG` environment variable
+    pub fn from_env() -> Option<Self> {
+        Self::from_tag(&env::var("LANG").ok()?)
+    }
+
+    /// Two-letter ISO 639-1 code of the language
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Polish => "pl",
+        }
+    }
+
+    fn catalogue(&self) -> &'static str {
+        match self {
+            Language::English => ENGLISH,
+            Language::Polish => POLISH,
+        }
+    }
+
+    /// Finds a message of the catalogue
+    fn lookup(&self, key: &str) -> Option<&'static str> {
+        self.catalogue()
+            .lines()
+            .filter_map(|line| line.split_once(" = "))
+            .find(|(k, _)| *k == key)
+            .map(|(_, message)| message)
+    }
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+thread_local! {
+    static LANGUAGE: Cell<Language> = const { Cell::new(Language::English) };
+}
+
+/// Sets the language of the messages created by the current thread
+pub fn set_language(language: Language) {
+    LANGUAGE.with(|l| l.set(language));
+}
+
+/// Returns a message in the current language, falling back to English
+pub fn text(key: &str) -> Option<&'static str> {
+    LANGUAGE
+        .with(Cell::get)
+        .lookup(key)
+        .or_else(|| Language::English.lookup(key))
+}
+
+/// Returns a message in the current language, with `{0}`, `{1}`, ... replaced by the arguments.
+/// Unknown keys are returned as they are.
+pub fn message(key: &str, args: &[&dyn Display]) -> String {
+    let mut message = text(key).unwrap_or(key).to_owned();
+    for (i, arg) in args.iter().enumerate() {
+        message = message.replace(&format!("{{{i}}}"), &arg.to_string());
+    }
+    message
+}
+
+/// Like `message`, but picks the singular form `<key>.one` for a count of 1,
+/// if the current language has one
+pub fn counted(key: &str, count: usize, args: &[&dyn Display]) -> String {
+    let singular = format!("{key}.one");
+    match LANGUAGE.with(Cell::get).lookup(&singular) {
+        Some(_) if count == 1 => message(&singular, args),
+        _ => message(key, args),
+    }
+}
+
+/// Returns the hint of a diagnostic code in the current language, empty if there is none
+pub fn hint(code: &str) -> &'static str {
+    text(&format!("{code}.hint")).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{counted, hint, message, set_language, Language, ENGLISH, POLISH};
+
+    fn keys(catalogue: &str) -> Vec<&str> {
+        catalogue
+            .lines()
+            .filter_map(|line| line.split_once(" = "))
+            .map(|(key, _)| key)
+            .collect()
+    }
+
+    #[test]
+    fn from_tag() {
+        assert_eq!(Language::from_tag("pl"), Some(Language::Polish));
+        assert_eq!(Language::from_tag("pl_PL.UTF-8"), Some(Language::Polish));
+        assert_eq!(Language::from_tag("EN-us"), Some(Language::English));
+        assert_eq!(Language::from_tag("C"), None);
+        assert_eq!(Language::from_tag(""), None);
+    }
+
+    #[test]
+    fn complete_catalogues() {
+        // Plural forms are optional, Polish messages avoid them
+        let english: Vec<&str> = keys(ENGLISH)
+            .into_iter()
+            .filter(|key| !key.ends_with(".one"))
+            .collect();
+        assert_eq!(keys(POLISH), english);
+        for key in english {
+            let placeholders = |language: Language| {
+                let message = language.lookup(key).unwrap();
+                (0..3)
+                    .filter(|i| message.contains(&format!("{{{i}}}")))
+                    .count()
+            };
+            assert_eq!(
+                placeholders(Language::English),
+                placeholders(Language::Polish)
+            );
+        }
+    }
+
+    #[test]
+    fn messages() {
+        assert_eq!(
+            message("R0008", &[&5, &2]),
+            "index 5 out of bounds for length 2"
+        );
+        assert_eq!(hint("P0106"), "add `:`");
+        assert_eq!(hint("R0012"), "");
+        assert_eq!(message("unknown", &[]), "unknown");
+        assert_eq!(
+            counted("R0009", 1, &[&1, &2]),
+            "expected 1 argument, 2 given"
+        );
+        assert_eq!(
+            counted("R0009", 2, &[&2, &1]),
+            "expected 2 arguments, 1 given"
+        );
+        set_language(Language::Polish);
+        assert_eq!(
+            message("R0008", &[&5, &2]),
+            "indeks 5 poza zakresem dla długości 2"
+        );
+        assert_eq!(hint("P0106"), "dodaj `:`");
+        assert_eq!(
+            counted("R0009", 1, &[&1, &2]),
+            "oczekiwana liczba argumentów: 1, podana: 2"
+        );
+    }
+}
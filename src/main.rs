@@ -1,223 +1,528 @@
 use std::{
-    env,
-    fmt::Display,
-    fs::OpenOptions,
-    io::{stdin, BufRead, BufReader},
-    path::PathBuf,
+    env, fs,
+    io::{stderr, stdin, stdout, Cursor, IsTerminal, Read},
+    path::Path,
 };
 
-use lexer::{lexem::LexerWarning, Lexer};
+use cli::{
+    exit_codes, parse_args, Analysis, AppError, ErrorDump, InputType, Instrumentation,
+    OutputFormat, ParsedArgs, MANUAL,
+};
+use diagnostics::{
+    explanations::explain, render::eprint_all, sarif::to_sarif, Diagnostic, Origin, Severity,
+};
+use interpreter::{
+    context::Snapshot, coverage::Coverage, debugger::Debugger, hooks::Hooks,
+    memory::MemoryProfiler, profiler::Profiler, standard_library::PrintOuts, trace::Tracer,
+    ExecutionErrorVariant,
+};
+use lexer::Lexer;
+use locale::Language;
 use parser::{
-    grammar::program::Program, token_scanner::TokenScanner, Parser, ParserError, ParserWarning,
+    grammar::{program::Program, Value},
+    parse_source, Parsed,
 };
 
+mod analysis;
+mod cli;
+mod diagnostics;
+mod golden;
 mod interpreter;
 mod lexer;
+mod locale;
 mod parser;
+mod repl;
 mod scannable;
 
-/// Source of code
-enum InputType {
-    Standard,
-    File(PathBuf),
+/// Entry point
+fn main() {
+    match app() {
+        Ok(code) => std::process::exit(code as i32),
+        Err(e) => {
+            eprintln!("{}", e.msg);
+            std::process::exit(e.code as i32);
+        }
+    }
 }
 
-/// Information about execution derived from input parameters
-enum ParsedArgs {
-    InstructionManual,
-    Run(InputType),
+/// Run application, returning the process exit code
+fn app() -> Result<u8, AppError> {
+    let (args, language) = parse_args(env::args().skip(1))?;
+    locale::set_language(language.or_else(Language::from_env).unwrap_or_default());
+    match args {
+        ParsedArgs::InstructionManual => {
+            println!("{MANUAL}");
+            Ok(0)
+        }
+        ParsedArgs::Interactive => {
+            repl::run();
+            Ok(0)
+        }
+        ParsedArgs::Explain(code) => explain_code(&code).map(|_| 0),
+        ParsedArgs::Eval(source) => Ok(eval(&source)),
+        ParsedArgs::Test(dir) => test(&dir),
+        ParsedArgs::Run(input, args, analysis, instrumentation) => {
+            run(input, args, analysis, instrumentation)
+        }
+        ParsedArgs::DumpTokens(input, format) => dump_tokens(input, format).map(|_| 0),
+        ParsedArgs::DumpAst(input, format) => dump_ast(input, format),
+        ParsedArgs::Check(input, format, deny_warnings, analysis) => {
+            check(input, format, deny_warnings, analysis).map(|_| 0)
+        }
+    }
 }
 
-/// Instruction manual
-static MANUAL: &str = include_str!("manual.txt");
-
-/// Parses arguments
-fn parse_args() -> Result<ParsedArgs, AppError> {
-    let mut args = env::args();
-    args.next();
-    if let Some(arg) = args.next() {
-        if arg.eq("-i") || arg.eq("--interactive") {
-            Ok(ParsedArgs::Run(InputType::Standard))
-        } else if arg.eq("-f") || arg.eq("--file") {
-            if let Some(path) = args.next() {
-                if let Ok(path) = PathBuf::try_from(&path) {
-                    Ok(ParsedArgs::Run(InputType::File(path)))
-                } else {
-                    Err(AppError {
-                        msg: format!("Invalid path `{path}`."),
-                        code: 1,
-                    })
-                }
-            } else {
-                Err(AppError {
-                    msg: "Missing input file path argument.".to_owned(),
-                    code: 2,
-                })
-            }
-        } else {
-            Err(AppError {
-                msg: format!("Invalid argument `{}`.", arg),
-                code: 3,
-            })
-        }
-    } else {
-        Ok(ParsedArgs::InstructionManual)
+/// Prints the long-form explanation of a diagnostic code
+fn explain_code(code: &str) -> Result<(), AppError> {
+    let explanation = explain(code).ok_or_else(|| AppError {
+        msg: format!("Unknown diagnostic code `{code}`."),
+        code: exit_codes::INVALID_ARGUMENT,
+    })?;
+    println!("{explanation}");
+    Ok(())
+}
+
+/// Reads the whole source code, so it can be shown in diagnostics
+fn read(input: &InputType) -> Result<String, AppError> {
+    let mut bytes = vec![];
+    let result = match input {
+        InputType::Standard => stdin().read_to_end(&mut bytes).map(|_| ()),
+        InputType::File(path) => fs::read(path).map(|b| bytes = b),
+    };
+    match result {
+        Ok(()) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+        Err(_) => Err(AppError {
+            msg: format!("No file found `{}`.", input),
+            code: exit_codes::NO_FILE,
+        }),
     }
 }
 
-/// Application error containing message and process return code
-struct AppError {
-    msg: String,
-    code: u8,
+/// Run a lexer and parser on source code
+fn parse(source: &str) -> Parsed<Program> {
+    parse_source(source, |parser| parser.parse())
 }
 
-impl Display for AppError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{}", self.msg))
+/// Resolves names, optionally checks types and lints a program which was parsed without errors.
+/// Returns the problems along with the exit code of the first error.
+fn analyse(parsed: &Parsed<Program>, analysis: Analysis) -> (Vec<Diagnostic>, Option<u8>) {
+    let program = match (&parsed.result, parsed.errors.is_empty()) {
+        (Some(program), true) => program,
+        _ => return (vec![], None),
+    };
+    let unresolved = program.resolve();
+    let mistyped = if analysis.typecheck {
+        program.check_types()
+    } else {
+        vec![]
+    };
+    let linted = if analysis.lints.is_empty() {
+        vec![]
+    } else {
+        program.lint(analysis.lints)
+    };
+    let code = unresolved
+        .first()
+        .map(|e| e.variant.exit_code())
+        .or_else(|| mistyped.first().map(|e| e.variant.exit_code()));
+    let diagnostics = unresolved
+        .iter()
+        .map(Diagnostic::from)
+        .chain(mistyped.iter().map(Diagnostic::from))
+        .chain(linted.iter().map(Diagnostic::from))
+        .collect();
+    (diagnostics, code)
+}
+
+/// Picks the exit code of a finished program, a status returned from `main` takes precedence over
+/// the lexer warnings which were reported
+fn exit_code(value: &Value, diagnostics: &[Diagnostic]) -> u8 {
+    match value {
+        Value::Int(status) => status.rem_euclid(256) as u8,
+        _ if diagnostics.iter().any(|d| d.origin == Origin::Lexer) => exit_codes::WARNINGS,
+        _ => 0,
     }
 }
 
-/// Entry point
-fn main() {
-    if let Err(e) = app() {
-        eprintln!("{}", e.msg);
-        std::process::exit(e.code as i32);
+/// Exit code of a program which can't run, because of parser errors
+/// or warnings turned into errors by `deny` directives
+fn blocked<T>(parsed: &Parsed<T>, diagnostics: &[Diagnostic]) -> Option<u8> {
+    if !parsed.errors.is_empty() || parsed.result.is_none() {
+        Some(exit_codes::PARSER_ERROR)
+    } else if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+        Some(exit_codes::WARNINGS)
     } else {
-        std::process::exit(0);
+        None
     }
 }
 
-/// Run application
-fn app() -> Result<(), AppError> {
-    match parse_args() {
-        Ok(ParsedArgs::InstructionManual) => {
-            println!("{MANUAL}");
+/// Prints or writes the variables of the contexts a runtime error propagated through
+fn dump_variables(snapshots: &[Snapshot], dump: &ErrorDump) -> Result<(), AppError> {
+    match dump {
+        ErrorDump::Print => {
+            for snapshot in snapshots {
+                eprintln!("{snapshot}");
+            }
             Ok(())
         }
-        Ok(ParsedArgs::Run(input)) => run(input),
-        Err(e) => Err(e),
+        ErrorDump::File(path, format) => {
+            fs::write(path, format.serialize(&snapshots)).map_err(|_| AppError {
+                msg: format!("Can't write variables to `{}`.", path.to_string_lossy()),
+                code: exit_codes::NO_FILE,
+            })
+        }
     }
 }
 
-/// Run a lexer and parser on some arbitrary reader
-fn parse(
-    reader: Box<dyn BufRead>,
-) -> (
-    Result<Program, ParserError>,
-    Vec<ParserWarning>,
-    Vec<LexerWarning>,
-) {
-    let mut lexer = Lexer::new_with_defaults(reader);
-
-    let (result, parser_warnings) = {
-        let mut parser = Parser::new_with_defaults(TokenScanner::new(&mut lexer));
-        let result = parser.parse();
-        let warnings = parser.get_warnings();
-        (result, warnings)
-    };
+/// Prints the table of measured functions and writes their stacks, if asked to
+fn report_profile(profiler: &Profiler, folded_stacks: Option<&Path>) -> Result<(), AppError> {
+    eprintln!("{}", profiler.table());
+    match folded_stacks {
+        Some(path) => fs::write(path, profiler.folded_stacks()).map_err(|_| AppError {
+            msg: format!("Can't write stacks to `{}`.", path.to_string_lossy()),
+            code: exit_codes::NO_FILE,
+        }),
+        None => Ok(()),
+    }
+}
 
-    let lexer_warnings = lexer.get_warnings();
-    (result, parser_warnings, lexer_warnings)
+/// Prints the summary of the coverage and writes its reports into a directory:
+/// `lcov.info` and the annotated source, named after the input
+fn report_coverage(
+    coverage: &Coverage,
+    dir: &Path,
+    source: &str,
+    input: &InputType,
+) -> Result<(), AppError> {
+    eprintln!("{}", coverage.summary());
+    let name = match input {
+        InputType::File(path) => path.file_name().unwrap_or_default().to_string_lossy(),
+        InputType::Standard => "stdin".into(),
+    };
+    let error = |_| AppError {
+        msg: format!("Can't write coverage to `{}`.", dir.to_string_lossy()),
+        code: exit_codes::NO_FILE,
+    };
+    fs::create_dir_all(dir).map_err(error)?;
+    fs::write(dir.join("lcov.info"), coverage.lcov(&input.to_string())).map_err(error)?;
+    fs::write(
+        dir.join(format!("{name}.cov.txt")),
+        coverage.annotated(source),
+    )
+    .map_err(error)
 }
 
-/// Run interpreter
-fn run(input: InputType) -> Result<(), AppError> {
-    let reader: Box<dyn BufRead> = match input {
-        InputType::Standard => Box::new(BufReader::new(stdin())),
-        InputType::File(path) => {
-            if let Ok(input_file) = OpenOptions::new().read(true).open(&path) {
-                Box::new(BufReader::new(input_file))
+/// Run interpreter, returning the process exit code
+fn run(
+    input: InputType,
+    args: Vec<String>,
+    analysis: Analysis,
+    instrumentation: Instrumentation,
+) -> Result<u8, AppError> {
+    let source = read(&input)?;
+    let parsed = parse(&source);
+    let (analysed, code) = analyse(&parsed, analysis);
+    let diagnostics = diagnostics::collect_with(&parsed, analysed);
+    eprint_all(&diagnostics, &source, &input.to_string());
+    if let Some(code) = code.or_else(|| blocked(&parsed, &diagnostics)) {
+        return Ok(code);
+    }
+
+    let program = parsed.result.unwrap();
+    let hooks = Hooks {
+        tracer: instrumentation
+            .trace
+            .map(|level| Tracer::new(level, PrintOuts::Err(stderr()))),
+        profiler: instrumentation.profile.then(Profiler::default),
+        memory_profiler: instrumentation.mem_profile.then(MemoryProfiler::default),
+        coverage: instrumentation
+            .coverage
+            .is_some()
+            .then(|| Coverage::new(program.probes())),
+        debugger: instrumentation.debug.then(|| {
+            let input = Box::new(stdin().lock());
+            let interactive = stdin().is_terminal();
+            Debugger::new(&source, input, PrintOuts::Std(stdout()), interactive)
+        }),
+    };
+    let result = program.run(args, &hooks);
+    if let Some(profiler) = &hooks.profiler {
+        report_profile(profiler, instrumentation.folded_stacks.as_deref())?;
+    }
+    if let Some(memory_profiler) = &hooks.memory_profiler {
+        eprintln!("{}", memory_profiler.summary());
+    }
+    if let (Some(coverage), Some(dir)) = (&hooks.coverage, &instrumentation.coverage) {
+        report_coverage(coverage, dir, &source, &input)?;
+    }
+    Ok(match result {
+        Ok(value) => {
+            println!("Program ended.");
+            exit_code(&value, &diagnostics)
+        }
+        Err(error) => {
+            if error.variant == ExecutionErrorVariant::Aborted {
+                println!("Program stopped.");
             } else {
-                return Err(AppError {
-                    msg: format!("No file found `{}`.", path.to_string_lossy()),
-                    code: 1,
-                });
+                eprint_all(&[Diagnostic::from(&error)], &source, &input.to_string());
             }
+            if let Some(dump) = &instrumentation.dump_on_error {
+                dump_variables(&error.details.snapshots, dump)?;
+            }
+            error.variant.exit_code()
         }
+    })
+}
+
+/// Runs every script of a directory, comparing what it prints and how it ends with the expected
+/// output and error. Prints the result of each script, the differences found and a summary.
+/// Returns the process exit code.
+fn test(dir: &Path) -> Result<u8, AppError> {
+    let scripts = golden::scripts(dir).map_err(|_| AppError {
+        msg: format!("No directory found `{}`.", dir.to_string_lossy()),
+        code: exit_codes::NO_FILE,
+    })?;
+    let mut failures = vec![];
+    for script in &scripts {
+        let result = test_script(script);
+        let status = if result.is_ok() { "ok" } else { "FAILED" };
+        println!("test {} ... {status}", script.to_string_lossy());
+        if let Err(problems) = result {
+            failures.push((script, problems));
+        }
+    }
+    for (script, problems) in &failures {
+        println!("\n---- {} ----\n{problems}", script.to_string_lossy());
+    }
+    let status = if failures.is_empty() { "ok" } else { "FAILED" };
+    println!(
+        "\ntest result: {status}. {} passed; {} failed",
+        scripts.len() - failures.len(),
+        failures.len()
+    );
+    Ok(if failures.is_empty() {
+        0
+    } else {
+        exit_codes::TESTS_FAILED
+    })
+}
+
+/// Runs a script with its output captured, describing how it differs from the expected one.
+/// A script which can't run ends with its first error.
+fn test_script(script: &Path) -> Result<(), String> {
+    let source = fs::read(script)
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .map_err(|_| "can't be read".to_owned())?;
+    let expectation = golden::Expectation::read(script, &source);
+    let parsed = parse(&source);
+    let (analysed, code) = analyse(&parsed, Analysis::default());
+    let diagnostics = diagnostics::collect_with(&parsed, analysed);
+    if code.or_else(|| blocked(&parsed, &diagnostics)).is_some() {
+        let error = diagnostics.iter().find(|d| d.severity == Severity::Error);
+        return expectation.compare("", error);
+    }
+    let program = parsed.result.unwrap();
+    program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]));
+    let result = program.run(vec![], &Hooks::default());
+    let output = match program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![])) {
+        PrintOuts::Vec(buffer) => String::from_utf8_lossy(&buffer).into_owned(),
+        _ => unreachable!(),
     };
+    let error = result.err().map(|e| Diagnostic::from(&e));
+    expectation.compare(&output, error.as_ref())
+}
 
-    let (result, parser_warnings, lexer_warnings) = parse(reader);
+/// Evaluate source code without a `main` function, printing the resulting value.
+/// Returns the process exit code.
+fn eval(source: &str) -> u8 {
+    let parsed = repl::parse(source);
+    let diagnostics = diagnostics::collect(&parsed);
+    eprint_all(&diagnostics, source, "eval");
+    if let Some(code) = blocked(&parsed, &diagnostics) {
+        return code;
+    }
 
-    match result {
-        Ok(program) => {
-            //println!("{}", program);
-            match program.run() {
-                Ok(_) => println!("Program ended."),
-                Err(error) => eprintln!("{}", error),
+    let items = parsed.result.unwrap();
+    match repl::Session::new().eval_items(items, source) {
+        Ok(value) => {
+            if value != Value::None {
+                println!("{value}");
             }
+            exit_code(&Value::None, &diagnostics)
+        }
+        Err(error) => {
+            eprint_all(&[Diagnostic::from(&error)], source, "eval");
+            error.variant.exit_code()
         }
-        Err(error) => eprintln!("{}", error),
     }
+}
+
+/// Print all lexems, including comments
+fn dump_tokens(input: InputType, format: OutputFormat) -> Result<(), AppError> {
+    let source = read(&input)?;
+    let mut lexer = Lexer::new_with_defaults(Cursor::new(source.clone().into_bytes()));
+    println!("{}", format.serialize(&lexer.all()));
 
-    for w in parser_warnings {
-        eprintln!("{}", w);
+    let warnings: Vec<Diagnostic> = lexer.get_warnings().iter().map(Diagnostic::from).collect();
+    eprint_all(&warnings, &source, &input.to_string());
+
+    Ok(())
+}
+
+/// Print the parsed program, or only the problems if it failed to parse
+fn dump_ast(input: InputType, format: OutputFormat) -> Result<u8, AppError> {
+    let source = read(&input)?;
+    let parsed = parse(&source);
+    let diagnostics = diagnostics::collect(&parsed);
+    eprint_all(&diagnostics, &source, &input.to_string());
+    if let Some(code) = blocked(&parsed, &diagnostics) {
+        return Ok(code);
     }
 
-    for w in lexer_warnings {
-        eprintln!("{}", w);
+    println!("{}", format.serialize(&parsed.result.unwrap()));
+    Ok(0)
+}
+
+/// Lex, parse and analyse without running, print all problems as machine-readable diagnostics
+fn check(
+    input: InputType,
+    format: OutputFormat,
+    deny_warnings: bool,
+    analysis: Analysis,
+) -> Result<(), AppError> {
+    let uri = input.to_string();
+    let parsed = parse(&read(&input)?);
+    let (analysed, _) = analyse(&parsed, analysis);
+    let diagnostics = diagnostics::collect_with(&parsed, analysed);
+
+    match format {
+        OutputFormat::Sarif => println!("{}", format.serialize(&to_sarif(&diagnostics, &uri))),
+        _ => println!("{}", format.serialize(&diagnostics)),
     }
 
-    Ok(())
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    if errors > 0 {
+        Err(AppError {
+            msg: format!("Check failed with {errors} error(s) and {warnings} warning(s)."),
+            code: exit_codes::PARSER_ERROR,
+        })
+    } else if deny_warnings && warnings > 0 {
+        Err(AppError {
+            msg: format!("Check failed with {warnings} denied warning(s)."),
+            code: exit_codes::WARNINGS,
+        })
+    } else {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{fs::OpenOptions, io::BufReader};
+    use std::{fs, path::Path};
 
     use crate::{
-        interpreter::{standard_library::PrintOuts, ExecutionError, ExecutionErrorVariant},
-        lexer::lexem::{LexerWarning, LexerWarningVariant},
+        analyse,
+        analysis::linter::Lints,
+        blocked,
+        cli::{exit_codes, Analysis},
+        diagnostics,
+        diagnostics::Origin,
+        diagnostics::Severity,
+        eval, exit_code, golden,
+        interpreter::{
+            context::Snapshot, hooks::Hooks, standard_library::PrintOuts, ErrorDetails,
+            ExecutionError, ExecutionErrorVariant, StackFrame,
+        },
+        lexer::lexem::LexerWarningVariant,
         parse,
         parser::{
-            grammar::program::Program, ParserError, ParserErrorVariant, ParserWarning,
-            ParserWarningVariant,
+            grammar::{program::Program, DataType, Value},
+            position::{Position, Span},
+            Parsed, ParserError, ParserErrorVariant, ParserWarningVariant,
         },
+        test, test_script,
     };
 
-    fn read(
-        path: &str,
-    ) -> (
-        Result<Program, ParserError>,
-        Vec<ParserWarning>,
-        Vec<LexerWarning>,
-    ) {
-        let file = OpenOptions::new().read(true).open(&path).unwrap();
-        let reader = Box::new(BufReader::new(file));
-        parse(reader)
+    fn read(path: &str) -> Parsed<Program> {
+        parse(&fs::read_to_string(path).unwrap())
+    }
+
+    fn snapshot(context: &str, variables: &[(&str, Value)]) -> Snapshot {
+        Snapshot {
+            context: context.to_owned(),
+            variables: variables
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        }
     }
 
     #[test]
     fn string() {
         let string = "// do nothing\nfn main() {\n    let a: int = 5;\n}";
-        let reader = Box::new(BufReader::new(string.as_bytes()));
-        let (res, par_warns, lex_warns) = parse(reader);
-        assert!(res.is_ok());
+        let Parsed {
+            result: res,
+            errors,
+            parser_warnings: par_warns,
+            lexer_warnings: lex_warns,
+            ..
+        } = parse(string);
+        assert!(res.is_some());
+        assert!(errors.is_empty());
         assert!(par_warns.is_empty());
         assert!(lex_warns.is_empty());
     }
 
     #[test]
     fn short() {
-        let (res, par_warns, lex_warns) = read("snippets/short.txt");
-        assert!(res.is_ok());
+        let Parsed {
+            result: res,
+            errors,
+            parser_warnings: par_warns,
+            lexer_warnings: lex_warns,
+            ..
+        } = read("snippets/short.txt");
+        assert!(res.is_some());
+        assert!(errors.is_empty());
         assert!(par_warns.is_empty());
         assert!(lex_warns.is_empty());
     }
 
     #[test]
     fn long() {
-        let (res, par_warns, lex_warns) = read("snippets/long.txt");
-        assert!(res.is_ok());
+        let Parsed {
+            result: res,
+            errors,
+            parser_warnings: par_warns,
+            lexer_warnings: lex_warns,
+            ..
+        } = read("snippets/long.txt");
+        assert!(res.is_some());
+        assert!(errors.is_empty());
         assert!(par_warns.is_empty());
         assert!(lex_warns.is_empty());
     }
 
     #[test]
     fn errors() {
-        let (res, par_warns, lex_warns) = read("snippets/parser_error.txt");
+        let Parsed {
+            result: res,
+            errors,
+            parser_warnings: par_warns,
+            lexer_warnings: lex_warns,
+            ..
+        } = read("snippets/parser_error.txt");
+        assert!(res.is_some());
         assert_eq!(
-            res.unwrap_err().error,
-            ParserErrorVariant::VariableDeclarationMissingType
+            errors,
+            vec![ParserError {
+                error: ParserErrorVariant::VariableDeclarationMissingType,
+                pos: Position::new(4, 11),
+            }]
         );
         assert_eq!(par_warns.len(), 1);
         assert_eq!(
@@ -231,10 +536,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn missing_semicolons() {
+        let string = "fn main() {\n    print(1)\n    print(2);\n    for x in [] {}\n    x\n}";
+        let Parsed { result, errors, .. } = parse(string);
+        assert!(result.is_some());
+        assert_eq!(
+            errors,
+            vec![
+                ParserError {
+                    error: ParserErrorVariant::ExpectedSemicolon,
+                    pos: Position::new(2, 13),
+                },
+                ParserError {
+                    error: ParserErrorVariant::ExpectedSemicolon,
+                    pos: Position::new(4, 19),
+                }
+            ]
+        );
+    }
+
     #[test]
     fn warnings() {
-        let (res, par_warns, lex_warns) = read("snippets/warnings.txt");
-        assert!(res.is_ok());
+        let Parsed {
+            result: res,
+            errors,
+            parser_warnings: par_warns,
+            lexer_warnings: lex_warns,
+            ..
+        } = read("snippets/warnings.txt");
+        assert!(res.is_some());
+        assert!(errors.is_empty());
         assert_eq!(par_warns.len(), 1);
         assert_eq!(
             par_warns[0].warning,
@@ -247,12 +579,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn analysed() {
+        let parsed = parse("fn main() {\n    if false { missing(); };\n    let a: int = \"a\";\n}");
+        let (diagnostics, code) = analyse(&parsed, Analysis::default());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "function `missing` does not exist");
+        assert_eq!(code, Some(10));
+
+        let typecheck = Analysis {
+            typecheck: true,
+            ..Default::default()
+        };
+        let (diagnostics, code) = analyse(&parsed, typecheck);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[1].origin, Origin::TypeChecker);
+        assert_eq!(code, Some(10));
+
+        let lint = Analysis {
+            lints: Lints::all(),
+            ..Default::default()
+        };
+        let (diagnostics, code) = analyse(&parsed, lint);
+        let linted: Vec<&str> = diagnostics
+            .iter()
+            .filter(|d| d.origin == Origin::Linter)
+            .map(|d| &d.variant[..])
+            .collect();
+        assert_eq!(linted, vec!["ConstantCondition", "UnusedVariable"]);
+        assert_eq!(code, Some(10));
+
+        let parsed = parse("fn main() { let a: int = ; missing(); }");
+        assert_eq!(analyse(&parsed, typecheck), (vec![], None));
+    }
+
+    #[test]
+    fn loop_declarations() {
+        let source = "fn main() {
+    let i: int = 0;
+    while i < 2 {
+        let x: int = i;
+        i = i + 1;
+    };
+    for n in [1, 2] {
+        let y: int = n;
+        print(y);
+    };
+}";
+        let parsed = parse(source);
+        let (diagnostics, code) = analyse(&parsed, Analysis::default());
+        assert_eq!(diagnostics.len(), 2);
+        let program = parsed.result.unwrap();
+        program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]));
+        let error = program.run(vec![], &Hooks::default()).unwrap_err();
+        assert_eq!(
+            error.variant,
+            ExecutionErrorVariant::VariableAlreadyExists("x".to_owned())
+        );
+        assert_eq!(code, Some(error.variant.exit_code()));
+    }
+
     #[test]
     fn run_short() {
-        let (res, _, _) = read("snippets/short.txt");
+        let res = read("snippets/short.txt").complete();
         let program = res.unwrap();
         program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]));
-        program.run().unwrap();
+        program.run(vec![], &Hooks::default()).unwrap();
         if let PrintOuts::Vec(buffer) = program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]))
         {
             assert_eq!(&buffer, b"17\n")
@@ -261,10 +653,10 @@ mod tests {
 
     #[test]
     fn run_long() {
-        let (res, _, _) = read("snippets/long.txt");
+        let res = read("snippets/long.txt").complete();
         let program = res.unwrap();
         program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]));
-        program.run().unwrap();
+        program.run(vec![], &Hooks::default()).unwrap();
         if let PrintOuts::Vec(buffer) = program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]))
         {
             assert_eq!(&buffer, b"Hello world!\n[3, 2]\n3\n")
@@ -273,20 +665,236 @@ mod tests {
 
     #[test]
     fn stack_trace() {
-        let (res, _, _) = read("snippets/stack_trace.txt");
+        let res = read("snippets/stack_trace.txt").complete();
         let program = res.unwrap();
         program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]));
         assert_eq!(
-            program.run().unwrap_err(),
+            program.run(vec![], &Hooks::default()).unwrap_err(),
             ExecutionError {
-                contexts: vec![
-                    "code block".to_owned(),
-                    "if branch".to_owned(),
-                    "while loop".to_owned(),
-                    "for loop".to_owned()
-                ],
-                variant: ExecutionErrorVariant::ExpectedSemicolon
+                variant: ExecutionErrorVariant::DivisionByZero,
+                span: Some(Span::new(Position::new(10, 27), Position::new(10, 32))),
+                details: Box::new(ErrorDetails {
+                    snapshots: vec![
+                        snapshot("code block", &[]),
+                        snapshot("if branch", &[]),
+                        snapshot("while loop", &[]),
+                        snapshot("for loop", &[("i", Value::Int(0))]),
+                        snapshot(
+                            "`main` function",
+                            &[("a", Value::Int(5)), ("b", Value::Float(6.0))]
+                        )
+                    ],
+                    frames: vec![StackFrame {
+                        function: "main".to_owned(),
+                        call_site: None
+                    }],
+                    suggestions: vec![],
+                })
             }
         )
     }
+
+    #[test]
+    fn stack_frames() {
+        let string =
+            "fn divide(a: int, b: int) -> int {\n    a / b\n}\nfn main() {\n    divide(1, 0);\n}";
+        let res = parse(string).complete();
+        let error = res.unwrap().run(vec![], &Hooks::default()).unwrap_err();
+        assert_eq!(error.variant, ExecutionErrorVariant::DivisionByZero);
+        assert_eq!(
+            error.span,
+            Some(Span::new(Position::new(2, 5), Position::new(2, 10)))
+        );
+        assert_eq!(
+            error.details.frames,
+            vec![
+                StackFrame {
+                    function: "divide".to_owned(),
+                    call_site: Some(Span::new(Position::new(5, 5), Position::new(5, 11)))
+                },
+                StackFrame {
+                    function: "main".to_owned(),
+                    call_site: None
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn snapshots() {
+        let string = "fn get(list: [], i: int) -> int {\n    let j: int = i + 1;\n    list[j]\n}\nfn main() {\n    let list: [] = [1, 2];\n    if true {\n        let name: string = \"x\";\n        get(list, 1);\n    }\n}";
+        let res = parse(string).complete();
+        let error = res.unwrap().run(vec![], &Hooks::default()).unwrap_err();
+        let list = Value::List(vec![Value::Int(1), Value::Int(2)]);
+        assert_eq!(
+            error.details.snapshots,
+            vec![
+                snapshot(
+                    "`get` function",
+                    &[
+                        ("i", Value::Int(1)),
+                        ("j", Value::Int(2)),
+                        ("list", list.clone())
+                    ]
+                ),
+                snapshot("if branch", &[("name", Value::String("x".to_owned()))]),
+                snapshot("`main` function", &[("list", list)])
+            ]
+        );
+        assert_eq!(
+            error.details.snapshots[1].to_string(),
+            "variables of the if branch:\n    name: string = \"x\""
+        );
+    }
+
+    #[test]
+    fn call_errors_point_at_call() {
+        let string = "fn main() {\n    cast_int(\"a\");\n}";
+        let res = parse(string).complete();
+        let error = res.unwrap().run(vec![], &Hooks::default()).unwrap_err();
+        assert_eq!(error.variant, ExecutionErrorVariant::CastFailed);
+        assert_eq!(
+            error.span,
+            Some(Span::new(Position::new(2, 5), Position::new(2, 18)))
+        );
+        assert_eq!(error.details.frames.len(), 1);
+    }
+
+    #[test]
+    fn exit_status() {
+        let string = "fn main() -> int {\n    return 259;\n}";
+        let res = parse(string).complete();
+        let value = res.unwrap().run(vec![], &Hooks::default()).unwrap();
+        assert_eq!(value, Value::Int(259));
+        assert_eq!(exit_code(&value, &[]), 3);
+        assert_eq!(exit_code(&Value::Int(-1), &[]), 255);
+    }
+
+    #[test]
+    fn exit_status_invalid_main() {
+        let string = "fn main() -> float {\n    return 1.0;\n}";
+        let res = parse(string).complete();
+        let error = res.unwrap().run(vec![], &Hooks::default()).unwrap_err();
+        assert_eq!(
+            error.variant,
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::None, DataType::Integer],
+                actual: DataType::Float
+            }
+        );
+        assert_eq!(
+            error.variant.to_string(),
+            "invalid type, expected `none` or `int`, found `float`"
+        );
+        assert_eq!(error.variant.exit_code(), 11);
+    }
+
+    #[test]
+    fn exit_warnings() {
+        let parsed = read("snippets/warnings.txt");
+        let diagnostics = diagnostics::collect(&parsed);
+        let program = parsed.result.unwrap();
+        program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]));
+        let value = program.run(vec![], &Hooks::default()).unwrap();
+        assert_eq!(exit_code(&value, &diagnostics), 1);
+        assert_eq!(exit_code(&Value::None, &diagnostics), exit_codes::WARNINGS);
+        assert_eq!(exit_code(&Value::None, &[]), 0);
+    }
+
+    #[test]
+    fn directives() {
+        let source = "fn main() {\n    let a: int = 1 $;\n    print(a);\n}";
+        let parsed = parse(source);
+        let diagnostics = diagnostics::collect(&parsed);
+        assert_eq!(blocked(&parsed, &diagnostics), None);
+        assert_eq!(exit_code(&Value::None, &diagnostics), exit_codes::WARNINGS);
+
+        let parsed = parse(&format!("// allow(InvalidSequence)\n\n{source}"));
+        let diagnostics = diagnostics::collect(&parsed);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(exit_code(&Value::None, &diagnostics), 0);
+
+        let parsed = parse(&format!("// deny(all)\n{source}"));
+        let diagnostics = diagnostics::collect(&parsed);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(blocked(&parsed, &diagnostics), Some(exit_codes::WARNINGS));
+    }
+
+    #[test]
+    fn exit_runtime_error() {
+        let res = read("snippets/stack_trace.txt").complete();
+        let program = res.unwrap();
+        program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]));
+        assert_eq!(
+            program
+                .run(vec![], &Hooks::default())
+                .unwrap_err()
+                .variant
+                .exit_code(),
+            13
+        );
+    }
+
+    #[test]
+    fn script_args() {
+        let string = "fn main(args: []) {\n    print(args);\n}";
+        let res = parse(string).complete();
+        let program = res.unwrap();
+        program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]));
+        program
+            .run(vec!["a".to_owned(), "b c".to_owned()], &Hooks::default())
+            .unwrap();
+        if let PrintOuts::Vec(buffer) = program.std_ctx.std_print.0.replace(PrintOuts::Vec(vec![]))
+        {
+            assert_eq!(std::str::from_utf8(&buffer).unwrap(), "[a, b c]\n")
+        }
+    }
+
+    #[test]
+    fn script_args_invalid_main() {
+        let string = "fn main(a: [], b: []) {}";
+        let res = parse(string).complete();
+        assert_eq!(
+            res.unwrap()
+                .run(vec![], &Hooks::default())
+                .unwrap_err()
+                .variant,
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 1,
+                given: 2
+            }
+        );
+        let string = "fn main(a: int) {}";
+        let res = parse(string).complete();
+        assert_eq!(
+            res.unwrap()
+                .run(vec![], &Hooks::default())
+                .unwrap_err()
+                .variant,
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Integer],
+                actual: DataType::List
+            }
+        );
+    }
+
+    #[test]
+    fn eval_exit_codes() {
+        assert_eq!(eval("let a: int = 2; a * 3;"), 0);
+        assert_eq!(eval("fn f() -> int { 1 / 0 } f()"), 13);
+        assert_eq!(eval("let"), exit_codes::PARSER_ERROR);
+    }
+
+    #[test]
+    fn golden_scripts() {
+        let scripts = golden::scripts(Path::new("snippets/golden")).unwrap();
+        assert_eq!(scripts.len(), 3);
+        for script in scripts {
+            assert_eq!(test_script(&script), Ok(()), "{}", script.display());
+        }
+        assert_eq!(
+            test(Path::new("snippets/missing")).unwrap_err().code,
+            exit_codes::NO_FILE
+        );
+    }
 }
@@ -0,0 +1,282 @@
+use super::{
+    keywords::Keyword,
+    operators::Operator,
+    position::{Position, Span},
+    token::{Token, TokenType},
+};
+
+/// What happens to the warnings a directive names
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    /// Warnings aren't reported
+    Allow,
+    /// Warnings are reported as errors
+    Deny,
+}
+
+/// Part of the source code a directive applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Line(usize),
+    Function(Span),
+    File,
+}
+
+impl Scope {
+    /// Narrower scopes take precedence over wider ones
+    fn width(&self) -> u8 {
+        match self {
+            Scope::Line(_) => 0,
+            Scope::Function(_) => 1,
+            Scope::File => 2,
+        }
+    }
+
+    fn contains(&self, pos: Position) -> bool {
+        match self {
+            Scope::Line(row) => pos.row == *row,
+            Scope::Function(span) => span.start <= pos && pos < span.stop,
+            Scope::File => true,
+        }
+    }
+}
+
+/// Comment like `// allow(MissingColon)` or `// deny(all)`, which controls
+/// the warnings reported within its scope. Warnings are named after their variants or codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Directive {
+    pub level: Level,
+    pub names: Vec<String>,
+    pub scope: Scope,
+    /// Start of the comment
+    pub pos: Position,
+}
+
+impl Directive {
+    /// Parses the content of a comment, returning `None` if it isn't a directive
+    fn parse(comment: &str, pos: Position) -> Option<Self> {
+        let comment = comment.trim();
+        let (level, rest) = if let Some(rest) = comment.strip_prefix("allow") {
+            (Level::Allow, rest)
+        } else {
+            (Level::Deny, comment.strip_prefix("deny")?)
+        };
+        let names = rest.trim_start().strip_prefix('(')?.strip_suffix(')')?;
+        let names: Vec<String> = names
+            .split(',')
+            .map(|name| name.trim().to_owned())
+            .filter(|name| !name.is_empty())
+            .collect();
+        if names.is_empty() {
+            return None;
+        }
+        Some(Self {
+            level,
+            names,
+            scope: Scope::File,
+            pos,
+        })
+    }
+
+    /// Whether the directive applies to a warning known by any of the names reported at the position
+    fn applies(&self, names: &[&str], pos: Position) -> bool {
+        self.scope.contains(pos)
+            && self
+                .names
+                .iter()
+                .any(|name| name == "all" || names.contains(&&name[..]))
+    }
+}
+
+/// Finds the directive deciding about a warning known by any of the names reported at the position.
+/// The one with the narrowest scope wins, the last one among equally narrow ones.
+pub fn applicable<'a>(
+    directives: &'a [Directive],
+    names: &[&str],
+    pos: Position,
+) -> Option<&'a Directive> {
+    directives
+        .iter()
+        .filter(|d| d.applies(names, pos))
+        .min_by_key(|d| (d.scope.width(), std::cmp::Reverse(d.pos)))
+}
+
+/// Picks up directives from the comments skipped by the token scanner,
+/// deciding their scopes from the tokens around them:
+/// - a directive following code on the same line applies to the line,
+/// - a directive directly above a line of code applies to it,
+///   or to the whole function if the line starts a function definition,
+/// - a directive separated from the code below it by an empty line applies to the whole file.
+///
+/// Directives stacked on consecutive lines share the scope.
+#[derive(Debug, Default)]
+pub struct DirectiveCollector {
+    directives: Vec<Directive>,
+    /// Directives on their own lines, waiting for the next token to decide their scope
+    pending: Vec<Directive>,
+    /// Last row of the comments above the next token
+    comments_end: usize,
+    /// End of the last token
+    previous: Option<Position>,
+    /// Number of curly brackets opened so far and not closed yet
+    depth: usize,
+    /// Directives of functions waiting for their closing bracket,
+    /// along with the depth at which the function was defined
+    functions: Vec<(usize, usize)>,
+}
+
+impl DirectiveCollector {
+    /// Handles a comment skipped by the token scanner
+    pub fn comment(&mut self, content: &str, start: Position, stop: Position) {
+        let trailing = self
+            .previous
+            .is_some_and(|previous| previous.row == start.row);
+        if trailing {
+            if let Some(mut directive) = Directive::parse(content, start) {
+                directive.scope = Scope::Line(start.row);
+                self.directives.push(directive);
+            }
+            return;
+        }
+        if start.row > self.comments_end + 1 {
+            self.directives.append(&mut self.pending);
+        }
+        self.comments_end = stop.row;
+        if let Some(directive) = Directive::parse(content, start) {
+            self.pending.push(directive);
+        }
+    }
+
+    /// Handles a token passed on by the token scanner
+    pub fn token(&mut self, token: &Token) {
+        let attached =
+            token.start.row <= self.comments_end + 1 && token.token_type != TokenType::EndOfTokens;
+        for mut directive in self.pending.drain(..) {
+            if attached {
+                directive.scope = match token.token_type {
+                    TokenType::Keyword(Keyword::Fn) => {
+                        self.functions.push((self.directives.len(), self.depth));
+                        Scope::Function(Span::new(token.start, Position::new(usize::MAX, 0)))
+                    }
+                    _ => Scope::Line(token.start.row),
+                };
+            }
+            self.directives.push(directive);
+        }
+        match token.token_type {
+            TokenType::Operator(Operator::OpenCurlyBracket) => self.depth += 1,
+            TokenType::Operator(Operator::CloseCurlyBracket) => {
+                self.depth = self.depth.saturating_sub(1);
+                let depth = self.depth;
+                let directives = &mut self.directives;
+                self.functions.retain(|(index, defined)| {
+                    if *defined != depth {
+                        return true;
+                    }
+                    if let Scope::Function(span) = &mut directives[*index].scope {
+                        span.stop = token.stop;
+                    }
+                    false
+                });
+            }
+            _ => {}
+        }
+        self.previous = Some(token.stop);
+    }
+
+    /// Returns all directives, the ones still waiting for code apply to the whole file
+    pub fn finish(mut self) -> Vec<Directive> {
+        self.directives.append(&mut self.pending);
+        self.directives
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        parse_source,
+        position::{Position, Span},
+    };
+
+    use super::{applicable, Directive, Level, Scope};
+
+    fn directives(source: &str) -> Vec<Directive> {
+        parse_source(source, |p| p.parse()).directives
+    }
+
+    #[test]
+    fn parse() {
+        let pos = Position::new(1, 1);
+        let directive = Directive::parse(" allow(MissingColon, ExpectedParameter) ", pos).unwrap();
+        assert_eq!(directive.level, Level::Allow);
+        assert_eq!(directive.names, vec!["MissingColon", "ExpectedParameter"]);
+        assert_eq!(
+            Directive::parse("deny (all)", pos).unwrap().level,
+            Level::Deny
+        );
+        assert_eq!(Directive::parse(" allow()", pos), None);
+        assert_eq!(Directive::parse(" allowed(a)", pos), None);
+        assert_eq!(Directive::parse(" deny(all", pos), None);
+        assert_eq!(Directive::parse(" do nothing", pos), None);
+    }
+
+    #[test]
+    fn scopes() {
+        let source = "// allow(A)
+
+// allow(B)
+// deny(C)
+fn main() {
+    let a: int = 1; // allow(D)
+    /* deny(E) */
+    print(a);
+    {
+        // allow(F)
+
+        print(a);
+    };
+}
+// allow(G)
+fn f() {}";
+        let scopes: Vec<(String, Scope)> = directives(source)
+            .into_iter()
+            .map(|d| (d.names.join(","), d.scope))
+            .collect();
+        let main = Span::new(Position::new(5, 1), Position::new(14, 2));
+        assert_eq!(
+            scopes,
+            vec![
+                ("A".to_owned(), Scope::File),
+                ("B".to_owned(), Scope::Function(main)),
+                ("C".to_owned(), Scope::Function(main)),
+                ("D".to_owned(), Scope::Line(6)),
+                ("E".to_owned(), Scope::Line(8)),
+                ("F".to_owned(), Scope::File),
+                (
+                    "G".to_owned(),
+                    Scope::Function(Span::new(Position::new(16, 1), Position::new(16, 10)))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        let source = "// deny(all)
+
+// allow(P0106)
+fn main() {
+    print(1); // deny(MissingColon)
+    print(2);
+}";
+        let directives = directives(source);
+        let level = |names: &[&str], row: usize| {
+            applicable(&directives, names, Position::new(row, 5)).map(|d| d.level)
+        };
+        let missing_colon = ["MissingColon", "P0106"];
+        assert_eq!(level(&missing_colon, 5), Some(Level::Deny));
+        assert_eq!(level(&missing_colon, 6), Some(Level::Allow));
+        assert_eq!(level(&missing_colon, 8), Some(Level::Deny));
+        assert_eq!(level(&["ExpectedParameter", "P0110"], 6), Some(Level::Deny));
+    }
+}
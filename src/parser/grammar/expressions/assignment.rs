@@ -1,18 +1,18 @@
 use crate::{
-    interpreter::{context::Context, ExecutionError, ExecutionErrorVariant},
+    interpreter::{context::Context, memory, ExecutionError, ExecutionErrorVariant},
     parser::grammar::Value,
 };
 
 use super::{
     super::utility::*, binary::parse_logical_alternative_expression, parse_expression, Evaluable,
-    Expression,
+    Expression, ExpressionKind,
 };
 
 /// Variable assignment expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct AssignmentExpr {
-    identifier: Box<Expression>,
-    expression: Box<Expression>,
+    pub identifier: Box<Expression>,
+    pub expression: Box<Expression>,
 }
 
 impl AssignmentExpr {
@@ -24,16 +24,17 @@ impl AssignmentExpr {
     }
 }
 
-impl From<AssignmentExpr> for Expression {
+impl From<AssignmentExpr> for ExpressionKind {
     fn from(e: AssignmentExpr) -> Self {
-        Expression::Assignment(e)
+        ExpressionKind::Assignment(e)
     }
 }
 
 impl Evaluable for AssignmentExpr {
     fn eval(&self, ctx: &dyn Context) -> Result<Value, ExecutionError> {
         let value = self.expression.eval(ctx)?;
-        if let Expression::Identifier(identifier_expr) = &*self.identifier {
+        if let ExpressionKind::Identifier(identifier_expr) = &self.identifier.kind {
+            memory::copied(ctx, &value);
             ctx.set_variable(&identifier_expr.0, value.clone())?;
             Ok(value)
         } else {
@@ -52,7 +53,8 @@ pub fn parse_variable_assignment_expression(p: &mut Parser) -> OptRes<Expression
         while p.operator(Op::Equal)? {
             let rhs = parse_expression(p)?
                 .ok_or_else(|| p.error(ErroVar::AssignmentMissingExpression))?;
-            lhs = AssignmentExpr::new(lhs, rhs).into();
+            let span = p.span_from(lhs.span.start);
+            lhs = Expression::from(AssignmentExpr::new(lhs, rhs)).spanned(span);
         }
         return Ok(Some(lhs));
     }
@@ -142,7 +144,7 @@ mod tests {
         let ctx = TestCtx::new();
         assert_eq!(
             expr("a", Value::Int(8)).eval(&ctx).unwrap_err().variant,
-            ExecutionErrorVariant::VariableDoesNotExist
+            ExecutionErrorVariant::VariableDoesNotExist("a".to_owned())
         );
     }
 }
@@ -1,9 +1,14 @@
 use crate::{
-    interpreter::{context::Context, types::validate_types, ExecutionError, ExecutionErrorVariant},
-    parser::grammar::{test_utils::tests::TokenType, Value},
+    interpreter::{
+        context::Context, memory, types::validate_types, ExecutionError, ExecutionErrorVariant,
+    },
+    parser::{grammar::Value, token::TokenType},
 };
 
-use super::{super::utility::*, unary::parse_unary_operator_expression, Evaluable, Expression};
+use super::{
+    super::utility::*, unary::parse_unary_operator_expression, Evaluable, Expression,
+    ExpressionKind,
+};
 
 /// Binary operators
 #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
@@ -26,9 +31,9 @@ pub enum BinaryOperator {
 /// Binary operation expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct BinaryExpr {
-    lhs: Box<Expression>,
-    operator: BinaryOperator,
-    rhs: Box<Expression>,
+    pub lhs: Box<Expression>,
+    pub operator: BinaryOperator,
+    pub rhs: Box<Expression>,
 }
 
 impl BinaryExpr {
@@ -41,9 +46,9 @@ impl BinaryExpr {
     }
 }
 
-impl From<BinaryExpr> for Expression {
+impl From<BinaryExpr> for ExpressionKind {
     fn from(e: BinaryExpr) -> Self {
-        Expression::Binary(e)
+        ExpressionKind::Binary(e)
     }
 }
 
@@ -51,16 +56,9 @@ impl Evaluable for BinaryExpr {
     fn eval(&self, ctx: &dyn Context) -> Result<Value, ExecutionError> {
         let lhs = self.lhs.eval(ctx)?;
         let rhs = self.rhs.eval(ctx)?;
-        validate_types(&lhs, &rhs)?;
-        match (lhs, rhs) {
-            (Value::Int(lhs), Value::Int(rhs)) => int_op(lhs, rhs, self.operator),
-            (Value::Float(lhs), Value::Float(rhs)) => float_op(lhs, rhs, self.operator),
-            (Value::Bool(lhs), Value::Bool(rhs)) => bool_op(lhs, rhs, self.operator),
-            (Value::String(lhs), Value::String(rhs)) => string_op(lhs, rhs, self.operator),
-            _ => Err(ExecutionError::new(
-                ExecutionErrorVariant::UnsupportedBinaryOperation,
-            )),
-        }
+        let value = apply(lhs, self.operator, rhs)?;
+        memory::allocated(ctx, &value);
+        Ok(value)
     }
 }
 
@@ -93,7 +91,8 @@ fn parse_mul_div_expression(p: &mut Parser) -> OptRes<Expression> {
         while let Some(operator) = parse_mul_div_operators(p)? {
             let rhs = parse_unary_operator_expression(p)?
                 .ok_or_else(|| p.error(ErroVar::BinaryOperatorMissingRHS))?;
-            lhs = BinaryExpr::new(lhs, operator, rhs).into();
+            let span = p.span_from(lhs.span.start);
+            lhs = Expression::from(BinaryExpr::new(lhs, operator, rhs)).spanned(span);
         }
         Ok(Some(lhs))
     } else {
@@ -126,7 +125,8 @@ fn parse_add_sub_expression(p: &mut Parser) -> OptRes<Expression> {
         while let Some(operator) = parse_add_sub_operators(p)? {
             let rhs = parse_mul_div_expression(p)?
                 .ok_or_else(|| p.error(ErroVar::BinaryOperatorMissingRHS))?;
-            lhs = BinaryExpr::new(lhs, operator, rhs).into();
+            let span = p.span_from(lhs.span.start);
+            lhs = Expression::from(BinaryExpr::new(lhs, operator, rhs)).spanned(span);
         }
         return Ok(Some(lhs));
     }
@@ -174,7 +174,8 @@ fn parse_comparison_expression(p: &mut Parser) -> OptRes<Expression> {
         while let Some(operator) = parse_comparison_operators(p)? {
             let rhs = parse_add_sub_expression(p)?
                 .ok_or_else(|| p.error(ErroVar::BinaryOperatorMissingRHS))?;
-            lhs = BinaryExpr::new(lhs, operator, rhs).into();
+            let span = p.span_from(lhs.span.start);
+            lhs = Expression::from(BinaryExpr::new(lhs, operator, rhs)).spanned(span);
         }
         return Ok(Some(lhs));
     }
@@ -211,6 +212,20 @@ pub fn parse_logical_alternative_expression(p: &mut Parser) -> OptRes<Expression
     Ok(None)
 }
 
+/// Applies an operator to 2 values
+pub fn apply(lhs: Value, operator: BinaryOperator, rhs: Value) -> Result<Value, ExecutionError> {
+    validate_types(&lhs, &rhs)?;
+    match (lhs, rhs) {
+        (Value::Int(lhs), Value::Int(rhs)) => int_op(lhs, rhs, operator),
+        (Value::Float(lhs), Value::Float(rhs)) => float_op(lhs, rhs, operator),
+        (Value::Bool(lhs), Value::Bool(rhs)) => bool_op(lhs, rhs, operator),
+        (Value::String(lhs), Value::String(rhs)) => string_op(lhs, rhs, operator),
+        _ => Err(ExecutionError::new(
+            ExecutionErrorVariant::UnsupportedBinaryOperation,
+        )),
+    }
+}
+
 fn int_op(lhs: i64, rhs: i64, op: BinaryOperator) -> Result<Value, ExecutionError> {
     match op {
         BinaryOperator::Addition => lhs
@@ -303,7 +318,7 @@ fn string_op(lhs: String, rhs: String, op: BinaryOperator) -> Result<Value, Exec
 mod tests {
     use crate::{
         interpreter::{test_utils::tests::TestCtx, ExecutionErrorVariant},
-        parser::grammar::expressions::parse_expression,
+        parser::grammar::{expressions::parse_expression, DataType},
     };
 
     use super::{super::super::test_utils::tests::*, BinaryExpr, BinaryOperator};
@@ -979,35 +994,62 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::String],
+                actual: DataType::Integer
+            }
         );
         assert_eq!(
             expr(Value::Float(0.0), BinaryOperator::Equal, Value::Int(0))
                 .eval(&ctx)
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Float],
+                actual: DataType::Integer
+            }
         );
         assert_eq!(
             expr(Value::Float(0.0), BinaryOperator::Equal, Value::Bool(true))
                 .eval(&ctx)
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Float],
+                actual: DataType::Bool
+            }
         );
         assert_eq!(
             expr(Value::None, BinaryOperator::Equal, Value::Bool(true))
                 .eval(&ctx)
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![
+                    DataType::Integer,
+                    DataType::Float,
+                    DataType::Bool,
+                    DataType::String,
+                    DataType::List,
+                ],
+                actual: DataType::None
+            }
         );
         assert_eq!(
             expr(Value::None, BinaryOperator::Equal, Value::List(vec![]))
                 .eval(&ctx)
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![
+                    DataType::Integer,
+                    DataType::Float,
+                    DataType::Bool,
+                    DataType::String,
+                    DataType::List,
+                ],
+                actual: DataType::None
+            }
         );
         assert_eq!(
             expr(
@@ -1061,7 +1103,16 @@ mod tests {
                 .eval(&ctx)
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![
+                    DataType::Integer,
+                    DataType::Float,
+                    DataType::Bool,
+                    DataType::String,
+                    DataType::List,
+                ],
+                actual: DataType::None
+            }
         );
     }
 }
@@ -9,12 +9,12 @@ use crate::{
 use super::{
     super::utility::*,
     statement::{alternate_statements, parse_code_block, Statement},
-    Evaluable, Expression,
+    Evaluable, Expression, ExpressionKind,
 };
 
 /// Block of code expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
-pub struct CodeBlockExpr(Vec<Statement>);
+pub struct CodeBlockExpr(pub Vec<Statement>);
 
 impl CodeBlockExpr {
     #[allow(dead_code)]
@@ -23,9 +23,9 @@ impl CodeBlockExpr {
     }
 }
 
-impl From<CodeBlockExpr> for Expression {
+impl From<CodeBlockExpr> for ExpressionKind {
     fn from(e: CodeBlockExpr) -> Self {
-        Expression::CodeBlock(e)
+        ExpressionKind::CodeBlock(e)
     }
 }
 
@@ -38,7 +38,9 @@ impl Evaluable for CodeBlockExpr {
 
 /// code_block
 pub fn parse_code_block_expression(p: &mut Parser) -> OptRes<Expression> {
-    parse_code_block(p).map(|v| v.map(|v| CodeBlockExpr(v).into()))
+    let start = p.curr().start;
+    let statements = parse_code_block(p)?;
+    Ok(statements.map(|v| Expression::from(CodeBlockExpr(v)).spanned(p.span_from(start))))
 }
 
 #[cfg(test)]
@@ -93,6 +95,35 @@ mod tests {
         assert!(warnings.is_empty());
     }
 
+    #[test]
+    fn parse_missing_semicolon() {
+        let (result, errors, warnings) = partial_parse_recovering(
+            vec![
+                dummy_token(TokenType::Operator(Op::OpenCurlyBracket)),
+                token(TokenType::Identifier("a".to_owned()), (1, 2), (1, 3)),
+                dummy_token(TokenType::Int(5)),
+                dummy_token(TokenType::Operator(Op::CloseCurlyBracket)),
+            ],
+            parse_expression,
+        );
+        assert_eq!(
+            result.unwrap().unwrap(),
+            CodeBlockExpr::new(vec![
+                IdentifierExpr::new("a".to_owned()).into(),
+                Value::Int(5).into(),
+            ])
+            .into()
+        );
+        assert_eq!(
+            errors,
+            vec![ParserError {
+                error: ParserErrorVariant::ExpectedSemicolon,
+                pos: Position::new(1, 3)
+            }]
+        );
+        assert!(warnings.is_empty());
+    }
+
     #[test]
     fn parse_missing_bracket() {
         let (result, warnings) = partial_parse(
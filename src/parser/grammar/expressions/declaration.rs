@@ -1,16 +1,16 @@
 use crate::{
-    interpreter::{context::Context, types::validate_type, ExecutionError},
+    interpreter::{context::Context, memory, types::validate_type, ExecutionError},
     parser::grammar::{types::parse_type, DataType, Value},
 };
 
-use super::{super::utility::*, parse_expression, Evaluable, Expression};
+use super::{super::utility::*, parse_expression, Evaluable, Expression, ExpressionKind};
 
 /// Variable declaration expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct DeclarationExpr {
-    identifier: String,
-    data_type: DataType,
-    expression: Box<Expression>,
+    pub identifier: String,
+    pub data_type: DataType,
+    pub expression: Box<Expression>,
 }
 
 impl DeclarationExpr {
@@ -23,9 +23,9 @@ impl DeclarationExpr {
     }
 }
 
-impl From<DeclarationExpr> for Expression {
+impl From<DeclarationExpr> for ExpressionKind {
     fn from(e: DeclarationExpr) -> Self {
-        Expression::Declaration(e)
+        ExpressionKind::Declaration(e)
     }
 }
 
@@ -33,6 +33,7 @@ impl Evaluable for DeclarationExpr {
     fn eval(&self, ctx: &dyn Context) -> Result<Value, ExecutionError> {
         let value = self.expression.eval(ctx)?;
         validate_type(self.data_type, &value)?;
+        memory::copied(ctx, &value);
         ctx.new_variable(&self.identifier, value.clone())?;
         Ok(value)
     }
@@ -42,6 +43,7 @@ impl Evaluable for DeclarationExpr {
 ///     = KW_LET, IDENTIFIER, COLON, TYPE_SIGNATURE, type, ASSIGN, control_flow_expression
 ///     ;
 pub fn parse_variable_declaration(p: &mut Parser) -> OptRes<Expression> {
+    let start = p.curr().start;
     if !p.keyword(Kw::Let)? {
         return Ok(None);
     }
@@ -59,7 +61,8 @@ pub fn parse_variable_declaration(p: &mut Parser) -> OptRes<Expression> {
     let expression = parse_expression(p)?
         .ok_or_else(|| p.error(ErroVar::VariableDeclarationMissingExpression))?;
     Ok(Some(
-        DeclarationExpr::new(identifier, data_type, expression).into(),
+        Expression::from(DeclarationExpr::new(identifier, data_type, expression))
+            .spanned(p.span_from(start)),
     ))
 }
 
@@ -266,7 +269,7 @@ mod tests {
                 .eval(&ctx)
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::VariableAlreadyExists
+            ExecutionErrorVariant::VariableAlreadyExists("a".to_owned())
         );
     }
 }
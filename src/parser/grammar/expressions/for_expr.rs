@@ -1,24 +1,25 @@
 use crate::{
+    analysis::coverage::ProbeKind,
     interpreter::{
         context::{BlockCtx, Context},
-        ExecutionError, ExecutionErrorVariant,
+        coverage, memory, trace, ExecutionError, ExecutionErrorVariant,
     },
-    parser::grammar::Value,
+    parser::grammar::{DataType, Value},
 };
 
 use super::{
     super::utility::*,
     parse_expression,
-    statement::{alternate_statements, parse_code_block, Statement},
-    Evaluable, Expression,
+    statement::{alternate_statements, block_value, parse_code_block, Statement},
+    Evaluable, Expression, ExpressionKind,
 };
 
 /// For loop expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ForExpr {
-    variable: String,
-    provider: Box<Expression>,
-    body: Vec<Statement>,
+    pub variable: String,
+    pub provider: Box<Expression>,
+    pub body: Vec<Statement>,
 }
 
 impl ForExpr {
@@ -29,11 +30,16 @@ impl ForExpr {
             body,
         }
     }
+
+    /// Returns the expression whose values are collected into the list returned by the loop
+    pub fn body_value(&self) -> Option<&Expression> {
+        block_value(&self.body)
+    }
 }
 
-impl From<ForExpr> for Expression {
+impl From<ForExpr> for ExpressionKind {
     fn from(e: ForExpr) -> Self {
-        Expression::For(e)
+        ExpressionKind::For(e)
     }
 }
 
@@ -43,16 +49,31 @@ impl Evaluable for ForExpr {
         if let Value::List(list) = provider {
             let ctx = BlockCtx::new(ctx, "for loop".to_owned());
             let mut results = vec![];
-            for v in list {
-                ctx.variables.borrow_mut().insert(self.variable.clone(), v);
-                results.push(ctx.escalate_error(alternate_statements(&self.body, &ctx))?);
+            for (i, v) in list.into_iter().enumerate() {
+                let previous = ctx.variables.borrow_mut().insert(self.variable.clone(), v);
+                memory::stored(
+                    &ctx,
+                    previous.as_ref(),
+                    ctx.variables.borrow().get(&self.variable),
+                );
+                coverage::hit(&ctx, ProbeKind::LoopBody, self.provider.span);
+                let result =
+                    trace::iteration(&ctx, "for loop", i + 1, Some(&self.variable), || {
+                        alternate_statements(&self.body, &ctx)
+                    });
+                results.push(ctx.escalate_error(result)?);
                 if ctx.is_ret() {
                     break;
                 }
             }
-            Ok(Value::List(results))
+            let results = Value::List(results);
+            memory::allocated(&ctx, &results);
+            Ok(results)
         } else {
-            Err(ExecutionError::new(ExecutionErrorVariant::InvalidType))
+            Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::List],
+                actual: (&provider).into(),
+            }))
         }
     }
 }
@@ -61,6 +82,7 @@ impl Evaluable for ForExpr {
 ///     = KW_FOR, IDENTIFIER, KW_IN, expression, code_block
 ///     ;
 pub fn parse_for_expression(p: &mut Parser) -> OptRes<Expression> {
+    let start = p.curr().start;
     if !p.keyword(Kw::For)? {
         return Ok(None);
     }
@@ -72,7 +94,9 @@ pub fn parse_for_expression(p: &mut Parser) -> OptRes<Expression> {
     }
     let provider = parse_expression(p)?.ok_or_else(|| p.error(ErroVar::ForLoopMissingProvider))?;
     let body = parse_code_block(p)?.ok_or_else(|| p.error(ErroVar::ForLoopMissingBody))?;
-    Ok(Some(ForExpr::new(variable, provider, body).into()))
+    Ok(Some(
+        Expression::from(ForExpr::new(variable, provider, body)).spanned(p.span_from(start)),
+    ))
 }
 
 #[cfg(test)]
@@ -88,7 +112,7 @@ mod tests {
         },
     };
 
-    use super::super::super::test_utils::tests::*;
+    use super::super::super::{test_utils::tests::*, DataType};
 
     #[test]
     fn parse_miss() {
@@ -346,7 +370,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::List],
+                actual: DataType::Integer
+            }
         );
     }
 }
@@ -5,14 +5,14 @@ use crate::{
 
 use super::{
     super::utility::*, identifier::parse_identifier_expression, parse_expression, Evaluable,
-    Expression,
+    Expression, ExpressionKind,
 };
 
 /// Function call expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct FunctionCallExpr {
-    identifier: Box<Expression>,
-    arguments: Vec<Expression>,
+    pub identifier: Box<Expression>,
+    pub arguments: Vec<Expression>,
 }
 
 impl FunctionCallExpr {
@@ -24,21 +24,22 @@ impl FunctionCallExpr {
     }
 }
 
-impl From<FunctionCallExpr> for Expression {
+impl From<FunctionCallExpr> for ExpressionKind {
     fn from(e: FunctionCallExpr) -> Self {
-        Expression::FunctionCall(e)
+        ExpressionKind::FunctionCall(e)
     }
 }
 
 impl Evaluable for FunctionCallExpr {
     fn eval(&self, ctx: &dyn Context) -> Result<Value, ExecutionError> {
-        if let Expression::Identifier(identifier) = &*self.identifier {
+        if let ExpressionKind::Identifier(identifier) = &self.identifier.kind {
             let arguments: Vec<Value> = self
                 .arguments
                 .iter()
                 .map(|v| v.eval(ctx))
                 .collect::<Result<_, ExecutionError>>()?;
-            ctx.escalate_error(ctx.call_function(&identifier.0, arguments))
+            ctx.call_function(&identifier.0, arguments)
+                .map_err(|e| e.called_from(&identifier.0, self.identifier.span))
         } else {
             Err(ExecutionError::new(
                 ExecutionErrorVariant::ExpectedIdentifier,
@@ -85,7 +86,9 @@ fn parse_function_call(p: &mut Parser) -> OptRes<Vec<Expression>> {
 pub fn parse_identifier_or_function_call_expression(p: &mut Parser) -> OptRes<Expression> {
     if let Some(mut expression) = parse_identifier_expression(p)? {
         if let Some(arguments) = parse_function_call(p)? {
-            expression = FunctionCallExpr::new(expression, arguments).into();
+            let span = p.span_from(expression.span.start);
+            expression =
+                Expression::from(FunctionCallExpr::new(expression, arguments)).spanned(span);
         }
         return Ok(Some(expression));
     }
@@ -104,6 +107,7 @@ mod tests {
                 parse_expression,
                 return_expr::ReturnExpr,
                 statement::Statement,
+                Expression,
             },
             function::{FunctionDefinition, Parameter},
             DataType,
@@ -332,7 +336,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Integer],
+                actual: DataType::Float
+            }
         );
     }
 
@@ -359,7 +366,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 2,
+                given: 1
+            }
         );
         assert_eq!(
             FunctionCallExpr::new(
@@ -373,7 +383,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::InvalidArgumentCount
+            ExecutionErrorVariant::InvalidArgumentCount {
+                expected: 2,
+                given: 3
+            }
         );
     }
 
@@ -500,7 +513,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Float],
+                actual: DataType::Integer
+            }
         );
     }
 
@@ -512,8 +528,36 @@ mod tests {
                 .eval(&ctx)
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::FunctionDoesNotExist
+            ExecutionErrorVariant::FunctionDoesNotExist("a".to_owned())
+        );
+    }
+
+    #[test]
+    fn eval_suggestions() {
+        let mut ctx = TestCtx::new();
+        ctx.functions.insert(
+            "push".to_owned(),
+            Box::new(FunctionDefinition::new(
+                "push".to_owned(),
+                vec![],
+                vec![],
+                DataType::None,
+            )),
         );
+        let error = Expression::from(FunctionCallExpr::new(
+            IdentifierExpr::new("append".to_owned()).into(),
+            vec![],
+        ))
+        .eval(&ctx)
+        .unwrap_err();
+        assert_eq!(error.details.suggestions, vec!["push".to_owned()]);
+        let error = Expression::from(FunctionCallExpr::new(
+            IdentifierExpr::new("psuh".to_owned()).into(),
+            vec![],
+        ))
+        .eval(&ctx)
+        .unwrap_err();
+        assert_eq!(error.details.suggestions, vec!["push".to_owned()]);
     }
 
     #[test]
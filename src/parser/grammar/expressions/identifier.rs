@@ -3,7 +3,7 @@ use crate::{
     parser::grammar::Value,
 };
 
-use super::{super::utility::*, Evaluable, Expression};
+use super::{super::utility::*, Evaluable, Expression, ExpressionKind};
 
 /// Identifier expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
@@ -15,9 +15,9 @@ impl IdentifierExpr {
     }
 }
 
-impl From<IdentifierExpr> for Expression {
+impl From<IdentifierExpr> for ExpressionKind {
     fn from(e: IdentifierExpr) -> Self {
-        Expression::Identifier(e)
+        ExpressionKind::Identifier(e)
     }
 }
 
@@ -29,8 +29,11 @@ impl Evaluable for IdentifierExpr {
 
 /// IDENTIFIER
 pub fn parse_identifier_expression(p: &mut Parser) -> OptRes<Expression> {
+    let start = p.curr().start;
     if let Some(identifier) = p.identifier()? {
-        return Ok(Some(IdentifierExpr::new(identifier).into()));
+        return Ok(Some(
+            Expression::from(IdentifierExpr::new(identifier)).spanned(p.span_from(start)),
+        ));
     }
     Ok(None)
 }
@@ -39,7 +42,7 @@ pub fn parse_identifier_expression(p: &mut Parser) -> OptRes<Expression> {
 mod tests {
     use crate::{
         interpreter::{test_utils::tests::TestCtx, ExecutionErrorVariant},
-        parser::grammar::expressions::{identifier::IdentifierExpr, parse_expression},
+        parser::grammar::expressions::{identifier::IdentifierExpr, parse_expression, Expression},
     };
 
     use super::super::super::test_utils::tests::*;
@@ -81,7 +84,22 @@ mod tests {
                 .eval(&ctx)
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::VariableDoesNotExist
+            ExecutionErrorVariant::VariableDoesNotExist("a".to_owned())
         );
     }
+
+    #[test]
+    fn eval_suggestions() {
+        let ctx = TestCtx::new();
+        ctx.variables
+            .borrow_mut()
+            .insert("count".to_owned(), Value::Int(8));
+        ctx.variables
+            .borrow_mut()
+            .insert("total".to_owned(), Value::Int(8));
+        let error = Expression::from(IdentifierExpr::new("cuont".to_owned()))
+            .eval(&ctx)
+            .unwrap_err();
+        assert_eq!(error.details.suggestions, vec!["count".to_owned()]);
+    }
 }
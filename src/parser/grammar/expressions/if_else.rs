@@ -1,24 +1,25 @@
 use crate::{
+    analysis::coverage::ProbeKind,
     interpreter::{
         context::{BlockCtx, Context},
-        ExecutionError, ExecutionErrorVariant,
+        coverage, ExecutionError, ExecutionErrorVariant,
     },
-    parser::grammar::Value,
+    parser::grammar::{DataType, Value},
 };
 
 use super::{
     super::utility::*,
     parse_expression,
     statement::{alternate_statements, parse_code_block, Statement},
-    Evaluable, Expression,
+    Evaluable, Expression, ExpressionKind,
 };
 
 /// If-else expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct IfElseExpr {
-    condition: Box<Expression>,
-    true_case: Vec<Statement>,
-    false_case: Option<Vec<Statement>>,
+    pub condition: Box<Expression>,
+    pub true_case: Vec<Statement>,
+    pub false_case: Option<Vec<Statement>>,
 }
 
 impl IfElseExpr {
@@ -35,9 +36,9 @@ impl IfElseExpr {
     }
 }
 
-impl From<IfElseExpr> for Expression {
+impl From<IfElseExpr> for ExpressionKind {
     fn from(e: IfElseExpr) -> Self {
-        Expression::IfElse(e)
+        ExpressionKind::IfElse(e)
     }
 }
 
@@ -46,10 +47,12 @@ impl Evaluable for IfElseExpr {
         let cond = self.condition.eval(ctx)?;
         match cond {
             Value::Bool(true) => {
+                coverage::hit(ctx, ProbeKind::TrueBranch, self.condition.span);
                 let ctx = BlockCtx::new(ctx, "if branch".to_owned());
                 ctx.escalate_error(alternate_statements(&self.true_case, &ctx))
             }
             Value::Bool(false) => {
+                coverage::hit(ctx, ProbeKind::FalseBranch, self.condition.span);
                 if let Some(statements) = &self.false_case {
                     let ctx = BlockCtx::new(ctx, "else branch".to_owned());
                     ctx.escalate_error(alternate_statements(statements, &ctx))
@@ -57,7 +60,10 @@ impl Evaluable for IfElseExpr {
                     Ok(Value::None)
                 }
             }
-            _ => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType)),
+            v => Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Bool],
+                actual: (&v).into(),
+            })),
         }
     }
 }
@@ -66,6 +72,7 @@ impl Evaluable for IfElseExpr {
 ///     = KW_IF, expression, code_block, [KW_ELSE, code_block]
 ///     ;
 pub fn parse_if_else_expression(p: &mut Parser) -> OptRes<Expression> {
+    let start = p.curr().start;
     if !p.keyword(Kw::If)? {
         return Ok(None);
     }
@@ -77,7 +84,8 @@ pub fn parse_if_else_expression(p: &mut Parser) -> OptRes<Expression> {
         None
     };
     Ok(Some(
-        IfElseExpr::new(condition, true_case, false_case).into(),
+        Expression::from(IfElseExpr::new(condition, true_case, false_case))
+            .spanned(p.span_from(start)),
     ))
 }
 
@@ -93,7 +101,7 @@ mod tests {
         },
     };
 
-    use super::super::super::test_utils::tests::*;
+    use super::super::super::{test_utils::tests::*, DataType};
 
     #[test]
     fn miss() {
@@ -316,7 +324,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Bool],
+                actual: DataType::Integer
+            }
         );
     }
 
@@ -1,13 +1,13 @@
 use crate::{
-    interpreter::{context::Context, ExecutionError},
+    interpreter::{context::Context, memory, ExecutionError},
     parser::grammar::Value,
 };
 
-use super::{super::utility::*, parse_expression, Evaluable, Expression};
+use super::{super::utility::*, parse_expression, Evaluable, Expression, ExpressionKind};
 
 /// List expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
-pub struct ListExpr(Vec<Expression>);
+pub struct ListExpr(pub Vec<Expression>);
 
 impl ListExpr {
     pub fn new(list: Vec<Expression>) -> Self {
@@ -15,9 +15,9 @@ impl ListExpr {
     }
 }
 
-impl From<ListExpr> for Expression {
+impl From<ListExpr> for ExpressionKind {
     fn from(e: ListExpr) -> Self {
-        Expression::List(e)
+        ExpressionKind::List(e)
     }
 }
 
@@ -28,7 +28,9 @@ impl Evaluable for ListExpr {
             .iter()
             .map(|e| e.eval(ctx))
             .collect::<Result<_, ExecutionError>>()?;
-        Ok(Value::List(values))
+        let list = Value::List(values);
+        memory::allocated(ctx, &list);
+        Ok(list)
     }
 }
 
@@ -37,6 +39,7 @@ impl Evaluable for ListExpr {
 ///     ;
 pub fn parse_list_expression(p: &mut Parser) -> OptRes<Expression> {
     let mut list: Vec<Expression> = vec![];
+    let start = p.curr().start;
     if !p.operator(Op::OpenSquareBracket)? {
         return Ok(None);
     }
@@ -53,7 +56,9 @@ pub fn parse_list_expression(p: &mut Parser) -> OptRes<Expression> {
     if !p.operator(Op::CloseSquareBracket)? {
         p.warn(WarnVar::MissingClosingSquareBracket)?;
     }
-    Ok(Some(ListExpr::new(list).into()))
+    Ok(Some(
+        Expression::from(ListExpr::new(list)).spanned(p.span_from(start)),
+    ))
 }
 
 #[cfg(test)]
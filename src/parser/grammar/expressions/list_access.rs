@@ -1,11 +1,11 @@
 use crate::{
-    interpreter::{context::Context, ExecutionError, ExecutionErrorVariant},
+    interpreter::{context::Context, memory, ExecutionError, ExecutionErrorVariant},
     parser::grammar::Value,
 };
 
 use super::{
     super::utility::*, parse_constant_or_identifier_or_bracket_expression, parse_expression,
-    Evaluable, Expression,
+    Evaluable, Expression, ExpressionKind,
 };
 
 /// Two ways of accessing list elements
@@ -28,8 +28,8 @@ impl IndexOrRange {
 /// List access expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct ListAccessExpr {
-    list: Box<Expression>,
-    access: IndexOrRange,
+    pub list: Box<Expression>,
+    pub access: IndexOrRange,
 }
 
 impl ListAccessExpr {
@@ -41,9 +41,9 @@ impl ListAccessExpr {
     }
 }
 
-impl From<ListAccessExpr> for Expression {
+impl From<ListAccessExpr> for ExpressionKind {
     fn from(e: ListAccessExpr) -> Self {
-        Expression::ListAccess(e)
+        ExpressionKind::ListAccess(e)
     }
 }
 
@@ -95,7 +95,8 @@ fn parse_list_access(p: &mut Parser) -> OptRes<IndexOrRange> {
 pub fn parse_list_access_expression(p: &mut Parser) -> OptRes<Expression> {
     if let Some(mut expression) = parse_constant_or_identifier_or_bracket_expression(p)? {
         if let Some(access) = parse_list_access(p)? {
-            expression = ListAccessExpr::new(expression, access).into();
+            let span = p.span_from(expression.span.start);
+            expression = Expression::from(ListAccessExpr::new(expression, access)).spanned(span);
         }
         return Ok(Some(expression));
     }
@@ -110,12 +111,16 @@ fn eval_list(
     match access {
         IndexOrRange::Index(idx) => {
             let idx = idx.eval(ctx)?;
-            eval_index(list, idx)
+            let value = eval_index(list, idx)?;
+            memory::copied(ctx, &value);
+            Ok(value)
         }
         IndexOrRange::Range(lidx, ridx) => {
             let lidx = lidx.eval(ctx)?;
             let ridx = ridx.eval(ctx)?;
-            eval_range(list, lidx, ridx).map(Value::List)
+            let value = Value::List(eval_range(list, lidx, ridx)?);
+            memory::allocated(ctx, &value);
+            Ok(value)
         }
     }
 }
@@ -126,7 +131,7 @@ fn eval_string(
     list: String,
 ) -> Result<Value, ExecutionError> {
     let list: Vec<char> = list.chars().collect();
-    match access {
+    let value = match access {
         IndexOrRange::Index(idx) => {
             let idx = idx.eval(ctx)?;
             eval_index(list, idx).map(|c| Value::String(c.into()))
@@ -136,7 +141,16 @@ fn eval_string(
             let ridx = ridx.eval(ctx)?;
             eval_range(list, lidx, ridx).map(|v| Value::String(v.into_iter().collect()))
         }
-    }
+    }?;
+    memory::allocated(ctx, &value);
+    Ok(value)
+}
+
+fn out_of_bounds(index: i64, length: i64) -> ExecutionError {
+    ExecutionError::new(ExecutionErrorVariant::IndexOutOfBounds {
+        index,
+        length: length as usize,
+    })
 }
 
 fn eval_range<T: Clone>(
@@ -149,7 +163,7 @@ fn eval_range<T: Clone>(
         if 0 <= lidx && lidx < list_size {
             lidx as usize
         } else {
-            return Err(ExecutionError::new(ExecutionErrorVariant::IndexOutOfBounds));
+            return Err(out_of_bounds(lidx, list_size));
         }
     } else {
         return Err(ExecutionError::new(ExecutionErrorVariant::NonIntegerIndex));
@@ -158,7 +172,7 @@ fn eval_range<T: Clone>(
         if 0 < ridx && ridx <= list_size {
             ridx as usize
         } else {
-            return Err(ExecutionError::new(ExecutionErrorVariant::IndexOutOfBounds));
+            return Err(out_of_bounds(ridx, list_size));
         }
     } else {
         return Err(ExecutionError::new(ExecutionErrorVariant::NonIntegerIndex));
@@ -172,7 +186,7 @@ fn eval_index<T: Clone>(list: Vec<T>, idx: Value) -> Result<T, ExecutionError> {
         if 0 <= idx && idx < list_size {
             idx as usize
         } else {
-            return Err(ExecutionError::new(ExecutionErrorVariant::IndexOutOfBounds));
+            return Err(out_of_bounds(idx, list_size));
         }
     } else {
         return Err(ExecutionError::new(ExecutionErrorVariant::NonIntegerIndex));
@@ -356,7 +370,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::IndexOutOfBounds,
+            ExecutionErrorVariant::IndexOutOfBounds {
+                index: 0,
+                length: 0
+            },
         );
         assert_eq!(
             ListAccessExpr::new(
@@ -366,7 +383,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::IndexOutOfBounds,
+            ExecutionErrorVariant::IndexOutOfBounds {
+                index: 4,
+                length: 3
+            },
         );
         assert_eq!(
             ListAccessExpr::new(
@@ -424,7 +444,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::IndexOutOfBounds,
+            ExecutionErrorVariant::IndexOutOfBounds {
+                index: 1,
+                length: 0
+            },
         );
         assert_eq!(
             ListAccessExpr::new(
@@ -434,7 +457,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::IndexOutOfBounds,
+            ExecutionErrorVariant::IndexOutOfBounds {
+                index: 7,
+                length: 3
+            },
         );
         assert_eq!(
             ListAccessExpr::new(
@@ -502,7 +528,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::IndexOutOfBounds,
+            ExecutionErrorVariant::IndexOutOfBounds {
+                index: -1,
+                length: 1
+            },
         );
         assert_eq!(
             ListAccessExpr::new(
@@ -512,7 +541,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::IndexOutOfBounds,
+            ExecutionErrorVariant::IndexOutOfBounds {
+                index: 4,
+                length: 4
+            },
         );
     }
 
@@ -550,7 +582,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::IndexOutOfBounds,
+            ExecutionErrorVariant::IndexOutOfBounds {
+                index: -1,
+                length: 1
+            },
         );
         assert_eq!(
             ListAccessExpr::new(
@@ -560,7 +595,10 @@ mod tests {
             .eval(&ctx)
             .unwrap_err()
             .variant,
-            ExecutionErrorVariant::IndexOutOfBounds,
+            ExecutionErrorVariant::IndexOutOfBounds {
+                index: 7,
+                length: 4
+            },
         );
     }
 }
@@ -1,13 +1,13 @@
 use crate::{
-    interpreter::{context::Context, ExecutionError},
+    interpreter::{context::Context, memory, ExecutionError},
     parser::grammar::Value,
 };
 
-use super::{super::utility::*, Evaluable, Expression};
+use super::{super::utility::*, Evaluable, Expression, ExpressionKind};
 
 /// Literal expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
-pub struct LiteralExpr(Value);
+pub struct LiteralExpr(pub Value);
 
 impl LiteralExpr {
     #[allow(dead_code)]
@@ -16,20 +16,21 @@ impl LiteralExpr {
     }
 }
 
-impl From<LiteralExpr> for Expression {
+impl From<LiteralExpr> for ExpressionKind {
     fn from(e: LiteralExpr) -> Self {
-        Expression::Literal(e)
+        ExpressionKind::Literal(e)
     }
 }
 
-impl From<Value> for Expression {
+impl From<Value> for ExpressionKind {
     fn from(v: Value) -> Self {
-        LiteralExpr(v).into()
+        ExpressionKind::Literal(LiteralExpr(v))
     }
 }
 
 impl Evaluable for LiteralExpr {
-    fn eval(&self, _ctx: &dyn Context) -> Result<Value, ExecutionError> {
+    fn eval(&self, ctx: &dyn Context) -> Result<Value, ExecutionError> {
+        memory::copied(ctx, &self.0);
         Ok(self.0.clone())
     }
 }
@@ -76,11 +77,12 @@ fn parse_string(p: &mut Parser) -> OptRes<Value> {
 ///     | CONST_STRING
 ///     ;
 pub fn parse_literal_expression(p: &mut Parser) -> OptRes<Expression> {
-    parse_integer(p)
+    let start = p.curr().start;
+    let value = parse_integer(p)
         .alt(|| parse_float(p))
         .alt(|| parse_bool(p))
-        .alt(|| parse_string(p))
-        .map(|v| v.map(|v| LiteralExpr(v).into()))
+        .alt(|| parse_string(p))?;
+    Ok(value.map(|v| Expression::from(LiteralExpr(v)).spanned(p.span_from(start))))
 }
 
 #[cfg(test)]
@@ -1,8 +1,8 @@
 use core::fmt::Debug;
 
 use crate::{
-    interpreter::{context::Context, ExecutionError},
-    parser::Parser,
+    interpreter::{context::Context, debugger, memory, trace, ExecutionError},
+    parser::{position::Span, Parser},
 };
 
 use self::{
@@ -44,8 +44,9 @@ pub trait Evaluable {
     fn eval(&self, ctx: &dyn Context) -> Result<Value, ExecutionError>;
 }
 
+/// Kinds of expressions
 #[derive(Debug, Clone, PartialEq, Serialize)]
-pub enum Expression {
+pub enum ExpressionKind {
     Assignment(AssignmentExpr),
     Binary(BinaryExpr),
     CodeBlock(CodeBlockExpr),
@@ -62,23 +63,71 @@ pub enum Expression {
     While(WhileExpr),
 }
 
+/// Expression along with the part of source code it was parsed from
+#[derive(Debug, Clone, Serialize)]
+pub struct Expression {
+    pub kind: ExpressionKind,
+    pub span: Span,
+}
+
+/// Spans are not compared, so the same code parsed from differently formatted sources is equal
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
+
+impl Expression {
+    /// Replaces the span of the expression
+    pub fn spanned(self, span: Span) -> Self {
+        Self { span, ..self }
+    }
+}
+
+impl<T: Into<ExpressionKind>> From<T> for Expression {
+    fn from(kind: T) -> Self {
+        Self {
+            kind: kind.into(),
+            span: Span::default(),
+        }
+    }
+}
+
 impl Evaluable for Expression {
+    /// Errors which don't point at any expression yet are attributed to this one
+    fn eval(&self, ctx: &dyn Context) -> Result<Value, ExecutionError> {
+        if ctx.hooks().is_none() {
+            return self
+                .kind
+                .eval(ctx)
+                .map_err(|e| e.suggest(ctx).at(self.span));
+        }
+        trace::expression(ctx, self.span, || {
+            debugger::expression(ctx, self.span, || {
+                memory::expression(ctx, self.span, || self.kind.eval(ctx))
+            })
+        })
+        .map_err(|e| e.suggest(ctx).at(self.span))
+    }
+}
+
+impl Evaluable for ExpressionKind {
     fn eval(&self, ctx: &dyn Context) -> Result<Value, ExecutionError> {
         match self {
-            Expression::Assignment(v) => v.eval(ctx),
-            Expression::Binary(v) => v.eval(ctx),
-            Expression::CodeBlock(v) => v.eval(ctx),
-            Expression::Declaration(v) => v.eval(ctx),
-            Expression::For(v) => v.eval(ctx),
-            Expression::FunctionCall(v) => v.eval(ctx),
-            Expression::Identifier(v) => v.eval(ctx),
-            Expression::IfElse(v) => v.eval(ctx),
-            Expression::ListAccess(v) => v.eval(ctx),
-            Expression::List(v) => v.eval(ctx),
-            Expression::Literal(v) => v.eval(ctx),
-            Expression::Return(v) => v.eval(ctx),
-            Expression::Unary(v) => v.eval(ctx),
-            Expression::While(v) => v.eval(ctx),
+            ExpressionKind::Assignment(v) => v.eval(ctx),
+            ExpressionKind::Binary(v) => v.eval(ctx),
+            ExpressionKind::CodeBlock(v) => v.eval(ctx),
+            ExpressionKind::Declaration(v) => v.eval(ctx),
+            ExpressionKind::For(v) => v.eval(ctx),
+            ExpressionKind::FunctionCall(v) => v.eval(ctx),
+            ExpressionKind::Identifier(v) => v.eval(ctx),
+            ExpressionKind::IfElse(v) => v.eval(ctx),
+            ExpressionKind::ListAccess(v) => v.eval(ctx),
+            ExpressionKind::List(v) => v.eval(ctx),
+            ExpressionKind::Literal(v) => v.eval(ctx),
+            ExpressionKind::Return(v) => v.eval(ctx),
+            ExpressionKind::Unary(v) => v.eval(ctx),
+            ExpressionKind::While(v) => v.eval(ctx),
         }
     }
 }
@@ -140,7 +189,7 @@ pub fn parse_expression(p: &mut Parser) -> OptRes<Expression> {
 
 #[cfg(test)]
 mod tests {
-    use crate::parser::grammar::expressions::parse_expression;
+    use crate::parser::{grammar::expressions::parse_expression, position::Span};
 
     use super::super::test_utils::tests::*;
 
@@ -231,4 +280,39 @@ mod tests {
 
         assert!(warnings.is_empty());
     }
+
+    #[test]
+    fn spans() {
+        let (result, warnings) = partial_parse(
+            vec![
+                token(TokenType::Identifier("a".to_owned()), (2, 5), (2, 6)),
+                token(TokenType::Operator(Op::Equal), (2, 7), (2, 8)),
+                token(TokenType::Identifier("f".to_owned()), (2, 9), (2, 10)),
+                token(TokenType::Operator(Op::OpenRoundBracket), (2, 10), (2, 11)),
+                token(TokenType::Int(1), (2, 11), (2, 12)),
+                token(TokenType::Operator(Op::CloseRoundBracket), (2, 12), (2, 13)),
+                token(TokenType::Operator(Op::Asterisk), (2, 14), (2, 15)),
+                token(TokenType::Int(2), (3, 1), (3, 2)),
+                token(TokenType::Operator(Op::Semicolon), (3, 2), (3, 3)),
+            ],
+            parse_expression,
+        );
+        let assignment = result.unwrap().unwrap();
+        assert_eq!(
+            assignment.span,
+            Span::new(Position::new(2, 5), Position::new(3, 2))
+        );
+        let json = serde_json::to_value(&assignment).unwrap();
+        let multiplication = &json["kind"]["Assignment"]["expression"];
+        assert_eq!(multiplication["span"]["start"]["col"], 9);
+        assert_eq!(multiplication["span"]["stop"]["row"], 3);
+        let call = &multiplication["kind"]["Binary"]["lhs"];
+        assert_eq!(call["span"]["start"]["col"], 9);
+        assert_eq!(call["span"]["stop"]["col"], 13);
+        let argument = &call["kind"]["FunctionCall"]["arguments"][0];
+        assert_eq!(argument["span"]["start"]["col"], 11);
+        assert_eq!(argument["span"]["stop"]["col"], 12);
+
+        assert!(warnings.is_empty());
+    }
 }
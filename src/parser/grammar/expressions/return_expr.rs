@@ -3,11 +3,13 @@ use crate::{
     parser::grammar::Value,
 };
 
-use super::{super::utility::*, parse_control_flow_expression, Evaluable, Expression};
+use super::{
+    super::utility::*, parse_control_flow_expression, Evaluable, Expression, ExpressionKind,
+};
 
 /// Return expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
-pub struct ReturnExpr(Option<Box<Expression>>);
+pub struct ReturnExpr(pub Option<Box<Expression>>);
 
 impl ReturnExpr {
     pub fn new(value: Expression) -> Self {
@@ -19,9 +21,9 @@ impl ReturnExpr {
     }
 }
 
-impl From<ReturnExpr> for Expression {
+impl From<ReturnExpr> for ExpressionKind {
     fn from(e: ReturnExpr) -> Self {
-        Expression::Return(e)
+        ExpressionKind::Return(e)
     }
 }
 
@@ -41,14 +43,18 @@ impl Evaluable for ReturnExpr {
 ///     KW_RETURN, [control_flow_expression]
 ///     ;
 pub fn parse_return(p: &mut Parser) -> OptRes<Expression> {
+    let start = p.curr().start;
     if !p.keyword(Kw::Return)? {
         return Ok(None);
     }
-    if let Some(expression) = parse_control_flow_expression(p)? {
-        Ok(Some(ReturnExpr::new(expression).into()))
+    let return_expr = if let Some(expression) = parse_control_flow_expression(p)? {
+        ReturnExpr::new(expression)
     } else {
-        Ok(Some(ReturnExpr::empty().into()))
-    }
+        ReturnExpr::empty()
+    };
+    Ok(Some(
+        Expression::from(return_expr).spanned(p.span_from(start)),
+    ))
 }
 
 #[cfg(test)]
@@ -1,6 +1,7 @@
 use crate::{
-    interpreter::{context::Context, ExecutionError, ExecutionErrorVariant},
-    parser::grammar::Value,
+    analysis::coverage::ProbeKind,
+    interpreter::{context::Context, coverage, ExecutionError, ExecutionErrorVariant},
+    parser::{grammar::Value, position::Position, ParserError},
 };
 
 use super::{parse_expression, Evaluable, Expression};
@@ -21,13 +22,47 @@ impl<T: Into<Expression>> From<T> for Statement {
     }
 }
 
+/// Records an error when an expression directly follows another one, without a `;` in between.
+/// `unterminated` holds the end of the previous expression until a `;` follows it.
+pub fn expect_semicolon(
+    p: &mut Parser,
+    unterminated: &mut Option<Position>,
+    statement: &Statement,
+) -> Res<()> {
+    match statement {
+        Statement::Expression(expression) => {
+            if let Some(pos) = unterminated.replace(expression.span.stop) {
+                p.recover(ParserError {
+                    error: ErroVar::ExpectedSemicolon,
+                    pos,
+                })?;
+            }
+        }
+        Statement::Semicolon => *unterminated = None,
+    }
+    Ok(())
+}
+
 /// statements
 ///     = {statement}
 ///     ;
+///
+/// Erroneous statements are skipped.
 fn parse_statements(p: &mut Parser) -> Res<Vec<Statement>> {
     let mut statements = vec![];
-    while let Some(statement) = parse_statement(p)? {
-        statements.push(statement);
+    let mut unterminated = None;
+    loop {
+        match parse_statement(p) {
+            Ok(Some(statement)) => {
+                expect_semicolon(p, &mut unterminated, &statement)?;
+                statements.push(statement);
+            }
+            Ok(None) => break,
+            Err(err) => {
+                p.recover(err)?;
+                p.synchronize();
+            }
+        }
     }
     Ok(statements)
 }
@@ -36,7 +71,7 @@ fn parse_statements(p: &mut Parser) -> Res<Vec<Statement>> {
 ///     = expression
 ///     | EXPRESSION_END
 ///     ;
-fn parse_statement(p: &mut Parser) -> OptRes<Statement> {
+pub fn parse_statement(p: &mut Parser) -> OptRes<Statement> {
     if p.operator(Op::Semicolon)? {
         return Ok(Some(Statement::Semicolon));
     } else if let Some(expression) = parse_expression(p)? {
@@ -68,12 +103,14 @@ pub fn alternate_statements(
 
     for statement in statements {
         match (statement, semicolon) {
-            (Statement::Expression(_), true) => {
-                return Err(ExecutionError::new(
-                    ExecutionErrorVariant::ExpectedSemicolon,
-                ))
+            (Statement::Expression(expression), true) => {
+                return Err(
+                    ExecutionError::new(ExecutionErrorVariant::ExpectedSemicolon)
+                        .at(expression.span),
+                )
             }
             (Statement::Expression(expression), false) => {
+                coverage::hit(ctx, ProbeKind::Statement, expression.span);
                 returning = expression.eval(ctx)?;
                 semicolon = true;
             }
@@ -90,3 +127,11 @@ pub fn alternate_statements(
 
     Ok(returning)
 }
+
+/// Returns the last expression of a block, unless it's followed by a `;`
+pub fn block_value(statements: &[Statement]) -> Option<&Expression> {
+    match statements.last() {
+        Some(Statement::Expression(expression)) => Some(expression),
+        _ => None,
+    }
+}
@@ -1,9 +1,12 @@
 use crate::{
     interpreter::{context::Context, ExecutionError, ExecutionErrorVariant},
-    parser::grammar::{test_utils::tests::TokenType, Value},
+    parser::{grammar::Value, token::TokenType},
 };
 
-use super::{super::utility::*, list_access::parse_list_access_expression, Evaluable, Expression};
+use super::{
+    super::utility::*, list_access::parse_list_access_expression, Evaluable, Expression,
+    ExpressionKind,
+};
 
 /// Algebraic negation and logical negation
 #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
@@ -15,8 +18,8 @@ pub enum UnaryOperator {
 /// Unary expression
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct UnaryExpr {
-    operator: UnaryOperator,
-    expression: Box<Expression>,
+    pub operator: UnaryOperator,
+    pub expression: Box<Expression>,
 }
 
 impl UnaryExpr {
@@ -28,23 +31,28 @@ impl UnaryExpr {
     }
 }
 
-impl From<UnaryExpr> for Expression {
+impl From<UnaryExpr> for ExpressionKind {
     fn from(e: UnaryExpr) -> Self {
-        Expression::Unary(e)
+        ExpressionKind::Unary(e)
     }
 }
 
 impl Evaluable for UnaryExpr {
     fn eval(&self, ctx: &dyn Context) -> Result<Value, ExecutionError> {
         let value = self.expression.eval(ctx)?;
-        match (self.operator, value) {
-            (UnaryOperator::AlgebraicNegation, Value::Int(value)) => Ok(Value::Int(-value)),
-            (UnaryOperator::AlgebraicNegation, Value::Float(value)) => Ok(Value::Float(-value)),
-            (UnaryOperator::LogicalNegation, Value::Bool(value)) => Ok(Value::Bool(!value)),
-            _ => Err(ExecutionError::new(
-                ExecutionErrorVariant::UnsupportedUnaryOperation,
-            )),
-        }
+        apply(self.operator, value)
+    }
+}
+
+/// Applies an operator to a value
+pub fn apply(operator: UnaryOperator, value: Value) -> Result<Value, ExecutionError> {
+    match (operator, value) {
+        (UnaryOperator::AlgebraicNegation, Value::Int(value)) => Ok(Value::Int(-value)),
+        (UnaryOperator::AlgebraicNegation, Value::Float(value)) => Ok(Value::Float(-value)),
+        (UnaryOperator::LogicalNegation, Value::Bool(value)) => Ok(Value::Bool(!value)),
+        _ => Err(ExecutionError::new(
+            ExecutionErrorVariant::UnsupportedUnaryOperation,
+        )),
     }
 }
 
@@ -69,10 +77,13 @@ fn parse_unary_operators(p: &mut Parser) -> OptRes<UnaryOperator> {
 ///     = {unary_operators}, list_access_expression
 ///     ;
 pub fn parse_unary_operator_expression(p: &mut Parser) -> OptRes<Expression> {
+    let start = p.curr().start;
     if let Some(operator) = parse_unary_operators(p)? {
         let expression = parse_unary_operator_expression(p)?
             .ok_or_else(|| p.error(ErroVar::UnaryOperatorMissingExpression))?;
-        Ok(Some(UnaryExpr::new(operator, expression).into()))
+        Ok(Some(
+            Expression::from(UnaryExpr::new(operator, expression)).spanned(p.span_from(start)),
+        ))
     } else if let Some(expression) = parse_list_access_expression(p)? {
         Ok(Some(expression))
     } else {
@@ -1,23 +1,24 @@
 use crate::{
+    analysis::coverage::ProbeKind,
     interpreter::{
         context::{BlockCtx, Context},
-        ExecutionError, ExecutionErrorVariant,
+        coverage, memory, trace, ExecutionError, ExecutionErrorVariant,
     },
-    parser::grammar::Value,
+    parser::grammar::{DataType, Value},
 };
 
 use super::{
     super::utility::*,
     parse_expression,
-    statement::{alternate_statements, parse_code_block, Statement},
-    Evaluable, Expression,
+    statement::{alternate_statements, block_value, parse_code_block, Statement},
+    Evaluable, Expression, ExpressionKind,
 };
 
 /// While loop expresison
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct WhileExpr {
-    condition: Box<Expression>,
-    body: Vec<Statement>,
+    pub condition: Box<Expression>,
+    pub body: Vec<Statement>,
 }
 
 impl WhileExpr {
@@ -27,11 +28,16 @@ impl WhileExpr {
             body,
         }
     }
+
+    /// Returns the expression whose values are collected into the list returned by the loop
+    pub fn body_value(&self) -> Option<&Expression> {
+        block_value(&self.body)
+    }
 }
 
-impl From<WhileExpr> for Expression {
+impl From<WhileExpr> for ExpressionKind {
     fn from(e: WhileExpr) -> Self {
-        Expression::While(e)
+        ExpressionKind::While(e)
     }
 }
 
@@ -41,14 +47,25 @@ impl Evaluable for WhileExpr {
         let mut results = vec![];
         while match self.condition.eval(&ctx)? {
             Value::Bool(b) => b,
-            _ => return Err(ExecutionError::new(ExecutionErrorVariant::InvalidType)),
+            v => {
+                return Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+                    expected: vec![DataType::Bool],
+                    actual: (&v).into(),
+                }))
+            }
         } {
-            results.push(ctx.escalate_error(alternate_statements(&self.body, &ctx))?);
+            coverage::hit(&ctx, ProbeKind::LoopBody, self.condition.span);
+            let result = trace::iteration(&ctx, "while loop", results.len() + 1, None, || {
+                alternate_statements(&self.body, &ctx)
+            });
+            results.push(ctx.escalate_error(result)?);
             if ctx.is_ret() {
                 break;
             }
         }
-        Ok(Value::List(results))
+        let results = Value::List(results);
+        memory::allocated(&ctx, &results);
+        Ok(results)
     }
 }
 
@@ -56,13 +73,16 @@ impl Evaluable for WhileExpr {
 ///     = KW_WHILE, expression, code_block
 ///     ;
 pub fn parse_while_expression(p: &mut Parser) -> OptRes<Expression> {
+    let start = p.curr().start;
     if !p.keyword(Kw::While)? {
         return Ok(None);
     }
     let condition =
         parse_expression(p)?.ok_or_else(|| p.error(ErroVar::WhileLoopMissingCondition))?;
     let body = parse_code_block(p)?.ok_or_else(|| p.error(ErroVar::WhileLoopMissingBody))?;
-    Ok(Some(WhileExpr::new(condition, body).into()))
+    Ok(Some(
+        Expression::from(WhileExpr::new(condition, body)).spanned(p.span_from(start)),
+    ))
 }
 
 #[cfg(test)]
@@ -79,7 +99,7 @@ mod tests {
         },
     };
 
-    use super::super::super::test_utils::tests::*;
+    use super::super::super::{test_utils::tests::*, DataType};
 
     #[test]
     fn miss_while_loop() {
@@ -239,7 +259,10 @@ mod tests {
                 .eval(&ctx)
                 .unwrap_err()
                 .variant,
-            ExecutionErrorVariant::InvalidType
+            ExecutionErrorVariant::InvalidType {
+                expected: vec![DataType::Bool],
+                actual: DataType::Integer
+            }
         );
     }
 }
@@ -1,10 +1,23 @@
-use std::{cell::RefCell, collections::HashMap};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+};
 
-use crate::interpreter::{
-    callable::Callable,
-    context::Context,
-    types::{validate_type, validate_types},
-    ExecutionError, ExecutionErrorVariant,
+use crate::{
+    analysis::coverage::ProbeKind,
+    interpreter::{
+        callable::Callable,
+        context::Context,
+        coverage::{self},
+        debugger::{self},
+        hooks::Hooks,
+        memory::{self},
+        profiler::{self},
+        trace::{self},
+        types::{validate_type, validate_types},
+        ExecutionError, ExecutionErrorVariant,
+    },
+    parser::position::Span,
 };
 
 use super::{
@@ -15,26 +28,51 @@ use super::{
 };
 
 /// A single function parameter
-#[derive(Debug, Serialize, PartialEq)]
+#[derive(Debug, Serialize)]
 pub struct Parameter {
     pub name: String,
     pub data_type: DataType,
+    /// From the name to the type
+    pub span: Span,
+}
+
+/// Spans are not compared, like the ones of function definitions
+impl PartialEq for Parameter {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.data_type == other.data_type
+    }
 }
 
 impl Parameter {
     #[allow(dead_code)]
     pub fn new(name: String, data_type: DataType) -> Self {
-        Self { name, data_type }
+        Self {
+            name,
+            data_type,
+            span: Span::default(),
+        }
     }
 }
 
 /// Definition of a function
-#[derive(Debug, Serialize, PartialEq)]
+#[derive(Debug, Serialize)]
 pub struct FunctionDefinition {
     pub identifier: String,
     pub params: Vec<Parameter>,
-    statements: Vec<Statement>,
+    pub statements: Vec<Statement>,
     pub data_type: DataType,
+    /// Signature, from `fn` to the return type
+    pub span: Span,
+}
+
+/// Spans are not compared, so the same code parsed from differently formatted sources is equal
+impl PartialEq for FunctionDefinition {
+    fn eq(&self, other: &Self) -> bool {
+        self.identifier == other.identifier
+            && self.params == other.params
+            && self.statements == other.statements
+            && self.data_type == other.data_type
+    }
 }
 
 impl FunctionDefinition {
@@ -50,24 +88,45 @@ impl FunctionDefinition {
             params,
             statements,
             data_type,
+            span: Span::default(),
         }
     }
 }
 
 impl Callable for FunctionDefinition {
     fn call(&self, ctx: &dyn Context, args: Vec<Value>) -> Result<Value, ExecutionError> {
+        if ctx.hooks().is_none() {
+            return self.execute(ctx, args);
+        }
+        coverage::hit(ctx, ProbeKind::Function(self.identifier.clone()), self.span);
+        trace::call(ctx, &self.identifier, args, |args| {
+            profiler::call(ctx, &self.identifier, false, || {
+                debugger::call(ctx, &self.identifier, || {
+                    memory::call(ctx, &self.identifier, || self.execute(ctx, args))
+                })
+            })
+        })
+    }
+}
+
+impl FunctionDefinition {
+    /// Binds the arguments to the parameters and runs the body
+    fn execute(&self, ctx: &dyn Context, args: Vec<Value>) -> Result<Value, ExecutionError> {
         if self.params.len() != args.len() {
             return Err(ExecutionError::new(
-                ExecutionErrorVariant::InvalidArgumentCount,
+                ExecutionErrorVariant::InvalidArgumentCount {
+                    expected: self.params.len(),
+                    given: args.len(),
+                },
             ));
         }
         let mut variables = HashMap::new();
-        for (parameter, argument) in self.params.iter().zip(args.into_iter()) {
+        for (parameter, argument) in self.params.iter().zip(args) {
             validate_type(parameter.data_type, &argument)?;
             variables.insert(parameter.name.clone(), argument);
         }
         let ctx = FunctionCtx::new(ctx, self.identifier.clone(), variables);
-        let returning = alternate_statements(&self.statements, &ctx)?;
+        let returning = ctx.escalate_error(alternate_statements(&self.statements, &ctx))?;
         let returning = ctx.returning.replace(None).unwrap_or(returning);
         validate_type(self.data_type, &returning)?;
         Ok(returning)
@@ -78,13 +137,25 @@ impl Callable for FunctionDefinition {
 pub struct FunctionCtx<'a> {
     name: String,
     parent: &'a dyn Context,
+    /// Hooks of the outermost context, looked up once instead of on every expression
+    hooks: Option<&'a Hooks>,
     returning: RefCell<Option<Value>>,
     variables: RefCell<HashMap<String, Value>>,
 }
 
+/// Variables go out of scope along with the context
+impl Drop for FunctionCtx<'_> {
+    fn drop(&mut self) {
+        for value in self.variables.get_mut().values() {
+            memory::stored(self.parent, Some(value), None);
+        }
+    }
+}
+
 impl Context for FunctionCtx<'_> {
     fn get_variable(&self, id: &str) -> Result<Value, ExecutionError> {
         if let Some(v) = self.variables.borrow().get(id) {
+            memory::copied(self, v);
             Ok(v.clone())
         } else {
             self.parent.get_variable(id)
@@ -94,6 +165,7 @@ impl Context for FunctionCtx<'_> {
     fn set_variable(&self, id: &str, value: Value) -> Result<(), ExecutionError> {
         if let Some(v) = self.variables.borrow_mut().get_mut(id) {
             validate_types(v, &value)?;
+            memory::stored(self, Some(v), Some(&value));
             *v = value;
             Ok(())
         } else {
@@ -104,9 +176,10 @@ impl Context for FunctionCtx<'_> {
     fn new_variable(&self, id: &str, value: Value) -> Result<(), ExecutionError> {
         if self.variables.borrow().contains_key(id) {
             return Err(ExecutionError::new(
-                ExecutionErrorVariant::VariableAlreadyExists,
+                ExecutionErrorVariant::VariableAlreadyExists(id.to_owned()),
             ));
         }
+        memory::stored(self, None, Some(&value));
         self.variables.borrow_mut().insert(id.to_owned(), value);
         Ok(())
     }
@@ -126,13 +199,39 @@ impl Context for FunctionCtx<'_> {
     fn name(&self) -> String {
         format!("`{}` function", self.name)
     }
+
+    fn variable_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.variables.borrow().keys().cloned().collect();
+        names.extend(self.parent.variable_names());
+        names
+    }
+
+    fn function_names(&self) -> Vec<String> {
+        self.parent.function_names()
+    }
+
+    fn local_variables(&self) -> BTreeMap<String, Value> {
+        self.variables.borrow().clone().into_iter().collect()
+    }
+
+    fn hooks(&self) -> Option<&Hooks> {
+        self.hooks
+    }
+
+    fn parent(&self) -> Option<&dyn Context> {
+        Some(self.parent)
+    }
 }
 
 impl<'a> FunctionCtx<'a> {
     pub fn new(ctx: &'a dyn Context, name: String, variables: HashMap<String, Value>) -> Self {
+        for value in variables.values() {
+            memory::stored(ctx, None, Some(value));
+        }
         Self {
             name,
             parent: ctx,
+            hooks: ctx.hooks(),
             returning: RefCell::new(None),
             variables: RefCell::new(variables),
         }
@@ -143,13 +242,18 @@ impl<'a> FunctionCtx<'a> {
 ///     = IDENTIFIER, TYPE_SIGNATURE, type
 ///     ;
 fn parse_parameter(p: &mut Parser) -> OptRes<Parameter> {
+    let start = p.curr().start;
     if let Some(name) = p.identifier()? {
         if !p.operator(Op::Colon)? {
             p.warn(WarnVar::MissingColon)?;
         }
         let data_type =
             parse_type(p)?.ok_or_else(|| p.error(ErroVar::FunctionParameterMissingType))?;
-        return Ok(Some(Parameter { name, data_type }));
+        return Ok(Some(Parameter {
+            name,
+            data_type,
+            span: p.span_from(start),
+        }));
     }
     Ok(None)
 }
@@ -164,7 +268,7 @@ fn parse_parameters(p: &mut Parser) -> Res<Vec<Parameter>> {
         while p.operator(Op::Split)? {
             if let Some(param) = parse_parameter(p)? {
                 if params.iter().any(|par: &Parameter| par.name == param.name) {
-                    return Err(p.error(ErroVar::DuplicateParameter));
+                    return Err(p.error(ErroVar::DuplicateParameter(param.name)));
                 }
                 params.push(param);
             } else {
@@ -179,6 +283,7 @@ fn parse_parameters(p: &mut Parser) -> Res<Vec<Parameter>> {
 ///     = KW_FN, OPEN_BRACKET, parameters, CLOSE_BRACKET, [RETURN_SIGNATURE, type], code_block
 ///     ;
 pub fn parse_function_def(p: &mut Parser) -> OptRes<FunctionDefinition> {
+    let start = p.curr().start;
     if !p.keyword(Kw::Fn)? {
         return Ok(None);
     }
@@ -197,12 +302,14 @@ pub fn parse_function_def(p: &mut Parser) -> OptRes<FunctionDefinition> {
     } else {
         DataType::None
     };
+    let span = p.span_from(start);
     let code_block = parse_code_block(p)?.ok_or_else(|| p.error(ErroVar::FunctionMissingBody))?;
     Ok(Some(FunctionDefinition {
         identifier,
         params,
         statements: code_block,
         data_type,
+        span,
     }))
 }
 
@@ -256,25 +363,19 @@ mod tests {
         );
         assert_eq!(
             result.unwrap().unwrap(),
-            FunctionDefinition {
-                identifier: "a".to_owned(),
-                params: vec![
-                    Parameter {
-                        name: "b".to_owned(),
-                        data_type: grammar::DataType::Integer
-                    },
-                    Parameter {
-                        name: "c".to_owned(),
-                        data_type: grammar::DataType::Integer
-                    }
+            FunctionDefinition::new(
+                "a".to_owned(),
+                vec![
+                    Parameter::new("b".to_owned(), grammar::DataType::Integer),
+                    Parameter::new("c".to_owned(), grammar::DataType::Integer)
                 ],
-                statements: vec![
+                vec![
                     FunctionCallExpr::new(IdentifierExpr::new("d".to_owned()).into(), vec![])
                         .into(),
                     Statement::Semicolon
                 ],
-                data_type: grammar::DataType::Integer
-            }
+                grammar::DataType::Integer
+            )
         );
 
         assert!(warnings.is_empty());
@@ -309,7 +410,7 @@ mod tests {
         assert_eq!(
             result.unwrap_err(),
             ParserError {
-                error: ParserErrorVariant::DuplicateParameter,
+                error: ParserErrorVariant::DuplicateParameter("b".to_owned()),
                 pos: Position::new(10, 6),
             }
         );
@@ -338,16 +439,16 @@ mod tests {
         );
         assert_eq!(
             result.unwrap().unwrap(),
-            FunctionDefinition {
-                identifier: "a".to_owned(),
-                params: vec![],
-                statements: vec![
+            FunctionDefinition::new(
+                "a".to_owned(),
+                vec![],
+                vec![
                     FunctionCallExpr::new(IdentifierExpr::new("c".to_owned()).into(), vec![])
                         .into(),
                     Statement::Semicolon
                 ],
-                data_type: grammar::DataType::Integer
-            }
+                grammar::DataType::Integer
+            )
         );
 
         assert!(warnings.is_empty());
@@ -375,19 +476,16 @@ mod tests {
         );
         assert_eq!(
             result.unwrap().unwrap(),
-            FunctionDefinition {
-                identifier: "a".to_owned(),
-                params: vec![Parameter {
-                    name: "b".to_owned(),
-                    data_type: grammar::DataType::Integer
-                }],
-                statements: vec![
+            FunctionDefinition::new(
+                "a".to_owned(),
+                vec![Parameter::new("b".to_owned(), grammar::DataType::Integer)],
+                vec![
                     FunctionCallExpr::new(IdentifierExpr::new("c".to_owned()).into(), vec![])
                         .into(),
                     Statement::Semicolon
                 ],
-                data_type: grammar::DataType::None
-            }
+                grammar::DataType::None
+            )
         );
 
         assert!(warnings.is_empty());
@@ -431,19 +529,16 @@ mod tests {
         );
         assert_eq!(
             result.unwrap().unwrap(),
-            FunctionDefinition {
-                identifier: "a".to_owned(),
-                params: vec![Parameter {
-                    name: "b".to_owned(),
-                    data_type: grammar::DataType::Integer
-                }],
-                statements: vec![
+            FunctionDefinition::new(
+                "a".to_owned(),
+                vec![Parameter::new("b".to_owned(), grammar::DataType::Integer)],
+                vec![
                     FunctionCallExpr::new(IdentifierExpr::new("c".to_owned()).into(), vec![])
                         .into(),
                     Statement::Semicolon
                 ],
-                data_type: grammar::DataType::None
-            }
+                grammar::DataType::None
+            )
         );
 
         assert_eq!(warnings.len(), 1);
@@ -478,19 +573,16 @@ mod tests {
         );
         assert_eq!(
             result.unwrap().unwrap(),
-            FunctionDefinition {
-                identifier: "a".to_owned(),
-                params: vec![Parameter {
-                    name: "b".to_owned(),
-                    data_type: grammar::DataType::Integer
-                }],
-                statements: vec![
+            FunctionDefinition::new(
+                "a".to_owned(),
+                vec![Parameter::new("b".to_owned(), grammar::DataType::Integer)],
+                vec![
                     FunctionCallExpr::new(IdentifierExpr::new("c".to_owned()).into(), vec![])
                         .into(),
                     Statement::Semicolon
                 ],
-                data_type: grammar::DataType::None
-            }
+                grammar::DataType::None
+            )
         );
 
         assert_eq!(warnings.len(), 1);
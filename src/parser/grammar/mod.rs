@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 pub mod expressions;
 pub mod function;
 pub mod program;
+pub mod repl;
+#[cfg(test)]
 mod test_utils;
 pub mod types;
 mod utility;
@@ -45,8 +47,24 @@ impl Display for Value {
     }
 }
 
+impl Value {
+    /// Shows the value the way it's written in source code, with strings in quotes
+    pub fn repr(&self) -> String {
+        match self {
+            Value::String(v) => format!("{v:?}"),
+            Value::Float(v) => format!("{v:?}"),
+            Value::List(v) => {
+                let items: Vec<String> = v.iter().map(Value::repr).collect();
+                format!("[{}]", items.join(", "))
+            }
+            Value::None => "none".to_owned(),
+            v => v.to_string(),
+        }
+    }
+}
+
 /// Possible data types
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum DataType {
     Integer,
     Float,
@@ -55,3 +73,46 @@ pub enum DataType {
     List,
     None,
 }
+
+impl From<&Value> for DataType {
+    fn from(v: &Value) -> Self {
+        match v {
+            Value::Int(_) => DataType::Integer,
+            Value::Float(_) => DataType::Float,
+            Value::Bool(_) => DataType::Bool,
+            Value::String(_) => DataType::String,
+            Value::List(_) => DataType::List,
+            Value::None => DataType::None,
+        }
+    }
+}
+
+impl Display for DataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataType::Integer => f.write_str("int"),
+            DataType::Float => f.write_str("float"),
+            DataType::Bool => f.write_str("bool"),
+            DataType::String => f.write_str("string"),
+            DataType::List => f.write_str("list"),
+            DataType::None => f.write_str("none"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repr() {
+        let list = Value::List(vec![
+            Value::Int(6),
+            Value::Float(6.0),
+            Value::Float(2.5),
+            Value::String("a".to_owned()),
+            Value::None,
+        ]);
+        assert_eq!(list.repr(), r#"[6, 6.0, 2.5, "a", none]"#);
+    }
+}
@@ -1,18 +1,30 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::{Debug, Display},
     io::stdout,
 };
 
 use ron::ser::PrettyConfig;
+use serde::Serializer;
 
+use crate::analysis::{
+    coverage::{Probe, Prober},
+    linter::{LintWarning, Linter, Lints},
+    resolver::{Resolver, ResolverError},
+    type_checker::{TypeChecker, TypeError},
+    visitor::Visitor,
+};
 use crate::interpreter::{
     callable::Callable,
     context::Context,
-    standard_library::{PrintOuts, StandardCtx},
+    hooks::Hooks,
+    memory, profiler,
+    standard_library::{PrintOuts, StandardCtx, BUILTINS},
     ExecutionError, ExecutionErrorVariant,
 };
 
+use crate::parser::{token::TokenType, ParserError};
+
 use super::{
     function::{parse_function_def, FunctionDefinition},
     utility::*,
@@ -24,9 +36,21 @@ use super::{
 pub struct Program {
     #[serde(skip_serializing)]
     pub std_ctx: StandardCtx,
+    #[serde(serialize_with = "serialize_sorted")]
     functions: HashMap<String, FunctionDefinition>,
 }
 
+/// Serializes functions in alphabetical order, so the output is stable between runs
+fn serialize_sorted<S: Serializer>(
+    functions: &HashMap<String, FunctionDefinition>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    functions
+        .iter()
+        .collect::<BTreeMap<_, _>>()
+        .serialize(serializer)
+}
+
 impl Debug for Program {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ProgramCtx")
@@ -49,24 +73,118 @@ impl Program {
         }
     }
 
-    pub fn run(&self) -> Result<(), ExecutionError> {
+    /// Runs the `main` function, returning its value, which is `none` or an exit status.
+    /// Script arguments are passed as a list of strings if `main` takes a parameter.
+    /// The hooks observe the run, their results can be read once it ends.
+    pub fn run(&self, args: Vec<String>, hooks: &Hooks) -> Result<Value, ExecutionError> {
         if let Some(main) = self.functions.get("main") {
-            if main.data_type != DataType::None {
-                return Err(ExecutionError::new(ExecutionErrorVariant::InvalidType));
+            if !matches!(main.data_type, DataType::None | DataType::Integer) {
+                return Err(ExecutionError::new(ExecutionErrorVariant::InvalidType {
+                    expected: vec![DataType::None, DataType::Integer],
+                    actual: main.data_type,
+                }));
             }
-            if !main.params.is_empty() {
-                return Err(ExecutionError::new(
-                    ExecutionErrorVariant::InvalidArgumentCount,
-                ));
-            }
-            main.call(self, vec![])?;
-            Ok(())
+            let args = match main.params.len() {
+                0 => vec![],
+                1 => vec![Value::List(args.into_iter().map(Value::String).collect())],
+                given => {
+                    return Err(ExecutionError::new(
+                        ExecutionErrorVariant::InvalidArgumentCount { expected: 1, given },
+                    ))
+                }
+            };
+            let ctx = RunCtx {
+                program: self,
+                hooks: (!hooks.is_empty()).then_some(hooks),
+            };
+            main.call(&ctx, args).map_err(ExecutionError::in_main)
         } else {
             Err(ExecutionError::new(
                 ExecutionErrorVariant::MissingMainFunction,
             ))
         }
     }
+
+    /// Adds a function definition, replacing the previous one with the same identifier
+    pub fn define_function(&mut self, function: FunctionDefinition) {
+        self.functions.insert(function.identifier.clone(), function);
+    }
+
+    /// Finds undeclared variables, missing functions and wrong argument counts
+    /// in all functions, without running the program
+    pub fn resolve(&self) -> Vec<ResolverError> {
+        let counts = self
+            .functions
+            .iter()
+            .map(|(id, function)| (id.clone(), function.params.len()))
+            .collect();
+        let mut resolver = Resolver::new(counts);
+        self.visit(&mut resolver);
+        resolver.finish()
+    }
+
+    /// Finds mismatches between declared and inferred types in all functions,
+    /// without running the program
+    pub fn check_types(&self) -> Vec<TypeError> {
+        let types = self
+            .functions
+            .iter()
+            .map(|(id, function)| {
+                let params = function.params.iter().map(|p| p.data_type).collect();
+                (id.clone(), (params, function.data_type))
+            })
+            .collect();
+        let mut checker = TypeChecker::new(types);
+        self.visit(&mut checker);
+        checker.finish()
+    }
+
+    /// Finds suspicious code reported by the enabled lints in all functions,
+    /// without running the program
+    pub fn lint(&self, lints: Lints) -> Vec<LintWarning> {
+        let mut linter = Linter::new(lints);
+        self.visit(&mut linter);
+        linter.finish()
+    }
+
+    /// Finds the places in all functions whose executions are counted when measuring coverage
+    pub fn probes(&self) -> Vec<Probe> {
+        let mut prober = Prober::default();
+        self.visit(&mut prober);
+        prober.finish()
+    }
+
+    /// Walks all functions with a static analysis pass
+    fn visit<V: Visitor>(&self, visitor: &mut V) {
+        for function in self.functions.values() {
+            visitor.visit_function(function);
+        }
+    }
+
+    /// Calls a user function or a builtin with the given context as the outermost one
+    fn call_in(
+        &self,
+        ctx: &dyn Context,
+        id: &str,
+        args: Vec<Value>,
+    ) -> Result<Value, ExecutionError> {
+        if let Some(func) = self.functions.get(id) {
+            func.call(ctx, args)
+        } else if BUILTINS.contains(&id) {
+            let result = profiler::call(ctx, id, true, || self.std_ctx.call_function(id, args));
+            if let Ok(value) = &result {
+                memory::allocated(ctx, value);
+            }
+            result
+        } else {
+            self.std_ctx.call_function(id, args)
+        }
+    }
+
+    /// Removes all function definitions
+    pub fn clear_functions(&mut self) {
+        self.functions.clear();
+    }
 }
 
 impl Context for Program {
@@ -83,16 +201,55 @@ impl Context for Program {
     }
 
     fn call_function(&self, id: &str, args: Vec<Value>) -> Result<Value, ExecutionError> {
-        if let Some(func) = self.functions.get(id) {
-            func.call(self, args)
-        } else {
-            self.std_ctx.call_function(id, args)
-        }
+        self.call_in(self, id, args)
+    }
+
+    fn name(&self) -> String {
+        unreachable!()
+    }
+
+    fn function_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.functions.keys().cloned().collect();
+        names.extend(self.std_ctx.function_names());
+        names
+    }
+}
+
+/// Outermost context of a run, which gives its functions access to the hooks
+struct RunCtx<'a> {
+    program: &'a Program,
+    /// `None` when there are no tools to run
+    hooks: Option<&'a Hooks>,
+}
+
+impl Context for RunCtx<'_> {
+    fn escalate_error(&self, r: Result<Value, ExecutionError>) -> Result<Value, ExecutionError> {
+        r
+    }
+
+    fn ret(&self, _value: Value) {
+        unreachable!()
+    }
+
+    fn is_ret(&self) -> bool {
+        unreachable!()
+    }
+
+    fn call_function(&self, id: &str, args: Vec<Value>) -> Result<Value, ExecutionError> {
+        self.program.call_in(self, id, args)
     }
 
     fn name(&self) -> String {
         unreachable!()
     }
+
+    fn function_names(&self) -> Vec<String> {
+        self.program.function_names()
+    }
+
+    fn hooks(&self) -> Option<&Hooks> {
+        self.hooks
+    }
 }
 
 impl Display for Program {
@@ -105,17 +262,48 @@ impl Display for Program {
 /// function_definitions
 ///     = {function_definition}
 ///     ;
+///
+/// Erroneous and repeated function definitions are skipped,
+/// and so are tokens which don't start a function definition.
 pub fn parse_program(p: &mut Parser) -> Res<Program> {
     let mut functions = HashMap::new();
-    while let Some(function) = parse_function_def(p)? {
-        if functions.contains_key(&function.identifier) {
-            return Err(p.error(ErroVar::FunctionAlredayExists));
+    loop {
+        match parse_function_def(p) {
+            Ok(Some(function)) if functions.contains_key(&function.identifier) => {
+                let err = p.error(ErroVar::FunctionAlredayExists(function.identifier));
+                p.recover(err)?;
+            }
+            Ok(Some(function)) => {
+                functions.insert(function.identifier.clone(), function);
+            }
+            Ok(None) if p.curr().token_type == TokenType::EndOfTokens => break,
+            Ok(None) => {
+                let err = ParserError {
+                    error: ErroVar::UnexpectedToken,
+                    pos: p.curr().start,
+                };
+                p.recover(err)?;
+                skip_to_function(p);
+            }
+            Err(err) => {
+                p.recover(err)?;
+                skip_to_function(p);
+            }
         }
-        functions.insert(function.identifier.clone(), function);
     }
     Ok(Program::new(functions))
 }
 
+/// Skips tokens until the next function definition or the end of tokens
+fn skip_to_function(p: &mut Parser) {
+    while !matches!(
+        p.curr().token_type,
+        TokenType::Keyword(Kw::Fn) | TokenType::EndOfTokens
+    ) {
+        p.pop();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -153,7 +341,7 @@ mod tests {
 
     #[test]
     fn double_defined() {
-        let (result, warnings) = partial_parse_non_opt(
+        let (result, errors, warnings) = partial_parse_recovering(
             vec![
                 dummy_token(TokenType::Keyword(Kw::Fn)),
                 dummy_token(TokenType::Identifier("a".to_owned())),
@@ -175,12 +363,13 @@ mod tests {
             "a".to_owned(),
             FunctionDefinition::new("a".to_owned(), vec![], vec![], DataType::None),
         );
+        assert_eq!(result.unwrap(), Program::new(functions));
         assert_eq!(
-            result.unwrap_err(),
-            ParserError {
-                error: ErroVar::FunctionAlredayExists,
+            errors,
+            vec![ParserError {
+                error: ErroVar::FunctionAlredayExists("a".to_owned()),
                 pos: Position::new(0, 0)
-            }
+            }]
         );
 
         assert!(warnings.is_empty());
@@ -197,16 +386,95 @@ mod tests {
 
     #[test]
     fn surface_error() {
-        let (result, warnings) = partial_parse_non_opt(
+        let (result, errors, warnings) = partial_parse_recovering(
             vec![token(TokenType::Keyword(Kw::Fn), (3, 4), (3, 6))],
             parse_program,
         );
+        assert_eq!(result.unwrap(), Program::new(HashMap::new()));
         assert_eq!(
-            result.unwrap_err(),
-            ParserError {
+            errors,
+            vec![ParserError {
                 error: ParserErrorVariant::FunctionMissingIdentifier,
                 pos: Position::new(3, 6)
-            }
+            }]
+        );
+
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn skip_broken_function() {
+        // fn a() 1; } fn c() {}
+        let (result, errors, warnings) = partial_parse_recovering(
+            vec![
+                dummy_token(TokenType::Keyword(Kw::Fn)),
+                dummy_token(TokenType::Identifier("a".to_owned())),
+                dummy_token(TokenType::Operator(Op::OpenRoundBracket)),
+                token(TokenType::Operator(Op::CloseRoundBracket), (1, 6), (1, 7)),
+                dummy_token(TokenType::Int(1)),
+                dummy_token(TokenType::Operator(Op::Semicolon)),
+                dummy_token(TokenType::Operator(Op::CloseCurlyBracket)),
+                dummy_token(TokenType::Keyword(Kw::Fn)),
+                dummy_token(TokenType::Identifier("c".to_owned())),
+                dummy_token(TokenType::Operator(Op::OpenRoundBracket)),
+                dummy_token(TokenType::Operator(Op::CloseRoundBracket)),
+                dummy_token(TokenType::Operator(Op::OpenCurlyBracket)),
+                dummy_token(TokenType::Operator(Op::CloseCurlyBracket)),
+            ],
+            parse_program,
+        );
+        let mut functions = HashMap::new();
+        functions.insert(
+            "c".to_owned(),
+            FunctionDefinition::new("c".to_owned(), vec![], vec![], DataType::None),
+        );
+        assert_eq!(result.unwrap(), Program::new(functions));
+        assert_eq!(
+            errors,
+            vec![ParserError {
+                error: ParserErrorVariant::FunctionMissingBody,
+                pos: Position::new(1, 7)
+            }]
+        );
+
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn skip_stray_token() {
+        // fn a() {} } fn c() {}
+        let (result, errors, warnings) = partial_parse_recovering(
+            vec![
+                dummy_token(TokenType::Keyword(Kw::Fn)),
+                dummy_token(TokenType::Identifier("a".to_owned())),
+                dummy_token(TokenType::Operator(Op::OpenRoundBracket)),
+                dummy_token(TokenType::Operator(Op::CloseRoundBracket)),
+                dummy_token(TokenType::Operator(Op::OpenCurlyBracket)),
+                token(TokenType::Operator(Op::CloseCurlyBracket), (1, 9), (1, 10)),
+                token(TokenType::Operator(Op::CloseCurlyBracket), (2, 1), (2, 2)),
+                dummy_token(TokenType::Keyword(Kw::Fn)),
+                dummy_token(TokenType::Identifier("c".to_owned())),
+                dummy_token(TokenType::Operator(Op::OpenRoundBracket)),
+                dummy_token(TokenType::Operator(Op::CloseRoundBracket)),
+                dummy_token(TokenType::Operator(Op::OpenCurlyBracket)),
+                dummy_token(TokenType::Operator(Op::CloseCurlyBracket)),
+            ],
+            parse_program,
+        );
+        let mut functions = HashMap::new();
+        for name in ["a", "c"] {
+            functions.insert(
+                name.to_owned(),
+                FunctionDefinition::new(name.to_owned(), vec![], vec![], DataType::None),
+            );
+        }
+        assert_eq!(result.unwrap(), Program::new(functions));
+        assert_eq!(
+            errors,
+            vec![ParserError {
+                error: ParserErrorVariant::UnexpectedToken,
+                pos: Position::new(2, 1)
+            }]
         );
 
         assert!(warnings.is_empty());
@@ -0,0 +1,113 @@
+use crate::parser::{token::TokenType, ParserError};
+
+use super::{
+    expressions::statement::{expect_semicolon, parse_statement, Statement},
+    function::{parse_function_def, FunctionDefinition},
+    utility::*,
+};
+
+/// A single element of interactive input
+#[derive(Debug, Serialize, PartialEq)]
+pub enum ReplItem {
+    Function(FunctionDefinition),
+    Statement(Statement),
+}
+
+/// repl_input
+///     = {function_definition | statement}
+///     ;
+pub fn parse_repl_input(p: &mut Parser) -> Res<Vec<ReplItem>> {
+    let mut items = vec![];
+    let mut unterminated = None;
+    loop {
+        if let Some(function) = parse_function_def(p)? {
+            items.push(ReplItem::Function(function));
+        } else if let Some(statement) = parse_statement(p)? {
+            expect_semicolon(p, &mut unterminated, &statement)?;
+            items.push(ReplItem::Statement(statement));
+        } else {
+            break;
+        }
+    }
+    if p.curr().token_type != TokenType::EndOfTokens {
+        return Err(ParserError {
+            error: ErroVar::UnexpectedToken,
+            pos: p.curr().start,
+        });
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::grammar::{
+        expressions::{identifier::IdentifierExpr, statement::Statement},
+        function::FunctionDefinition,
+        repl::{parse_repl_input, ReplItem},
+        DataType,
+    };
+
+    use super::super::test_utils::tests::*;
+
+    #[test]
+    fn empty() {
+        let (result, warnings) = partial_parse_non_opt(vec![], parse_repl_input);
+        assert_eq!(result.unwrap(), vec![]);
+
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn mixed() {
+        let (result, warnings) = partial_parse_non_opt(
+            vec![
+                dummy_token(TokenType::Keyword(Kw::Fn)),
+                dummy_token(TokenType::Identifier("a".to_owned())),
+                dummy_token(TokenType::Operator(Op::OpenRoundBracket)),
+                dummy_token(TokenType::Operator(Op::CloseRoundBracket)),
+                dummy_token(TokenType::Operator(Op::OpenCurlyBracket)),
+                dummy_token(TokenType::Operator(Op::CloseCurlyBracket)),
+                dummy_token(TokenType::Identifier("b".to_owned())),
+                dummy_token(TokenType::Operator(Op::Semicolon)),
+                dummy_token(TokenType::Int(5)),
+            ],
+            parse_repl_input,
+        );
+        assert_eq!(
+            result.unwrap(),
+            vec![
+                ReplItem::Function(FunctionDefinition::new(
+                    "a".to_owned(),
+                    vec![],
+                    vec![],
+                    DataType::None
+                )),
+                ReplItem::Statement(IdentifierExpr::new("b".to_owned()).into()),
+                ReplItem::Statement(Statement::Semicolon),
+                ReplItem::Statement(Value::Int(5).into()),
+            ]
+        );
+
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn unexpected_token() {
+        let (result, warnings) = partial_parse_non_opt(
+            vec![
+                dummy_token(TokenType::Int(5)),
+                token(TokenType::Operator(Op::CloseCurlyBracket), (1, 2), (1, 3)),
+            ],
+            parse_repl_input,
+        );
+        assert_eq!(
+            result.unwrap_err(),
+            ParserError {
+                error: ParserErrorVariant::UnexpectedToken,
+                pos: Position::new(1, 2),
+            }
+        );
+
+        assert!(warnings.is_empty());
+    }
+}
@@ -33,4 +33,14 @@ pub mod tests {
         let mut parser = Parser::new_with_defaults(scanner);
         (parse_func(&mut parser), parser.get_warnings())
     }
+
+    pub fn partial_parse_recovering<T>(
+        tokens: Vec<Token>,
+        parse_func: fn(p: &mut Parser) -> Result<T, ParserError>,
+    ) -> (Result<T, ParserError>, Vec<ParserError>, Vec<ParserWarning>) {
+        let scanner = DummyScanner::new(tokens);
+        let mut parser = Parser::new_with_defaults(scanner);
+        let result = parse_func(&mut parser);
+        (result, parser.take_errors(), parser.get_warnings())
+    }
 }
@@ -80,6 +80,25 @@ pub trait ParsingHelper: Scannable<Token> {
         }
         Ok(None)
     }
+
+    /// Skips tokens after an error until a synchronization point.
+    /// Stops after a `;`, or before a `}`, `fn` or the end of tokens.
+    fn synchronize(&mut self) {
+        loop {
+            match self.curr().token_type {
+                TokenType::Operator(Op::Semicolon) => {
+                    self.pop();
+                    break;
+                }
+                TokenType::Operator(Op::CloseCurlyBracket)
+                | TokenType::Keyword(Kw::Fn)
+                | TokenType::EndOfTokens => break,
+                _ => {
+                    self.pop();
+                }
+            }
+        }
+    }
 }
 
 impl<T: Scannable<Token>> ParsingHelper for T {}
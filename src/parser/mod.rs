@@ -1,14 +1,25 @@
 use std::error::Error;
 use std::fmt::Display;
+use std::io::Cursor;
 
-use crate::scannable::Scannable;
+use crate::{
+    lexer::{lexem::LexerWarning, Lexer},
+    locale,
+    scannable::Scannable,
+};
 
 use self::{
-    grammar::program::{parse_program, Program},
-    position::Position,
+    directives::{Directive, DirectiveCollector},
+    grammar::{
+        program::{parse_program, Program},
+        repl::{parse_repl_input, ReplItem},
+    },
+    position::{Position, Span},
     token::Token,
+    token_scanner::TokenScanner,
 };
 
+pub mod directives;
 pub mod grammar;
 pub mod keywords;
 pub mod operators;
@@ -42,8 +53,62 @@ pub enum ParserErrorVariant {
     VariableDeclarationMissingIdentifier,
     VariableDeclarationMissingExpression,
     TooManyWarnings,
-    DuplicateParameter,
-    FunctionAlredayExists,
+    DuplicateParameter(String),
+    FunctionAlredayExists(String),
+    UnexpectedToken,
+    ExpectedSemicolon,
+}
+
+impl Display for ParserErrorVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            ParserErrorVariant::DuplicateParameter(name)
+            | ParserErrorVariant::FunctionAlredayExists(name) => {
+                locale::message(self.code(), &[name])
+            }
+            _ => locale::message(self.code(), &[]),
+        };
+        f.write_str(&message)
+    }
+}
+
+impl ParserErrorVariant {
+    /// Stable code identifying the problem, explained by `--explain`
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParserErrorVariant::FunctionParameterMissingType => "P0001",
+            ParserErrorVariant::FunctionMissingIdentifier => "P0002",
+            ParserErrorVariant::FunctionMissingReturnType => "P0003",
+            ParserErrorVariant::FunctionMissingBody => "P0004",
+            ParserErrorVariant::IfMissingCondition => "P0005",
+            ParserErrorVariant::IfMissingTrueBranch => "P0006",
+            ParserErrorVariant::IfMissingFalseBranch => "P0007",
+            ParserErrorVariant::WhileLoopMissingCondition => "P0008",
+            ParserErrorVariant::WhileLoopMissingBody => "P0009",
+            ParserErrorVariant::ForLoopMissingVariable => "P0010",
+            ParserErrorVariant::ForLoopMissingProvider => "P0011",
+            ParserErrorVariant::ForLoopMissingBody => "P0012",
+            ParserErrorVariant::InvalidBracketExpression => "P0013",
+            ParserErrorVariant::ListRangeAccessIncomplete => "P0014",
+            ParserErrorVariant::ListAccessEmpty => "P0015",
+            ParserErrorVariant::UnaryOperatorMissingExpression => "P0016",
+            ParserErrorVariant::BinaryOperatorMissingRHS => "P0017",
+            ParserErrorVariant::AssignmentMissingExpression => "P0018",
+            ParserErrorVariant::VariableDeclarationMissingType => "P0019",
+            ParserErrorVariant::VariableDeclarationMissingIdentifier => "P0020",
+            ParserErrorVariant::VariableDeclarationMissingExpression => "P0021",
+            ParserErrorVariant::TooManyWarnings => "P0022",
+            ParserErrorVariant::DuplicateParameter(_) => "P0023",
+            ParserErrorVariant::FunctionAlredayExists(_) => "P0024",
+            ParserErrorVariant::UnexpectedToken => "P0025",
+            ParserErrorVariant::ExpectedSemicolon => "P0026",
+        }
+    }
+
+    /// Suggestion on how to fix the problem
+    pub fn hint(&self) -> &'static str {
+        locale::hint(self.code())
+    }
 }
 
 /// Critical errors remember the last position before they happened
@@ -55,10 +120,7 @@ pub struct ParserError {
 
 impl Display for ParserError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!(
-            "Parser error at {}: {:?}",
-            self.pos, self.error
-        ))
+        f.write_fmt(format_args!("Parser error at {}: {}", self.pos, self.error))
     }
 }
 
@@ -79,6 +141,35 @@ pub enum ParserWarningVariant {
     ExpectedParameter,
 }
 
+impl Display for ParserWarningVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&locale::message(self.code(), &[]))
+    }
+}
+
+impl ParserWarningVariant {
+    /// Stable code identifying the problem, explained by `--explain`
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParserWarningVariant::ExpectedExpression => "P0101",
+            ParserWarningVariant::MissingOpeningRoundBracket => "P0102",
+            ParserWarningVariant::MissingClosingRoundBracket => "P0103",
+            ParserWarningVariant::MissingClosingSquareBracket => "P0104",
+            ParserWarningVariant::MissingClosingCurlyBracket => "P0105",
+            ParserWarningVariant::MissingColon => "P0106",
+            ParserWarningVariant::VariableDeclarationMissingEqualsSign => "P0107",
+            ParserWarningVariant::VariableDeclarationMissingTypeSeparator => "P0108",
+            ParserWarningVariant::ForLoopMissingInKeyword => "P0109",
+            ParserWarningVariant::ExpectedParameter => "P0110",
+        }
+    }
+
+    /// Suggestion on how to fix the problem
+    pub fn hint(&self) -> &'static str {
+        locale::hint(self.code())
+    }
+}
+
 /// Elusive errors remember the position where they were supposed to be
 #[derive(Debug, PartialEq, Eq)]
 pub struct ParserWarning {
@@ -90,7 +181,7 @@ pub struct ParserWarning {
 impl Display for ParserWarning {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "Parser warning from {} to {}: {:?}",
+            "Parser warning from {} to {}: {}",
             self.start, self.stop, self.warning
         ))
     }
@@ -102,6 +193,7 @@ impl Error for ParserWarning {}
 ///
 pub struct Parser<'a> {
     warnings: Vec<ParserWarning>,
+    errors: Vec<ParserError>,
     pos: Position,
     scanner: Box<dyn Scannable<Token> + 'a>,
     max_warnings: i32,
@@ -112,6 +204,7 @@ impl<'a> Parser<'a> {
     pub fn new_with_defaults(token_scanner: impl Scannable<Token> + 'a) -> Self {
         Self {
             warnings: vec![],
+            errors: vec![],
             pos: Position { row: 1, col: 1 },
             scanner: Box::new(token_scanner),
             max_warnings: -1,
@@ -122,18 +215,37 @@ impl<'a> Parser<'a> {
     pub fn new(token_scanner: impl Scannable<Token> + 'a, max_warnings: i32) -> Self {
         Self {
             warnings: vec![],
+            errors: vec![],
             pos: Position { row: 1, col: 1 },
             scanner: Box::new(token_scanner),
             max_warnings,
         }
     }
 
-    /// Attempts to parse.
-    /// Returns either a `Program` or critical parsing error.
+    /// Attempts to parse, recovering from critical errors.
+    /// Returns a `Program`, which is partial if any errors were recovered from,
+    /// or an error which aborted parsing.
     pub fn parse(&mut self) -> Result<Program, ParserError> {
         parse_program(self)
     }
 
+    /// Attempts to parse a single interactive input.
+    /// Returns either function definitions and statements or critical parsing error.
+    /// Errors inside code blocks are recovered from.
+    pub fn parse_repl(&mut self) -> Result<Vec<ReplItem>, ParserError> {
+        parse_repl_input(self)
+    }
+
+    /// Span from the provided start to the end of the last consumed token.
+    pub fn span_from(&self, start: Position) -> Span {
+        Span::new(start, self.pos)
+    }
+
+    /// Returns all errors the parser recovered from, leaving none behind.
+    pub fn take_errors(&mut self) -> Vec<ParserError> {
+        std::mem::take(&mut self.errors)
+    }
+
     /// Consumes parser and returns all parser warnings.
     pub fn get_warnings(self) -> Vec<ParserWarning> {
         self.warnings
@@ -149,6 +261,10 @@ pub trait ErrorHandler {
     /// Creates a critical error which aborts parsing.
     #[must_use]
     fn error(&mut self, err: ParserErrorVariant) -> ParserError;
+
+    /// Records a critical error, so parsing can continue from a synchronization point.
+    /// Errors which can't be recovered from are returned back.
+    fn recover(&mut self, err: ParserError) -> Result<(), ParserError>;
 }
 
 impl<'a> ErrorHandler for Parser<'a> {
@@ -174,6 +290,67 @@ impl<'a> ErrorHandler for Parser<'a> {
             pos: self.pos,
         }
     }
+
+    fn recover(&mut self, err: ParserError) -> Result<(), ParserError> {
+        if err.error == ParserErrorVariant::TooManyWarnings {
+            return Err(err);
+        }
+        self.errors.push(err);
+        Ok(())
+    }
+}
+
+/// Outcome of lexing and parsing source code
+pub struct Parsed<T> {
+    /// Parsed code, possibly partial, or nothing if parsing was aborted
+    pub result: Option<T>,
+    /// Errors in the order they were encountered
+    pub errors: Vec<ParserError>,
+    pub parser_warnings: Vec<ParserWarning>,
+    pub lexer_warnings: Vec<LexerWarning>,
+    /// Directives found in comments, controlling which warnings are reported
+    pub directives: Vec<Directive>,
+}
+
+impl<T> Parsed<T> {
+    /// Returns the parsed code only if there were no errors
+    pub fn complete(self) -> Result<T, Vec<ParserError>> {
+        match self.result {
+            Some(result) if self.errors.is_empty() => Ok(result),
+            _ => Err(self.errors),
+        }
+    }
+}
+
+/// Runs a lexer and parser on source code, gathering all problems
+pub fn parse_source<T>(
+    source: &str,
+    parse: impl FnOnce(&mut Parser) -> Result<T, ParserError>,
+) -> Parsed<T> {
+    let mut lexer = Lexer::new_with_defaults(Cursor::new(source.to_owned().into_bytes()));
+    let mut directives = DirectiveCollector::default();
+
+    let (result, errors, parser_warnings) = {
+        let mut parser = Parser::new_with_defaults(TokenScanner::new(&mut lexer, &mut directives));
+        let result = parse(&mut parser);
+        let mut errors = parser.take_errors();
+        let result = match result {
+            Ok(result) => Some(result),
+            Err(err) => {
+                errors.push(err);
+                None
+            }
+        };
+        (result, errors, parser.get_warnings())
+    };
+
+    Parsed {
+        result,
+        errors,
+        parser_warnings,
+        lexer_warnings: lexer.get_warnings(),
+        directives: directives.finish(),
+    }
 }
 
 impl<'a> Scannable<Token> for Parser<'a> {
@@ -193,7 +370,10 @@ mod tests {
 
     use crate::parser::{
         grammar::{
-            expressions::{declaration::DeclarationExpr, statement::Statement},
+            expressions::{
+                declaration::DeclarationExpr, function_call::FunctionCallExpr,
+                identifier::IdentifierExpr, statement::Statement,
+            },
             function::FunctionDefinition,
             program::Program,
             DataType, Value,
@@ -208,7 +388,7 @@ mod tests {
 
     #[test]
     fn sole_parser() {
-        let (result, warnings) = parse(vec![
+        let (result, errors, warnings) = parse(vec![
             dummy_token(TokenType::Keyword(Keyword::Fn)),
             dummy_token(TokenType::Identifier("main".to_owned())),
             dummy_token(TokenType::Operator(Operator::OpenRoundBracket)),
@@ -224,6 +404,7 @@ mod tests {
             dummy_token(TokenType::Operator(Operator::CloseCurlyBracket)),
         ]);
 
+        assert!(errors.is_empty());
         assert!(warnings.is_empty());
 
         let mut functions = HashMap::new();
@@ -248,7 +429,7 @@ mod tests {
 
     #[test]
     fn sole_parser_warn() {
-        let (result, warnings) = parse(vec![
+        let (result, errors, warnings) = parse(vec![
             dummy_token(TokenType::Keyword(Keyword::Fn)),
             dummy_token(TokenType::Identifier("main".to_owned())),
             dummy_token(TokenType::Operator(Operator::OpenRoundBracket)),
@@ -263,6 +444,7 @@ mod tests {
             dummy_token(TokenType::Operator(Operator::CloseCurlyBracket)),
         ]);
 
+        assert!(errors.is_empty());
         assert_eq!(warnings.len(), 1);
         assert_eq!(
             warnings[0].warning,
@@ -291,7 +473,7 @@ mod tests {
 
     #[test]
     fn sole_parser_error() {
-        let (result, warnings) = parse(vec![
+        let (result, errors, warnings) = parse(vec![
             dummy_token(TokenType::Keyword(Keyword::Fn)),
             dummy_token(TokenType::Identifier("main".to_owned())),
             dummy_token(TokenType::Operator(Operator::OpenRoundBracket)),
@@ -309,11 +491,85 @@ mod tests {
         assert!(warnings.is_empty());
 
         assert_eq!(
-            ParserError {
+            vec![ParserError {
                 error: ParserErrorVariant::VariableDeclarationMissingType,
                 pos: Position::new(2, 6),
-            },
-            result.unwrap_err()
+            }],
+            errors
+        );
+
+        let mut functions = HashMap::new();
+        functions.insert(
+            "main".to_owned(),
+            FunctionDefinition::new("main".to_owned(), vec![], vec![], DataType::None),
+        );
+        assert_eq!(Program::new(functions), result.unwrap());
+    }
+
+    #[test]
+    fn many_parser_errors() {
+        // fn main() { let a = 1; let b: = 2; print(a); }
+        let (result, errors, warnings) = parse(vec![
+            dummy_token(TokenType::Keyword(Keyword::Fn)),
+            dummy_token(TokenType::Identifier("main".to_owned())),
+            dummy_token(TokenType::Operator(Operator::OpenRoundBracket)),
+            dummy_token(TokenType::Operator(Operator::CloseRoundBracket)),
+            dummy_token(TokenType::Operator(Operator::OpenCurlyBracket)),
+            dummy_token(TokenType::Keyword(Keyword::Let)),
+            token(TokenType::Identifier("a".to_owned()), (2, 9), (2, 10)),
+            dummy_token(TokenType::Operator(Operator::Equal)),
+            dummy_token(TokenType::Int(1)),
+            dummy_token(TokenType::Operator(Operator::Semicolon)),
+            dummy_token(TokenType::Keyword(Keyword::Let)),
+            dummy_token(TokenType::Identifier("b".to_owned())),
+            token(TokenType::Operator(Operator::Colon), (3, 10), (3, 11)),
+            dummy_token(TokenType::Operator(Operator::Equal)),
+            dummy_token(TokenType::Int(2)),
+            dummy_token(TokenType::Operator(Operator::Semicolon)),
+            dummy_token(TokenType::Identifier("print".to_owned())),
+            dummy_token(TokenType::Operator(Operator::OpenRoundBracket)),
+            dummy_token(TokenType::Identifier("a".to_owned())),
+            dummy_token(TokenType::Operator(Operator::CloseRoundBracket)),
+            dummy_token(TokenType::Operator(Operator::Semicolon)),
+            dummy_token(TokenType::Operator(Operator::CloseCurlyBracket)),
+        ]);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].warning,
+            ParserWarningVariant::VariableDeclarationMissingTypeSeparator
+        );
+        assert_eq!(
+            vec![
+                ParserError {
+                    error: ParserErrorVariant::VariableDeclarationMissingType,
+                    pos: Position::new(2, 10),
+                },
+                ParserError {
+                    error: ParserErrorVariant::VariableDeclarationMissingType,
+                    pos: Position::new(3, 11),
+                }
+            ],
+            errors
+        );
+
+        let mut functions = HashMap::new();
+        functions.insert(
+            "main".to_owned(),
+            FunctionDefinition::new(
+                "main".to_owned(),
+                vec![],
+                vec![
+                    FunctionCallExpr::new(
+                        IdentifierExpr::new("print".to_owned()).into(),
+                        vec![IdentifierExpr::new("a".to_owned()).into()],
+                    )
+                    .into(),
+                    Statement::Semicolon,
+                ],
+                DataType::None,
+            ),
         );
+        assert_eq!(Program::new(functions), result.unwrap());
     }
 }
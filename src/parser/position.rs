@@ -1,9 +1,10 @@
 use std::fmt::Display;
 
+use crate::locale;
 use serde::{Deserialize, Serialize};
 
 /// Position of a token
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Position {
     pub row: usize,
     pub col: usize,
@@ -18,6 +19,27 @@ impl Position {
 
 impl Display for Position {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("Ln {}, Col {}", self.row, self.col))
+        f.write_str(&locale::message("position", &[&self.row, &self.col]))
+    }
+}
+
+/// Part of the source code between two positions, the stop position is exclusive.
+///
+/// Nodes created outside of the parser have a default span at `Ln 0, Col 0`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Span {
+    pub start: Position,
+    pub stop: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, stop: Position) -> Self {
+        Self { start, stop }
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{} to {}", self.start, self.stop))
     }
 }
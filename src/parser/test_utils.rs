@@ -64,9 +64,16 @@ pub mod tests {
         }
     }
 
-    pub fn parse(tokens: Vec<Token>) -> (Result<Program, ParserError>, Vec<ParserWarning>) {
+    pub fn parse(
+        tokens: Vec<Token>,
+    ) -> (
+        Result<Program, ParserError>,
+        Vec<ParserError>,
+        Vec<ParserWarning>,
+    ) {
         let scanner = DummyScanner::new(tokens);
         let mut parser = Parser::new_with_defaults(scanner);
-        (parser.parse(), parser.get_warnings())
+        let result = parser.parse();
+        (result, parser.take_errors(), parser.get_warnings())
     }
 }
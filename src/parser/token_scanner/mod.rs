@@ -5,22 +5,27 @@ use crate::{
 
 use self::map::from_lexem;
 
-use super::token::{Token, TokenType};
+use super::{
+    directives::DirectiveCollector,
+    token::{Token, TokenType},
+};
 
 mod map;
 
 /// Converts `Lexem`s into `Token`s.
-/// Skips comments.
+/// Skips comments, passing them on to the directive collector along with the tokens.
 pub struct TokenScanner<'a> {
     lexer: &'a mut Lexer,
     curr: Token,
+    directives: &'a mut DirectiveCollector,
 }
 
 impl<'a> TokenScanner<'a> {
-    pub fn new(lexer: &'a mut Lexer) -> Self {
+    pub fn new(lexer: &'a mut Lexer, directives: &'a mut DirectiveCollector) -> Self {
         let mut scanner = Self {
             lexer,
             curr: Token::empty(),
+            directives,
         };
         scanner.pop();
         scanner
@@ -37,7 +42,9 @@ impl<'a> Scannable<Token> for TokenScanner<'a> {
         self.curr = loop {
             if let Some(lx) = opt_lx {
                 match lx.lexem_type {
-                    LexemType::Comment(_) => {
+                    LexemType::Comment(content) => {
+                        self.directives
+                            .comment(&content, lx.start.into(), lx.stop.into());
                         opt_lx = self.lexer.next();
                         continue;
                     }
@@ -66,6 +73,7 @@ impl<'a> Scannable<Token> for TokenScanner<'a> {
                 };
             }
         };
+        self.directives.token(&self.curr);
         self.curr.token_type == TokenType::EndOfTokens
     }
 }
@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use crate::locale;
+
 /// Position of a token
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Position {
@@ -28,7 +30,7 @@ impl Default for Position {
 
 impl Display for Position {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("Ln {}, Col {}", self.row, self.col))
+        f.write_str(&locale::message("position", &[&self.row, &self.col]))
     }
 }
 
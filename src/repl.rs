@@ -0,0 +1,465 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::{stdin, stdout, BufRead, Cursor, IsTerminal, Write},
+};
+
+use ron::ser::PrettyConfig;
+
+use crate::{
+    diagnostics::{self, render::eprint_all, Diagnostic},
+    interpreter::{context::Context, types::validate_types, ExecutionError, ExecutionErrorVariant},
+    lexer::{
+        lexem::{LexemType, LexerWarningVariant},
+        operators::Operator,
+        Lexer,
+    },
+    parser::{
+        grammar::{
+            expressions::statement::{alternate_statements, Statement},
+            program::Program,
+            repl::ReplItem,
+            DataType, Value,
+        },
+        parse_source,
+        position::Position,
+        Parsed,
+    },
+};
+
+/// Prompt displayed when waiting for new input
+const PROMPT: &str = ">> ";
+
+/// Prompt displayed when waiting for the rest of unfinished input
+const CONTINUATION_PROMPT: &str = ".. ";
+
+/// Meta-command manual
+static HELP: &str = "\
+:help           - Display this message
+:type <input>   - Evaluate input and display the type of the result
+:ast <input>    - Display the parsed input without evaluating it
+:reset          - Remove all variables and functions
+:quit           - Exit the shell";
+
+/// Commands that control the shell instead of being evaluated
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Help,
+    Type(String),
+    Ast(String),
+    Reset,
+    Quit,
+}
+
+impl Command {
+    /// Parses a meta-command, returns `None` if the input is not one
+    pub fn parse(input: &str) -> Option<Result<Command, String>> {
+        let input = input.trim().strip_prefix(':')?;
+        let (name, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let rest = rest.trim().to_owned();
+        Some(match name {
+            "help" | "h" => Ok(Command::Help),
+            "type" | "t" => Ok(Command::Type(rest)),
+            "ast" => Ok(Command::Ast(rest)),
+            "reset" => Ok(Command::Reset),
+            "quit" | "q" => Ok(Command::Quit),
+            _ => Err(format!("Unknown command `:{name}`, try `:help`.")),
+        })
+    }
+}
+
+/// Checks whether the input has unclosed brackets, strings or comments
+pub fn is_incomplete(input: &str) -> bool {
+    let mut lexer = Lexer::new_with_defaults(Cursor::new(input.to_owned().into_bytes()));
+    let mut depth: i64 = 0;
+    for lexem in lexer.all() {
+        if let LexemType::Operator(operator) = lexem.lexem_type {
+            match operator {
+                Operator::OpenCurlyBracket
+                | Operator::OpenRoundBracket
+                | Operator::OpenSquareBracket => depth += 1,
+                Operator::CloseCurlyBracket
+                | Operator::CloseRoundBracket
+                | Operator::CloseSquareBracket => depth -= 1,
+                _ => {}
+            }
+        }
+    }
+    depth > 0
+        || lexer.get_warnings().iter().any(|w| {
+            w.warning == LexerWarningVariant::StringNeverEnds
+                || w.warning == LexerWarningVariant::CommentNeverEnds
+        })
+}
+
+/// Runs a lexer and parser on interactive input
+pub fn parse(input: &str) -> Parsed<Vec<ReplItem>> {
+    parse_source(input, |parser| parser.parse_repl())
+}
+
+/// Interactive session.
+///
+/// The outermost context which keeps variables and functions between inputs.
+pub struct Session {
+    pub program: Program,
+    /// Input each function was defined in, errors inside the function point into it
+    sources: HashMap<String, String>,
+    variables: RefCell<HashMap<String, Value>>,
+    returning: RefCell<Option<Value>>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self {
+            program: Program::new(HashMap::new()),
+            sources: HashMap::new(),
+            variables: RefCell::new(HashMap::new()),
+            returning: RefCell::new(None),
+        }
+    }
+
+    /// Defines all functions, then evaluates all statements of the input they were parsed from.
+    /// Returns the value of the last unterminated expression or the returned value.
+    pub fn eval_items(
+        &mut self,
+        items: Vec<ReplItem>,
+        input: &str,
+    ) -> Result<Value, ExecutionError> {
+        let mut statements = vec![];
+        for item in items {
+            match item {
+                ReplItem::Function(function) => {
+                    self.sources
+                        .insert(function.identifier.clone(), input.to_owned());
+                    self.program.define_function(function)
+                }
+                ReplItem::Statement(statement) => statements.push(statement),
+            }
+        }
+        self.eval_statements(&statements)
+    }
+
+    /// Evaluates statements in the session context
+    pub fn eval_statements(&self, statements: &Vec<Statement>) -> Result<Value, ExecutionError> {
+        let result = alternate_statements(statements, self);
+        let returning = self.returning.replace(None);
+        result.map(|value| returning.unwrap_or(value))
+    }
+
+    /// Source text the span of an error points into.
+    /// Errors inside a function point into the input which defined it,
+    /// other errors point into the evaluated `input`.
+    pub fn error_source<'a>(&'a self, error: &ExecutionError, input: &'a str) -> Option<&'a str> {
+        match error.details.frames.first() {
+            Some(frame) => self.sources.get(&frame.function).map(String::as_str),
+            None => Some(input),
+        }
+    }
+
+    /// Removes all variables and functions
+    pub fn reset(&mut self) {
+        self.program.clear_functions();
+        self.sources.clear();
+        self.variables.borrow_mut().clear();
+        self.returning.replace(None);
+    }
+}
+
+impl Context for Session {
+    fn get_variable(&self, id: &str) -> Result<Value, ExecutionError> {
+        if let Some(v) = self.variables.borrow().get(id) {
+            Ok(v.clone())
+        } else {
+            Err(ExecutionError::new(
+                ExecutionErrorVariant::VariableDoesNotExist(id.to_owned()),
+            ))
+        }
+    }
+
+    fn set_variable(&self, id: &str, value: Value) -> Result<(), ExecutionError> {
+        if let Some(v) = self.variables.borrow_mut().get_mut(id) {
+            validate_types(v, &value)?;
+            *v = value;
+            Ok(())
+        } else {
+            Err(ExecutionError::new(
+                ExecutionErrorVariant::VariableDoesNotExist(id.to_owned()),
+            ))
+        }
+    }
+
+    /// Top-level variables can be redeclared
+    fn new_variable(&self, id: &str, value: Value) -> Result<(), ExecutionError> {
+        self.variables.borrow_mut().insert(id.to_owned(), value);
+        Ok(())
+    }
+
+    fn escalate_error(&self, r: Result<Value, ExecutionError>) -> Result<Value, ExecutionError> {
+        r
+    }
+
+    fn ret(&self, value: Value) {
+        *self.returning.borrow_mut() = Some(value);
+    }
+
+    fn is_ret(&self) -> bool {
+        self.returning.borrow().is_some()
+    }
+
+    fn call_function(&self, id: &str, args: Vec<Value>) -> Result<Value, ExecutionError> {
+        self.program.call_function(id, args)
+    }
+
+    fn name(&self) -> String {
+        "shell".to_owned()
+    }
+
+    fn variable_names(&self) -> Vec<String> {
+        self.variables.borrow().keys().cloned().collect()
+    }
+
+    fn function_names(&self) -> Vec<String> {
+        self.program.function_names()
+    }
+}
+
+/// Parses input and reports all problems, returns parsed items if there were no errors
+fn parse_and_report(input: &str) -> Option<Vec<ReplItem>> {
+    let parsed = parse(input);
+    eprint_all(&diagnostics::collect(&parsed), input, "shell");
+    parsed.complete().ok()
+}
+
+/// Evaluates input and displays the result
+fn eval_and_report(session: &mut Session, input: &str, display: impl Fn(Value)) {
+    if let Some(items) = parse_and_report(input) {
+        match session.eval_items(items, input) {
+            Ok(value) => display(value),
+            Err(e) => {
+                let mut diagnostic = Diagnostic::from(&e);
+                let source = session.error_source(&e, input);
+                if source.is_none() {
+                    // Only the message and the frames are shown without the source
+                    diagnostic.start = Position::default();
+                    diagnostic.stop = Position::default();
+                }
+                eprint_all(&[diagnostic], source.unwrap_or_default(), "shell")
+            }
+        }
+    }
+}
+
+/// Executes a meta-command, returns `false` if the shell should exit
+fn execute_command(session: &mut Session, command: Command) -> bool {
+    match command {
+        Command::Help => println!("{HELP}"),
+        Command::Type(input) => eval_and_report(session, &input, |value| {
+            println!("{}", DataType::from(&value))
+        }),
+        Command::Ast(input) => {
+            if let Some(items) = parse_and_report(&input) {
+                println!(
+                    "{}",
+                    ron::ser::to_string_pretty(&items, PrettyConfig::new()).unwrap()
+                );
+            }
+        }
+        Command::Reset => session.reset(),
+        Command::Quit => return false,
+    }
+    true
+}
+
+/// Runs the read-eval-print loop until the end of input
+pub fn run() {
+    let interactive = stdin().is_terminal();
+    let mut session = Session::new();
+    let mut buffer = String::new();
+    let mut lines = stdin().lock().lines();
+    loop {
+        if interactive {
+            let prompt = if buffer.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            };
+            print!("{prompt}");
+            stdout().flush().ok();
+        }
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => break,
+        };
+        if buffer.is_empty() {
+            if let Some(command) = Command::parse(&line) {
+                match command {
+                    Ok(command) => {
+                        if !execute_command(&mut session, command) {
+                            break;
+                        }
+                    }
+                    Err(msg) => eprintln!("{msg}"),
+                }
+                continue;
+            }
+        }
+        buffer.push_str(&line);
+        buffer.push('\n');
+        if is_incomplete(&buffer) {
+            continue;
+        }
+        let input = std::mem::take(&mut buffer);
+        eval_and_report(&mut session, &input, |value| {
+            if value != Value::None {
+                println!("{value}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        interpreter::{standard_library::PrintOuts, ExecutionError, ExecutionErrorVariant},
+        parser::{grammar::Value, ParserErrorVariant},
+    };
+
+    use super::{is_incomplete, parse, Command, Session};
+
+    fn eval(session: &mut Session, input: &str) -> Result<Value, ExecutionError> {
+        session.eval_items(parse(input).complete().unwrap(), input)
+    }
+
+    fn parser_error(input: &str) -> ParserErrorVariant {
+        parse(input).complete().unwrap_err().remove(0).error
+    }
+
+    #[test]
+    fn persistent_variables() {
+        let mut session = Session::new();
+        assert_eq!(eval(&mut session, "let a: int = 5;").unwrap(), Value::None);
+        assert_eq!(eval(&mut session, "a = a + 1;").unwrap(), Value::None);
+        assert_eq!(eval(&mut session, "a * 2").unwrap(), Value::Int(12));
+    }
+
+    #[test]
+    fn redeclared_variable() {
+        let mut session = Session::new();
+        eval(&mut session, "let a: int = 5;").unwrap();
+        eval(&mut session, "let a: string = \"b\";").unwrap();
+        assert_eq!(
+            eval(&mut session, "a").unwrap(),
+            Value::String("b".to_owned())
+        );
+    }
+
+    #[test]
+    fn persistent_functions() {
+        let mut session = Session::new();
+        eval(&mut session, "fn double(x: int) -> int {\n    x * 2\n}").unwrap();
+        assert_eq!(eval(&mut session, "double(4)").unwrap(), Value::Int(8));
+        eval(&mut session, "fn double(x: int) -> int { x }").unwrap();
+        assert_eq!(eval(&mut session, "double(4)").unwrap(), Value::Int(4));
+    }
+
+    #[test]
+    fn print_and_return() {
+        let mut session = Session::new();
+        session
+            .program
+            .std_ctx
+            .std_print
+            .0
+            .replace(PrintOuts::Vec(vec![]));
+        assert_eq!(
+            eval(&mut session, "print(3); return 7; 8").unwrap(),
+            Value::Int(7)
+        );
+        assert_eq!(eval(&mut session, "9").unwrap(), Value::Int(9));
+        if let PrintOuts::Vec(buffer) = session
+            .program
+            .std_ctx
+            .std_print
+            .0
+            .replace(PrintOuts::Vec(vec![]))
+        {
+            assert_eq!(&buffer, b"3\n")
+        }
+    }
+
+    #[test]
+    fn errors_keep_state() {
+        let mut session = Session::new();
+        eval(&mut session, "let a: int = 1;").unwrap();
+        assert_eq!(
+            eval(&mut session, "a = 1 / 0;").unwrap_err().variant,
+            ExecutionErrorVariant::DivisionByZero
+        );
+        assert_eq!(parser_error("a }"), ParserErrorVariant::UnexpectedToken);
+        assert_eq!(
+            parser_error("a = 2 fn f() {} a"),
+            ParserErrorVariant::ExpectedSemicolon
+        );
+        assert_eq!(eval(&mut session, "a").unwrap(), Value::Int(1));
+    }
+
+    #[test]
+    fn error_sources() {
+        let mut session = Session::new();
+        let definition = "fn f(x: int) -> int { x / 0 }";
+        eval(&mut session, definition).unwrap();
+        let error = eval(&mut session, "f(3)").unwrap_err();
+        assert_eq!(session.error_source(&error, "f(3)"), Some(definition));
+        let error = eval(&mut session, "1 / 0").unwrap_err();
+        assert_eq!(session.error_source(&error, "1 / 0"), Some("1 / 0"));
+        session.reset();
+        assert_eq!(session.error_source(&error, "1 / 0"), Some("1 / 0"));
+        let error = eval(&mut session, "fn g() { 1 / 0; } g()").unwrap_err();
+        session.reset();
+        assert_eq!(session.error_source(&error, ""), None);
+    }
+
+    #[test]
+    fn reset() {
+        let mut session = Session::new();
+        eval(&mut session, "let a: int = 1; fn f() {}").unwrap();
+        session.reset();
+        assert_eq!(
+            eval(&mut session, "a").unwrap_err().variant,
+            ExecutionErrorVariant::VariableDoesNotExist("a".to_owned())
+        );
+        assert_eq!(
+            eval(&mut session, "f()").unwrap_err().variant,
+            ExecutionErrorVariant::FunctionDoesNotExist("f".to_owned())
+        );
+    }
+
+    #[test]
+    fn incomplete() {
+        assert!(!is_incomplete("let a: int = 5;\n"));
+        assert!(is_incomplete("fn main() {\n"));
+        assert!(is_incomplete("print(\n"));
+        assert!(is_incomplete("[1, 2,\n"));
+        assert!(is_incomplete("\"abc\n"));
+        assert!(is_incomplete("/* abc\n"));
+        assert!(!is_incomplete("fn main() {\n}\n"));
+        assert!(!is_incomplete("}\n"));
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(Command::parse("1 + 2"), None);
+        assert_eq!(Command::parse(":help"), Some(Ok(Command::Help)));
+        assert_eq!(
+            Command::parse(":type 1 + 2"),
+            Some(Ok(Command::Type("1 + 2".to_owned())))
+        );
+        assert_eq!(
+            Command::parse("  :ast  a "),
+            Some(Ok(Command::Ast("a".to_owned())))
+        );
+        assert_eq!(Command::parse(":reset"), Some(Ok(Command::Reset)));
+        assert_eq!(Command::parse(":q"), Some(Ok(Command::Quit)));
+        assert!(Command::parse(":nope").unwrap().is_err());
+    }
+}